@@ -1,5 +1,7 @@
-use criterion::{criterion_group, criterion_main, Criterion, Throughput, BatchSize};
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput, BatchSize};
 use common::random::Random;
+use common::batch::{pseudohash_lanes, LANES};
+use common::util::{pseudohash_bytes, LuaRandom};
 
 fn bench_random_new_and_get_nodes(c: &mut Criterion) {
     let mut group = c.benchmark_group("random_new_get_node");
@@ -62,5 +64,48 @@ fn bench_get_node_only(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, bench_random_new_and_get_nodes, bench_random_new_only, bench_get_node_only);
+fn bench_lua_random(c: &mut Criterion) {
+    let mut group = c.benchmark_group("lua_random");
+    group.throughput(Throughput::Elements(1));
+
+    // Seeding runs ten warm-up draws, so it dominates a pseudorandom() call.
+    let mut seed = 0.0f64;
+    group.bench_function("new+random", |b| {
+        b.iter(|| {
+            seed = (seed + 0.618033988749895) % 1.0;
+            LuaRandom::new(seed).random()
+        });
+    });
+
+    let mut rng = LuaRandom::new(0.5);
+    group.bench_function("random_reuse", |b| {
+        b.iter(|| rng.random());
+    });
+
+    group.finish();
+}
+
+fn bench_pseudohash_lanes(c: &mut Criterion) {
+    let mut group = c.benchmark_group("pseudohash");
+    group.throughput(Throughput::Elements(LANES as u64));
+
+    let seeds: [[u8; 8]; LANES] = [*b"ABCDEFG0", *b"HELLO123", *b"ZZZZZZZZ", *b"1A2B3C4D"];
+    group.bench_function("scalar", |b| {
+        b.iter(|| seeds.map(|s| pseudohash_bytes([black_box(&s[..])])));
+    });
+    group.bench_function("lanes", |b| {
+        b.iter(|| pseudohash_lanes::<LANES>(std::array::from_fn(|l| black_box(&seeds[l][..]))));
+    });
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_random_new_and_get_nodes,
+    bench_random_new_only,
+    bench_get_node_only,
+    bench_lua_random,
+    bench_pseudohash_lanes
+);
 criterion_main!(benches);
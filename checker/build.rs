@@ -1,10 +1,17 @@
-use std::env;
-use std::path;
-
-use cuda_builder::CudaBuilder;
-
 fn main() {
     println!("cargo::rerun-if-changed=build.rs");
+
+    #[cfg(feature = "gpu")]
+    build_gpu_driver();
+}
+
+#[cfg(feature = "gpu")]
+fn build_gpu_driver() {
+    use std::env;
+    use std::path;
+
+    use cuda_builder::CudaBuilder;
+
     println!("cargo::rerun-if-changed=../gpu_driver");
 
     let out_path = path::PathBuf::from(env::var("OUT_DIR").unwrap());
@@ -14,4 +21,4 @@ fn main() {
         .copy_to(out_path.join("gpu_driver.ptx"))
         .build()
         .unwrap();
-}
\ No newline at end of file
+}
@@ -0,0 +1,252 @@
+use crate::seeds::{flag_number, flag_values, MAX_SEED_LEN, SeedSource};
+use common::filter::{Clause, Filter, SeedView};
+use common::game::RunConfig;
+use common::items::{Bosses, JokerTypes, Tags, Vouchers};
+use common::names::ItemName;
+use common::walk::length_indices;
+use rayon::prelude::*;
+use std::time::{Duration, Instant};
+
+// Rows shown for tables with many items (jokers); shorter tables are printed in full.
+const TABLE_ROWS: usize = 20;
+
+/// Occurrence counts for every item of one kind, in enum order.
+pub struct Tally<T: ItemName> {
+    items: Vec<T>,
+    counts: Vec<u64>,
+}
+
+impl<T: ItemName + PartialEq> Tally<T> {
+    fn new() -> Self {
+        let items: Vec<T> = T::iter().collect();
+        let counts = vec![0; items.len()];
+        Self { items, counts }
+    }
+
+    #[inline]
+    fn add(&mut self, item: T) {
+        if let Some(i) = self.items.iter().position(|&x| x == item) {
+            self.counts[i] += 1;
+        }
+    }
+
+    fn merge(&mut self, other: &Self) {
+        for (a, b) in self.counts.iter_mut().zip(&other.counts) {
+            *a += b;
+        }
+    }
+
+    fn print(&self, title: &str, seeds: u64, rows: usize) {
+        let mut rows_by_count: Vec<(T, u64)> =
+            self.items.iter().copied().zip(self.counts.iter().copied()).filter(|&(_, c)| c > 0).collect();
+        rows_by_count.sort_by(|a, b| b.1.cmp(&a.1));
+        println!("{title}:");
+        for (item, count) in rows_by_count.iter().take(rows) {
+            println!("  {:<24} {:>10} {:>8.3}%", item.name(), count, percent(*count, seeds));
+        }
+        if rows_by_count.len() > rows {
+            println!("  ... {} more", rows_by_count.len() - rows);
+        }
+    }
+}
+
+/// Frequency tables and clause hit counts over a seed sample.
+pub struct Analysis {
+    pub seeds: u64,
+    pub bosses: Tally<Bosses>,
+    pub vouchers: Tally<Vouchers>,
+    /// Seeds offering the tag on either blind of ante 1.
+    pub tags: Tally<Tags>,
+    /// Seeds whose first shop contains the joker in either slot.
+    pub shop_jokers: Tally<JokerTypes>,
+    /// Seeds matching each clause.
+    pub clause_hits: Vec<u64>,
+    /// Seeds matching both clause `i` and clause `j`, at `i * clauses + j`.
+    pub pair_hits: Vec<u64>,
+    /// Seeds matching the whole filter.
+    pub matches: u64,
+}
+
+impl Analysis {
+    fn new(clauses: usize) -> Self {
+        Self {
+            seeds: 0,
+            bosses: Tally::new(),
+            vouchers: Tally::new(),
+            tags: Tally::new(),
+            shop_jokers: Tally::new(),
+            clause_hits: vec![0; clauses],
+            pair_hits: vec![0; clauses * clauses],
+            matches: 0,
+        }
+    }
+
+    fn add(&mut self, seed: &str, filter: &Filter, config: RunConfig) {
+        let mut view = SeedView::with_config(seed, config);
+        self.seeds += 1;
+        if let Some(boss) = view.boss(1) {
+            self.bosses.add(boss);
+        }
+        self.vouchers.add(view.voucher(1));
+        let tags = view.tags(1);
+        self.tags.add(tags[0]);
+        if tags[1] != tags[0] {
+            self.tags.add(tags[1]);
+        }
+        // The second slot never repeats the first, whose key is held while on show.
+        for joker in view.shop_jokers(1).into_iter().flatten() {
+            self.shop_jokers.add(joker);
+        }
+
+        let n = filter.clauses.len();
+        let hits: Vec<bool> = filter.clauses.iter().map(|c| c.matches(&mut view)).collect();
+        for i in (0..n).filter(|&i| hits[i]) {
+            self.clause_hits[i] += 1;
+            for j in (0..n).filter(|&j| hits[j]) {
+                self.pair_hits[i * n + j] += 1;
+            }
+        }
+        if hits.iter().all(|&h| h) {
+            self.matches += 1;
+        }
+    }
+
+    fn merge(mut self, other: Self) -> Self {
+        self.seeds += other.seeds;
+        self.bosses.merge(&other.bosses);
+        self.vouchers.merge(&other.vouchers);
+        self.tags.merge(&other.tags);
+        self.shop_jokers.merge(&other.shop_jokers);
+        for (a, b) in self.clause_hits.iter_mut().zip(&other.clause_hits) {
+            *a += b;
+        }
+        for (a, b) in self.pair_hits.iter_mut().zip(&other.pair_hits) {
+            *a += b;
+        }
+        self.matches += other.matches;
+        self
+    }
+}
+
+/// A sampling run: which seeds to draw, the run they are played under, the
+/// filter to evaluate, and the size of the seed space the sample stands for:
+/// the seeds of the sampled length that a full search enumerates
+/// ([`length_indices`]).
+pub struct AnalysisConfig {
+    pub source: SeedSource,
+    pub run: RunConfig,
+    pub filter: Filter,
+    pub space: u64,
+}
+
+impl AnalysisConfig {
+    /// `--analyze N` samples N random seeds of `--length L` (default 8,
+    /// reproducible with `--rng-seed X`); each `--clause kind@ante=name`
+    /// adds a filter clause. Seeds are played under `run`. Returns `None`
+    /// without `--analyze`.
+    pub fn from_args(args: &[String], run: RunConfig) -> Result<Option<Self>, String> {
+        let Some(count) = flag_number(args, "--analyze")? else {
+            return Ok(None);
+        };
+        let length = flag_number(args, "--length")?.map_or(MAX_SEED_LEN, |l| l as usize);
+        let source = SeedSource::sample(count, length, flag_number(args, "--rng-seed")?)?;
+        let clauses = flag_values(args, "--clause")
+            .into_iter()
+            .map(|c| c.parse::<Clause>().map_err(|e| e.to_string()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let indices = length_indices(length);
+        Ok(Some(Self { source, run, filter: Filter::new(clauses), space: indices.end - indices.start }))
+    }
+
+    pub fn run(&self) -> (Analysis, Duration) {
+        let started = Instant::now();
+        let n = self.filter.clauses.len();
+        let analysis = (0..self.source.len())
+            .into_par_iter()
+            .fold(
+                || Analysis::new(n),
+                |mut a, i| {
+                    a.add(self.source.seed(i).as_str(), &self.filter, self.run);
+                    a
+                },
+            )
+            .reduce(|| Analysis::new(n), Analysis::merge);
+        (analysis, started.elapsed())
+    }
+
+    pub fn print(&self, analysis: &Analysis, elapsed: Duration) {
+        let seeds = analysis.seeds;
+        println!(
+            "analyzed {} in {:?} ({:.2} k seeds/s)",
+            self.source.describe(),
+            elapsed,
+            seeds as f64 / elapsed.as_secs_f64() / 1e3
+        );
+        analysis.bosses.print("ante 1 boss", seeds, usize::MAX);
+        analysis.vouchers.print("ante 1 voucher", seeds, usize::MAX);
+        analysis.tags.print("ante 1 tags (either blind)", seeds, usize::MAX);
+        analysis.shop_jokers.print("ante 1 first shop jokers (either slot)", seeds, TABLE_ROWS);
+
+        let clauses = &self.filter.clauses;
+        if clauses.is_empty() {
+            return;
+        }
+        let n = clauses.len();
+        println!("filter clauses:");
+        for (i, c) in clauses.iter().enumerate() {
+            println!("  [{i}] {:<32} {:>10} {:>8.3}%", c.to_string(), analysis.clause_hits[i], percent(analysis.clause_hits[i], seeds));
+        }
+        if n > 1 {
+            // Joint vs independent probabilities show which clauses are correlated.
+            println!("pairwise joint probability (independent estimate in parentheses):");
+            for i in 0..n {
+                for j in (i + 1)..n {
+                    let independent = percent(analysis.clause_hits[i], seeds) * percent(analysis.clause_hits[j], seeds) / 100.0;
+                    println!(
+                        "  [{i}]&[{j}] {:>10} {:>8.3}% ({:.3}%)",
+                        analysis.pair_hits[i * n + j],
+                        percent(analysis.pair_hits[i * n + j], seeds),
+                        independent
+                    );
+                }
+            }
+        }
+
+        let (lo, hi) = wilson_interval(analysis.matches, seeds);
+        let space = self.space as f64;
+        println!(
+            "all clauses: {} of {} ({:.5}%, 95% CI {:.5}%..{:.5}%)",
+            analysis.matches,
+            seeds,
+            percent(analysis.matches, seeds),
+            lo * 100.0,
+            hi * 100.0
+        );
+        println!(
+            "estimated matches in the {} enumerated seeds of this length: {:.0} (95% CI {:.0}..{:.0})",
+            self.space,
+            analysis.matches as f64 / seeds.max(1) as f64 * space,
+            lo * space,
+            hi * space
+        );
+    }
+}
+
+#[inline]
+fn percent(count: u64, total: u64) -> f64 {
+    100.0 * count as f64 / total.max(1) as f64
+}
+
+// 95% Wilson score interval; stays meaningful when a rare filter has few or no hits.
+fn wilson_interval(hits: u64, n: u64) -> (f64, f64) {
+    if n == 0 {
+        return (0.0, 1.0);
+    }
+    const Z: f64 = 1.96;
+    let n = n as f64;
+    let p = hits as f64 / n;
+    let denom = 1.0 + Z * Z / n;
+    let centre = (p + Z * Z / (2.0 * n)) / denom;
+    let margin = Z * (p * (1.0 - p) / n + Z * Z / (4.0 * n * n)).sqrt() / denom;
+    ((centre - margin).max(0.0), (centre + margin).min(1.0))
+}
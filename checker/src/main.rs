@@ -1,124 +1,142 @@
-use cust::prelude::*;
+use analysis::AnalysisConfig;
 use rayon::prelude::*;
-use common::random::Random;
+use common::batch::LANES;
+use common::challenge::Challenge;
+use common::game::RunConfig;
+use common::walk::{walk_hashed_seeds, SEED_SPACE};
+use common::score::{Criterion, Scorer};
+use scheduler::{Scheduler, Search};
+use seeds::{flag_number, flag_value, flag_values, SeedSource};
+use staged::StagedFilter;
 use std::error::Error;
-use std::thread;
-use std::time::Instant;
+use std::ops::Range;
 
-//
+mod analysis;
+mod ranking;
+mod scheduler;
+mod seeds;
+mod simd;
+mod staged;
 
+#[cfg(feature = "gpu")]
 static PTX: &str = include_str!(concat!(env!("OUT_DIR"), "/gpu_driver.ptx"));
 
-const CHARSET: [u8; 35] = *b"ABCDEFGHIJKLMNOPQRSTUVWXYZ123456789";
+// Seeds per rayon task when walking an enumerated range; each walk starts by
+// hashing a whole seed, so blocks should be much longer than that.
+const WALK_BLOCK: u64 = 1 << 16;
 
-#[inline(always)]
-fn encode_seed_bytes(mut n: u64, out: &mut [u8; 8]) -> (usize, usize) {
-    // Matches gpu_driver::encode_seed: base-35, no leading symbols
-    let mut end = out.len();
-    if n == 0 {
-        end -= 1;
-        out[end] = CHARSET[0];
-        return (end, 1);
-    }
-    while n > 0 && end > 0 {
-        let rem = (n % 35) as usize;
-        end -= 1;
-        out[end] = CHARSET[rem];
-        n /= 35;
-    }
-    let len = out.len() - end;
-    (end, len)
-}
+// Seeds listed at the end of a ranked run unless `--top` says otherwise.
+const DEFAULT_TOP_K: usize = 10;
 
 /// CPU-parallel mirror of gpu_driver::iterate_seeds using rayon.
 /// Returns the sum of per-item values (mirrors current GPU: rng.hashed_seed per seed).
-pub fn iterate_seeds_cpu(start: u64, total: u64) -> f64 {
-    (0..total)
+/// Enumerated ranges are walked in blocks that keep suffix hashes between
+/// neighbouring seeds; other sources are hashed [`LANES`] seeds at a time.
+pub fn iterate_seeds_cpu(source: &SeedSource, range: Range<u64>) -> f64 {
+    if let Some(start) = source.range_start() {
+        return (0..(range.end - range.start).div_ceil(WALK_BLOCK))
+            .into_par_iter()
+            .map(|block| {
+                let lo = range.start + block * WALK_BLOCK;
+                let mut sum = 0.0;
+                walk_hashed_seeds(start + lo..start + (lo + WALK_BLOCK).min(range.end), |_, _, hashed| sum += hashed);
+                sum
+            })
+            .sum();
+    }
+    let lanes = LANES as u64;
+    (0..(range.end - range.start).div_ceil(lanes))
         .into_par_iter()
-        .map(|i| {
-            let idx = start + i;
-            let mut seed_buf = [0u8; 8];
-            let (off, len) = encode_seed_bytes(idx, &mut seed_buf);
-            let rng = Random::new(&seed_buf[off..off + len]);
-            // Mirror current GPU behavior (summing hashed_seed). If the GPU switches back to get_node,
-            // change this to: rng.get_node((i as usize) % IDS_LEN)
-            rng.hashed_seed
+        .map(|block| {
+            let start = range.start + block * lanes;
+            simd::hashed_seed_sum(source, start..(start + lanes).min(range.end))
         })
         .sum()
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
-    // initialize CUDA, this will pick the first available device and will
-    // make a CUDA context from it.
-    // We don't need the context for anything but it must be kept alive.
-    let _ctx = cust::quick_init()?;
+    let args: Vec<String> = std::env::args().collect();
 
-    // Make the CUDA module, modules just house the GPU code for the kernels we created.
-    // they can be made from PTX code, cubins, or fatbins.
-    let module = Module::from_ptx(PTX, &[])?;
-
-    // make a CUDA stream to issue calls to. You can think of this as an OS thread but for dispatching
-    // GPU calls.
-    let stream = Stream::new(StreamFlags::NON_BLOCKING, None)?;
+    // `--challenge Jokerless` plays every seed as a run of that challenge,
+    // named as in the game or by its key (`c_jokerless_1`).
+    let challenge = flag_value(&args, "--challenge")
+        .map(|name| Challenge::from_name(name).ok_or_else(|| format!("unknown challenge '{name}'")))
+        .transpose()?;
+    if let Some(challenge) = challenge {
+        println!("challenge: {}", challenge.name);
+    }
+    let config = RunConfig { challenge };
 
-    // allocate the GPU memory needed to house our numbers and c
-    // ---------------------------------------------------------------------
-    // Launch the iterate_seeds kernel over the first 100,000,000 seeds
-    // ---------------------------------------------------------------------
-    let iterate = module.get_function("iterate_seeds")?;
-    // Choose a reasonable block size suggested by CUDA; 0 dynamic shared mem
-    let (_, iter_block_size) = iterate.suggested_launch_configuration(0, 0.into())?;
-    let iter_grid_size: u32 = 4096; // fixed grid to bound output size
-    let threads_per_grid = (iter_grid_size as usize) * (iter_block_size as usize);
+    // `--analyze N` samples seeds and prints frequency tables instead of searching.
+    if let Some(config) = AnalysisConfig::from_args(&args, config)? {
+        let (analysis, elapsed) = config.run();
+        config.print(&analysis, elapsed);
+        return Ok(());
+    }
 
-    thread::spawn(move || {
-        let start: u64 = 0;
-        let mut total: u64 = 1_000_000;
+    // Every CUDA device plus the rayon CPU pool pull chunks from the same range,
+    // so each seed is scanned exactly once. `--gpu-only` leaves the CPU idle.
+    // Builds without the `gpu` feature always run on the CPU pool alone.
+    let use_cpu = !args.iter().any(|a| a == "--gpu-only");
+    let scheduler = Scheduler::discover(use_cpu);
+    println!("workers: {}", scheduler.describe());
 
-        while total < 35u64.pow(8) {
-            let start_time = Instant::now();
-            iterate_seeds_cpu(start, total);
-            total *= 10;
-            println!(
-                "[CPU] iterate_seeds over {}: time={:?}",
-                total,
-                start_time.elapsed()
-            );
+    // `--clause kind@ante=name` keeps only seeds matching every clause;
+    // `--score clause[:weight]` ranks seeds instead of checksumming them.
+    // Either searches the given source or the whole 8-character seed space.
+    let clauses = flag_values(&args, "--clause")
+        .into_iter()
+        .map(|c| c.parse().map_err(|e: common::filter::ParseClauseError| e.to_string()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let filter = StagedFilter::new(clauses);
+    let criteria = flag_values(&args, "--score")
+        .into_iter()
+        .map(|c| c.parse::<Criterion>().map_err(|e| e.to_string()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let source = SeedSource::from_args(&args)?;
+    if !criteria.is_empty() || !filter.is_empty() {
+        let scorer = Scorer::new(criteria);
+        let source = source.unwrap_or(SeedSource::Range { start: 0, total: SEED_SPACE });
+        let mut search = if scorer.criteria.is_empty() {
+            let by: Vec<String> = filter.clauses().iter().map(|c| c.to_string()).collect();
+            println!("filtering {} by {}:", source.describe(), by.join(", "));
+            Search::checksum(&source)
+        } else {
+            let top_k = flag_number(&args, "--top")?.map_or(DEFAULT_TOP_K, |k| k as usize);
+            let by: Vec<String> = scorer.criteria.iter().map(|c| c.to_string()).collect();
+            println!("ranking {} by {}:", source.describe(), by.join(", "));
+            Search::ranked(&source, &scorer, top_k)
+        };
+        if !filter.is_empty() {
+            search = search.filtered(&filter);
+        }
+        search = search.under(config);
+        let summary = scheduler.run(&search)?;
+        summary.print();
+        if !filter.is_empty() {
+            filter.print_stats();
         }
-    });
+        return Ok(());
+    }
+
+    // An explicit source (seed list, pattern, sample or range) runs once;
+    // otherwise benchmark growing prefixes of the base-35 seed space.
+    if let Some(source) = source {
+        let summary = scheduler.run(&Search::checksum(&source))?;
+        println!("iterate_seeds over {}:", source.describe());
+        summary.print();
+        return Ok(());
+    }
+
     let start: u64 = 0;
     let mut total: u64 = 1_000_000;
 
-    while total < 35u64.pow(8) {
-        // Output buffer for per-thread checksums
-        let mut checksums = vec![0.0f64; threads_per_grid];
-        let checksums_buf = checksums.as_slice().as_dbuf()?;
-        let start_time = Instant::now();
-        unsafe {
-            launch!(
-                iterate<<<iter_grid_size, iter_block_size, 0, stream>>>(
-                    start,
-                    total,
-                    checksums_buf.as_device_ptr(),
-                )
-            )?;
-        }
-
-        stream.synchronize()?;
-        checksums_buf.copy_to(&mut checksums)?;
-        let checksum_sum: f64 = checksums.iter().copied().sum();
-        println!(
-            "[GPU] iterate_seeds over {}: grid={} block={} threads={} total={} checksum_sum={} time={:?}",
-            total,
-            iter_grid_size,
-            iter_block_size,
-            threads_per_grid,
-            total,
-            checksum_sum,
-            start_time.elapsed()
-        );
+    while total < SEED_SPACE {
+        let summary = scheduler.run(&Search::checksum(&SeedSource::Range { start, total }))?;
+        println!("iterate_seeds over {total}:");
+        summary.print();
         total *= 10;
     }
 
     Ok(())
-}
\ No newline at end of file
+}
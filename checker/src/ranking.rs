@@ -0,0 +1,129 @@
+use crate::seeds::{Seed, SeedSource};
+use crate::staged::StagedFilter;
+use common::filter::SeedView;
+use common::game::RunConfig;
+use common::score::Scorer;
+use rayon::prelude::*;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::ops::Range;
+
+// A scored seed and its index in the source, ordered worst to best: by score,
+// then the lower index wins.
+type Entry = (i32, Reverse<u64>, Seed);
+
+/// The `k` highest scoring seeds seen so far. Ties keep the seed that comes
+/// first in the source, as gpu_driver::score_seeds does.
+#[derive(Clone)]
+pub struct TopK {
+    k: usize,
+    // Min-heap, so the weakest kept seed is the one evicted.
+    heap: BinaryHeap<Reverse<Entry>>,
+    // Seeds ever pushed, kept or not.
+    offered: u64,
+}
+
+impl TopK {
+    pub fn new(k: usize) -> Self {
+        Self { k, heap: BinaryHeap::with_capacity(k + 1), offered: 0 }
+    }
+
+    /// Offer the seed at `index` of the source.
+    #[inline]
+    pub fn push(&mut self, score: i32, index: u64, seed: Seed) {
+        self.offered += 1;
+        self.insert((score, Reverse(index), seed));
+    }
+
+    #[inline]
+    fn insert(&mut self, entry: Entry) {
+        if self.k == 0 {
+            return;
+        }
+        if self.heap.len() < self.k {
+            self.heap.push(Reverse(entry));
+        } else if let Some(Reverse(worst)) = self.heap.peek()
+            && entry > *worst
+        {
+            self.heap.pop();
+            self.heap.push(Reverse(entry));
+        }
+    }
+
+    pub fn merge(&mut self, other: TopK) {
+        self.offered += other.offered;
+        for Reverse(entry) in other.heap {
+            self.insert(entry);
+        }
+    }
+
+    pub fn merged(mut self, other: TopK) -> TopK {
+        self.merge(other);
+        self
+    }
+
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    /// Number of seeds scored, including those that did not make the cut.
+    pub fn offered(&self) -> u64 {
+        self.offered
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Best seed so far.
+    pub fn best(&self) -> Option<(i32, Seed)> {
+        self.heap.iter().map(|Reverse(e)| *e).max().map(|(score, _, seed)| (score, seed))
+    }
+
+    /// Kept seeds, best first.
+    pub fn sorted(&self) -> Vec<(i32, Seed)> {
+        let mut all: Vec<Entry> = self.heap.iter().map(|Reverse(e)| *e).collect();
+        all.sort_by(|a, b| b.cmp(a));
+        all.into_iter().map(|(score, _, seed)| (score, seed)).collect()
+    }
+}
+
+/// CPU counterpart of gpu_driver::score_seeds: ranks the seeds of `range` that
+/// pass `filter` (all of them without one), each under `config`, and returns
+/// the chunk's top seeds plus the sum of all scores as a checksum.
+pub fn score_seeds_cpu(
+    source: &SeedSource,
+    range: Range<u64>,
+    filter: Option<&StagedFilter>,
+    scorer: &Scorer,
+    config: RunConfig,
+    k: usize,
+) -> (TopK, f64) {
+    let order = filter.map(StagedFilter::order).unwrap_or_default();
+    let (top, sum, stats) = range
+        .into_par_iter()
+        .fold(
+            || (TopK::new(k), 0.0, filter.map(StagedFilter::local_stats)),
+            |(mut top, sum, mut stats), i| {
+                let seed = source.seed(i);
+                let passed = match (filter, stats.as_mut()) {
+                    (Some(filter), Some(stats)) => filter.matches(&order, seed.as_str(), config, stats),
+                    _ => true,
+                };
+                if !passed {
+                    return (top, sum, stats);
+                }
+                let score = scorer.score(&mut SeedView::with_config(seed.as_str(), config));
+                top.push(score, i, seed);
+                (top, sum + score as f64, stats)
+            },
+        )
+        .reduce(
+            || (TopK::new(k), 0.0, filter.map(StagedFilter::local_stats)),
+            |(a, sa, ta), (b, sb, tb)| (a.merged(b), sa + sb, ta.zip(tb).map(|(x, y)| x.merged(y))),
+        );
+    if let (Some(filter), Some(stats)) = (filter, stats) {
+        filter.record(&stats);
+    }
+    (top, sum)
+}
@@ -0,0 +1,621 @@
+#[cfg(feature = "gpu")]
+use cust::error::CudaResult;
+#[cfg(feature = "gpu")]
+use cust::prelude::*;
+use crate::ranking::{score_seeds_cpu, TopK};
+use crate::seeds::{Seed, SeedSource};
+use crate::staged::{filter_seeds_cpu, StagedFilter};
+#[cfg(feature = "gpu")]
+use common::score::{GPU_BLOCK_TOP, GPU_MAX_BLOCK};
+#[cfg(feature = "gpu")]
+use common::filter::SeedView;
+use common::game::RunConfig;
+use common::score::Scorer;
+use std::error::Error;
+use std::ops::Range;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+type WorkerResult<T> = Result<T, Box<dyn Error + Send + Sync>>;
+
+// Smallest chunk ever handed out; keeps launch/dispatch overhead negligible.
+const MIN_CHUNK: u64 = 1 << 16;
+// Chunk size used before a worker has measured its own throughput.
+const INITIAL_CHUNK: u64 = 1 << 20;
+// Each chunk should keep a worker busy for roughly this long.
+const TARGET_CHUNK_TIME: Duration = Duration::from_millis(250);
+
+/// Shared pool of seed indices. Workers pull ranges from the front; ranges that
+/// a worker failed to finish are handed back and served first.
+pub struct ChunkQueue {
+    next: AtomicU64,
+    end: u64,
+    returned: Mutex<Vec<Range<u64>>>,
+}
+
+impl ChunkQueue {
+    pub fn new(start: u64, total: u64) -> Self {
+        Self { next: AtomicU64::new(start), end: start + total, returned: Mutex::new(Vec::new()) }
+    }
+
+    #[inline]
+    pub fn remaining(&self) -> u64 {
+        self.end.saturating_sub(self.next.load(Ordering::Relaxed))
+    }
+
+    /// Take up to `want` indices, or `None` once the queue is drained.
+    pub fn take(&self, want: u64) -> Option<Range<u64>> {
+        if let Some(r) = self.returned.lock().unwrap().pop() {
+            return Some(r);
+        }
+        let want = want.max(1);
+        let begin = self.next.fetch_add(want, Ordering::Relaxed);
+        if begin >= self.end {
+            return None;
+        }
+        Some(begin..(begin + want).min(self.end))
+    }
+
+    // Only GPU workers can fail part-way through a chunk.
+    #[cfg_attr(not(feature = "gpu"), allow(dead_code))]
+    pub fn give_back(&self, range: Range<u64>) {
+        self.returned.lock().unwrap().push(range);
+    }
+}
+
+/// What a run computes for every seed of `source`: the benchmark checksum, the
+/// seeds passing a filter, or with a scorer, a ranking that keeps each worker's
+/// `top_k` best seeds (of those passing the filter, if there is one). Filters
+/// and scorers see each seed's run as `config` sets it up.
+pub struct Search<'a> {
+    pub source: &'a SeedSource,
+    pub filter: Option<&'a StagedFilter>,
+    pub scorer: Option<&'a Scorer>,
+    pub top_k: usize,
+    pub config: RunConfig,
+    // Highest score announced so far, shared by all workers.
+    best_score: AtomicI64,
+}
+
+impl<'a> Search<'a> {
+    pub fn checksum(source: &'a SeedSource) -> Self {
+        Self {
+            source,
+            filter: None,
+            scorer: None,
+            top_k: 0,
+            config: RunConfig::default(),
+            best_score: AtomicI64::new(i64::MIN),
+        }
+    }
+
+    pub fn ranked(source: &'a SeedSource, scorer: &'a Scorer, top_k: usize) -> Self {
+        Self {
+            source,
+            filter: None,
+            scorer: Some(scorer),
+            top_k,
+            config: RunConfig::default(),
+            best_score: AtomicI64::new(i64::MIN),
+        }
+    }
+
+    /// Only consider seeds that pass `filter`.
+    pub fn filtered(mut self, filter: &'a StagedFilter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// Follow `config`'s release and challenge instead of a plain run.
+    pub fn under(mut self, config: RunConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    // Print a worker's chunk winner when it beats every seed announced before it.
+    fn announce_best(&self, worker: &str, top: &TopK) {
+        if let Some((score, seed)) = top.best()
+            && self.best_score.fetch_max(score as i64, Ordering::Relaxed) < score as i64
+        {
+            println!("  [{worker}] new best {seed} score {score}");
+        }
+    }
+}
+
+// What a worker produced for one chunk.
+struct ChunkResult {
+    checksum: f64,
+    top: TopK,
+    // Filter matches of unranked runs.
+    matches: Vec<Seed>,
+}
+
+impl ChunkResult {
+    fn checksum(checksum: f64) -> Self {
+        Self { checksum, top: TopK::new(0), matches: Vec::new() }
+    }
+}
+
+/// Per-worker totals, merged into a [`RunSummary`] once the queue is drained.
+pub struct WorkerReport {
+    pub name: String,
+    pub seeds: u64,
+    pub chunks: u64,
+    pub busy: Duration,
+    pub checksum: f64,
+    pub best: TopK,
+    /// Seeds that passed the filter.
+    pub matches: u64,
+}
+
+impl WorkerReport {
+    fn new(name: String, top_k: usize) -> Self {
+        Self { name, seeds: 0, chunks: 0, busy: Duration::ZERO, checksum: 0.0, best: TopK::new(top_k), matches: 0 }
+    }
+
+    /// Seeds per second while the worker was busy.
+    pub fn throughput(&self) -> f64 {
+        let secs = self.busy.as_secs_f64();
+        if secs > 0.0 { self.seeds as f64 / secs } else { 0.0 }
+    }
+
+    fn record(&mut self, search: &Search, len: u64, elapsed: Duration, result: ChunkResult) {
+        self.seeds += len;
+        self.chunks += 1;
+        self.busy += elapsed;
+        self.checksum += result.checksum;
+        if search.scorer.is_some() {
+            // Ranked runs score exactly the seeds that passed the filter.
+            self.matches += result.top.offered();
+            search.announce_best(&self.name, &result.top);
+            self.best.merge(result.top);
+        } else {
+            self.matches += result.matches.len() as u64;
+            for seed in &result.matches {
+                println!("  [{}] match {seed}", self.name);
+            }
+        }
+    }
+
+    // Size the next chunk so it takes about TARGET_CHUNK_TIME at the measured rate,
+    // but never more than a fair share of what is left so the tail stays balanced.
+    fn next_chunk(&self, queue: &ChunkQueue, workers: u64) -> u64 {
+        let by_rate = if self.chunks == 0 {
+            INITIAL_CHUNK
+        } else {
+            (self.throughput() * TARGET_CHUNK_TIME.as_secs_f64()) as u64
+        };
+        let fair_share = queue.remaining() / (2 * workers.max(1));
+        by_rate.min(fair_share).max(MIN_CHUNK)
+    }
+}
+
+pub struct RunSummary {
+    pub workers: Vec<WorkerReport>,
+    pub elapsed: Duration,
+    pub filtered: bool,
+}
+
+impl RunSummary {
+    pub fn checksum(&self) -> f64 {
+        self.workers.iter().map(|w| w.checksum).sum()
+    }
+
+    pub fn seeds(&self) -> u64 {
+        self.workers.iter().map(|w| w.seeds).sum()
+    }
+
+    pub fn matches(&self) -> u64 {
+        self.workers.iter().map(|w| w.matches).sum()
+    }
+
+    /// Best seeds across all workers.
+    pub fn best(&self) -> TopK {
+        let k = self.workers.iter().map(|w| w.best.k()).max().unwrap_or(0);
+        self.workers.iter().fold(TopK::new(k), |top, w| top.merged(w.best.clone()))
+    }
+
+    pub fn print(&self) {
+        let total = self.seeds().max(1) as f64;
+        for w in &self.workers {
+            println!(
+                "  [{}] seeds={} chunks={} share={:.1}% rate={:.2} M/s",
+                w.name,
+                w.seeds,
+                w.chunks,
+                100.0 * w.seeds as f64 / total,
+                w.throughput() / 1e6
+            );
+        }
+        println!(
+            "  total seeds={} checksum_sum={} time={:?} rate={:.2} M/s",
+            self.seeds(),
+            self.checksum(),
+            self.elapsed,
+            self.seeds() as f64 / self.elapsed.as_secs_f64() / 1e6
+        );
+        if self.filtered {
+            println!("  matches={} ({:.6}%)", self.matches(), 100.0 * self.matches() as f64 / total);
+        }
+        let best = self.best();
+        if !best.is_empty() {
+            println!("  best seeds:");
+            for (rank, (score, seed)) in best.sorted().iter().enumerate() {
+                println!("  {:>4}. {seed:<8} {score}", rank + 1);
+            }
+        }
+    }
+}
+
+/// Splits a seed source between every CUDA device and the rayon CPU pool.
+pub struct Scheduler {
+    devices: Vec<(u32, String)>,
+    use_cpu: bool,
+}
+
+impl Scheduler {
+    /// Discover all CUDA devices. If the driver cannot be initialized, or the
+    /// `gpu` feature is disabled, the scheduler falls back to the CPU pool alone.
+    pub fn discover(use_cpu: bool) -> Self {
+        let devices = match Self::list_devices() {
+            Ok(devices) => devices,
+            Err(e) => {
+                eprintln!("CUDA unavailable ({e}); running on CPU only");
+                Vec::new()
+            }
+        };
+        // Without a GPU the CPU pool is the only worker left.
+        let use_cpu = use_cpu || devices.is_empty();
+        Self { devices, use_cpu }
+    }
+
+    #[cfg(feature = "gpu")]
+    fn list_devices() -> WorkerResult<Vec<(u32, String)>> {
+        cust::init(CudaFlags::empty())?;
+        let mut devices = Vec::new();
+        for ordinal in 0..Device::num_devices()? {
+            let device = Device::get_device(ordinal)?;
+            devices.push((ordinal, device.name()?));
+        }
+        Ok(devices)
+    }
+
+    #[cfg(not(feature = "gpu"))]
+    fn list_devices() -> WorkerResult<Vec<(u32, String)>> {
+        Err("built without the `gpu` feature".into())
+    }
+
+    pub fn describe(&self) -> String {
+        let mut parts: Vec<String> = self.devices.iter().map(|(i, name)| format!("gpu{i} ({name})")).collect();
+        if self.use_cpu {
+            parts.push(format!("cpu ({} threads)", rayon::current_num_threads()));
+        }
+        parts.join(", ")
+    }
+
+    pub fn run(&self, search: &Search) -> Result<RunSummary, Box<dyn Error>> {
+        let queue = ChunkQueue::new(0, search.source.len());
+        let workers = (self.devices.len() + self.use_cpu as usize) as u64;
+        let started = Instant::now();
+
+        let results: Vec<WorkerResult<WorkerReport>> = thread::scope(|s| {
+            let queue = &queue;
+            let mut handles = Vec::new();
+            for (ordinal, _) in &self.devices {
+                let ordinal = *ordinal;
+                handles.push(s.spawn(move || gpu_worker(ordinal, search, queue, workers)));
+            }
+            if self.use_cpu {
+                handles.push(s.spawn(move || cpu_worker(search, queue, workers)));
+            }
+            handles.into_iter().map(|h| h.join().expect("worker thread panicked")).collect()
+        });
+
+        let mut reports = Vec::new();
+        for result in results {
+            match result {
+                Ok(report) => reports.push(report),
+                Err(e) => eprintln!("worker failed: {e}"),
+            }
+        }
+        if queue.take(1).is_some() {
+            return Err("all workers stopped before the range was finished".into());
+        }
+        Ok(RunSummary { workers: reports, elapsed: started.elapsed(), filtered: search.filter.is_some() })
+    }
+}
+
+#[cfg(not(feature = "gpu"))]
+fn gpu_worker(_ordinal: u32, _search: &Search, _queue: &ChunkQueue, _workers: u64) -> WorkerResult<WorkerReport> {
+    unreachable!("no CUDA devices are discovered without the `gpu` feature")
+}
+
+fn cpu_worker(search: &Search, queue: &ChunkQueue, workers: u64) -> WorkerResult<WorkerReport> {
+    let mut report = WorkerReport::new(String::from("cpu"), search.top_k);
+    while let Some(range) = queue.take(report.next_chunk(queue, workers)) {
+        let len = range.end - range.start;
+        let t = Instant::now();
+        let result = match (search.scorer, search.filter) {
+            (Some(scorer), filter) => {
+                let (top, checksum) = score_seeds_cpu(search.source, range, filter, scorer, search.config, search.top_k);
+                ChunkResult { checksum, top, matches: Vec::new() }
+            }
+            (None, Some(filter)) => {
+                let matches = filter_seeds_cpu(search.source, range, filter, search.config);
+                ChunkResult { checksum: matches.len() as f64, top: TopK::new(0), matches }
+            }
+            (None, None) => ChunkResult::checksum(crate::iterate_seeds_cpu(search.source, range)),
+        };
+        report.record(search, len, t.elapsed(), result);
+    }
+    Ok(report)
+}
+
+// Per-device state: the loaded module, a stream, and output buffers reused across chunks.
+#[cfg(feature = "gpu")]
+struct GpuLaunch {
+    module: Module,
+    stream: Stream,
+    grid_size: u32,
+    // Each kernel's own suggested block size: the kernels that play seeds
+    // through SeedView need far more registers than the hashing ones.
+    iterate_block: u32,
+    list_block: u32,
+    score_block: u32,
+    filter_block: u32,
+    checksums: Vec<f64>,
+    checksums_buf: DeviceBuffer<f64>,
+    // Ranked runs: encoded criteria, and each block's best seeds per launch.
+    criteria_buf: DeviceBuffer<u32>,
+    top_scores: Vec<i32>,
+    top_scores_buf: DeviceBuffer<i32>,
+    top_indices: Vec<u64>,
+    top_indices_buf: DeviceBuffer<u64>,
+    // Filtered runs: the survivor count of the current stage, and two index
+    // buffers that stages alternate between reading and writing.
+    survivors_buf: DeviceBuffer<u64>,
+    stage_bufs: [DeviceBuffer<u64>; 2],
+    // Declared last so it is dropped after everything allocated in it.
+    _ctx: Context,
+}
+
+#[cfg(feature = "gpu")]
+impl GpuLaunch {
+    fn new(ordinal: u32, search: &Search) -> CudaResult<Self> {
+        // Context::new retains the device's primary context and makes it current on this thread.
+        let ctx = Context::new(Device::get_device(ordinal)?)?;
+        let module = Module::from_ptx(crate::PTX, &[])?;
+        let stream = Stream::new(StreamFlags::NON_BLOCKING, None)?;
+        let block = |name: &str| -> CudaResult<u32> {
+            Ok(module.get_function(name)?.suggested_launch_configuration(0, 0.into())?.1)
+        };
+        let (iterate_block, list_block) = (block("iterate_seeds")?, block("iterate_seed_list")?);
+        // score_seeds' shared scratch holds GPU_MAX_BLOCK threads.
+        let score_block = block("score_seeds")?.min(GPU_MAX_BLOCK as u32);
+        let filter_block = block("filter_stage")?;
+        let grid_size: u32 = 4096;
+        let threads = grid_size as usize * iterate_block.max(list_block).max(score_block) as usize;
+        let checksums = vec![0.0f64; threads];
+        let checksums_buf = checksums.as_slice().as_dbuf()?;
+        let criteria = search.scorer.map(Scorer::to_words).unwrap_or_default();
+        let criteria_buf = criteria.as_slice().as_dbuf()?;
+        let slots = if search.scorer.is_some() { grid_size as usize * GPU_BLOCK_TOP } else { 0 };
+        let top_scores = vec![i32::MIN; slots];
+        let top_scores_buf = top_scores.as_slice().as_dbuf()?;
+        let top_indices = vec![0u64; slots];
+        let top_indices_buf = top_indices.as_slice().as_dbuf()?;
+        let survivors_buf = [0u64].as_dbuf()?;
+        // Sized for the first chunk; `filter` grows them if a chunk outgrows it.
+        let stage_len = if search.filter.is_some() { INITIAL_CHUNK as usize } else { 0 };
+        let stage_bufs = unsafe { [DeviceBuffer::uninitialized(stage_len)?, DeviceBuffer::uninitialized(stage_len)?] };
+        Ok(Self {
+            module,
+            stream,
+            grid_size,
+            iterate_block,
+            list_block,
+            score_block,
+            filter_block,
+            checksums,
+            checksums_buf,
+            criteria_buf,
+            top_scores,
+            top_scores_buf,
+            top_indices,
+            top_indices_buf,
+            survivors_buf,
+            stage_bufs,
+            _ctx: ctx,
+        })
+    }
+
+    fn chunk(&mut self, search: &Search, range: Range<u64>) -> CudaResult<ChunkResult> {
+        let source = search.source;
+        let len = range.end - range.start;
+        // Contiguous ranges are enumerated on the device; everything else is
+        // materialized on the host and uploaded as packed 8-byte seeds.
+        let start = source.range_start().map_or(0, |s| s + range.start);
+        let packed = match source.range_start() {
+            Some(_) => None,
+            None => {
+                let bytes: Vec<u8> = range.clone().flat_map(|i| source.seed(i).padded()).collect();
+                Some(bytes.as_slice().as_dbuf()?)
+            }
+        };
+        let seeds = packed.as_ref().map_or(DevicePointer::null(), |b| b.as_device_ptr());
+
+        if let Some(filter) = search.filter {
+            let survivors = self.filter(filter, search.config, start, seeds, len)?;
+            // Survivors are rare enough that ranking them on the host is cheap.
+            return Ok(match search.scorer {
+                Some(scorer) => {
+                    let mut top = TopK::new(search.top_k);
+                    let mut checksum = 0.0;
+                    for i in survivors {
+                        let seed = source.seed(range.start + i);
+                        let score = scorer.score(&mut SeedView::with_config(seed.as_str(), search.config));
+                        checksum += score as f64;
+                        top.push(score, range.start + i, seed);
+                    }
+                    ChunkResult { checksum, top, matches: Vec::new() }
+                }
+                None => {
+                    let matches: Vec<Seed> = survivors.iter().map(|&i| source.seed(range.start + i)).collect();
+                    ChunkResult { checksum: matches.len() as f64, top: TopK::new(0), matches }
+                }
+            });
+        }
+
+        let grid_size = self.grid_size;
+        let stream = &self.stream;
+        if search.scorer.is_none() {
+            let block_size = match &packed {
+                None => self.iterate_block,
+                Some(_) => self.list_block,
+            };
+            unsafe {
+                match &packed {
+                    None => {
+                        let iterate = self.module.get_function("iterate_seeds")?;
+                        launch!(iterate<<<grid_size, block_size, 0, stream>>>(start, len, self.checksums_buf.as_device_ptr()))?;
+                    }
+                    Some(_) => {
+                        let iterate_list = self.module.get_function("iterate_seed_list")?;
+                        launch!(iterate_list<<<grid_size, block_size, 0, stream>>>(seeds, len, self.checksums_buf.as_device_ptr()))?;
+                    }
+                }
+            }
+            stream.synchronize()?;
+            self.checksums_buf.copy_to(&mut self.checksums)?;
+            return Ok(ChunkResult::checksum(self.launched_checksum(block_size)));
+        }
+
+        let score = self.module.get_function("score_seeds")?;
+        let score_block = self.score_block;
+        let config = search.config.to_word();
+        unsafe {
+            launch!(
+                score<<<grid_size, score_block, 0, stream>>>(
+                    start,
+                    seeds,
+                    len,
+                    self.criteria_buf.as_device_ptr(),
+                    self.criteria_buf.len() as u32,
+                    config,
+                    self.top_scores_buf.as_device_ptr(),
+                    self.top_indices_buf.as_device_ptr(),
+                    self.checksums_buf.as_device_ptr(),
+                )
+            )?;
+        }
+        stream.synchronize()?;
+        self.top_scores_buf.copy_to(&mut self.top_scores)?;
+        self.top_indices_buf.copy_to(&mut self.top_indices)?;
+        self.checksums_buf.copy_to(&mut self.checksums)?;
+
+        let mut top = TopK::new(search.top_k);
+        for (&score, &i) in self.top_scores.iter().zip(&self.top_indices) {
+            // Blocks that saw fewer than GPU_BLOCK_TOP seeds leave i32::MIN slots.
+            if score != i32::MIN && i < len {
+                top.push(score, range.start + i, source.seed(range.start + i));
+            }
+        }
+        Ok(ChunkResult { checksum: self.launched_checksum(score_block), top, matches: Vec::new() })
+    }
+
+    // Sum of the per-thread checksums a launch of `block_size` threads per block wrote.
+    fn launched_checksum(&self, block_size: u32) -> f64 {
+        self.checksums[..self.grid_size as usize * block_size as usize].iter().sum()
+    }
+
+    // Multi-pass filter: one launch per clause in the filter's current order.
+    // Each stage runs only on the previous stage's survivors, compacted into a
+    // dense index buffer, so threads in a warp all evaluate live candidates
+    // instead of idling behind rejected ones. Returns sorted chunk-relative
+    // indices of the seeds passing every clause.
+    fn filter(
+        &mut self,
+        filter: &StagedFilter,
+        config: RunConfig,
+        start: u64,
+        seeds: DevicePointer<u8>,
+        len: u64,
+    ) -> CudaResult<Vec<u64>> {
+        let stage = self.module.get_function("filter_stage")?;
+        let config = config.to_word();
+        let block_size = self.filter_block;
+        if self.stage_bufs[0].len() < len as usize {
+            self.stage_bufs = unsafe { [DeviceBuffer::uninitialized(len as usize)?, DeviceBuffer::uninitialized(len as usize)?] };
+        }
+        let stream = &self.stream;
+        let mut stats = filter.local_stats();
+        // Index into stage_bufs of the last stage's survivors; None before the first stage.
+        let mut candidates: Option<usize> = None;
+        let mut count = len;
+
+        for i in filter.order() {
+            if count == 0 {
+                break;
+            }
+            let out = candidates.map_or(0, |c| 1 - c);
+            self.survivors_buf.copy_from(&[0u64])?;
+            let in_ptr = candidates.map_or(DevicePointer::null(), |c| self.stage_bufs[c].as_device_ptr());
+            let grid_size = (count.div_ceil(block_size as u64)).min(self.grid_size as u64) as u32;
+            let word = filter.clauses()[i].to_word();
+            unsafe {
+                launch!(
+                    stage<<<grid_size, block_size, 0, stream>>>(
+                        start,
+                        seeds,
+                        in_ptr,
+                        count,
+                        word,
+                        config,
+                        self.stage_bufs[out].as_device_ptr(),
+                        self.survivors_buf.as_device_ptr(),
+                    )
+                )?;
+            }
+            stream.synchronize()?;
+            let mut survivors = [0u64];
+            self.survivors_buf.copy_to(&mut survivors)?;
+            let survivors = survivors[0];
+            stats.add_stage(i, count, count - survivors);
+            count = survivors;
+            candidates = Some(out);
+        }
+        filter.record(&stats);
+
+        let mut indices = vec![0u64; count as usize];
+        match candidates {
+            // Survivors are appended in whatever order threads finish.
+            Some(c) if count > 0 => self.stage_bufs[c].index(..count as usize).copy_to(&mut indices[..])?,
+            Some(_) => {}
+            // An empty filter passes everything.
+            None => indices.iter_mut().enumerate().for_each(|(i, x)| *x = i as u64),
+        }
+        indices.sort_unstable();
+        Ok(indices)
+    }
+}
+
+#[cfg(feature = "gpu")]
+fn gpu_worker(ordinal: u32, search: &Search, queue: &ChunkQueue, workers: u64) -> WorkerResult<WorkerReport> {
+    let mut gpu = GpuLaunch::new(ordinal, search)?;
+    let mut report = WorkerReport::new(format!("gpu{ordinal}"), search.top_k);
+
+    while let Some(range) = queue.take(report.next_chunk(queue, workers)) {
+        let len = range.end - range.start;
+        let t = Instant::now();
+        match gpu.chunk(search, range.clone()) {
+            Ok(result) => report.record(search, len, t.elapsed(), result),
+            Err(e) => {
+                queue.give_back(range);
+                return Err(e.into());
+            }
+        }
+    }
+    Ok(report)
+}
@@ -0,0 +1,272 @@
+use std::fmt;
+use std::fs;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use common::walk::encode_seed;
+pub use common::walk::{CHARSET, MAX_SEED_LEN};
+
+// Fixed-width base-35 digits of `n` written into `out` (most significant first).
+#[inline(always)]
+fn write_digits(mut n: u64, out: &mut [u8]) {
+    for b in out.iter_mut().rev() {
+        *b = CHARSET[(n % 35) as usize];
+        n /= 35;
+    }
+}
+
+#[inline(always)]
+fn charset_index(c: u8) -> Option<usize> {
+    CHARSET.iter().position(|&x| x == c)
+}
+
+/// Value following `name` on the command line, if present.
+pub fn flag_value<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    args.iter().position(|a| a == name).and_then(|i| args.get(i + 1)).map(String::as_str)
+}
+
+/// Every value following a repeatable flag such as `--clause`.
+pub fn flag_values<'a>(args: &'a [String], name: &str) -> Vec<&'a str> {
+    args.windows(2).filter(|w| w[0] == name).map(|w| w[1].as_str()).collect()
+}
+
+pub fn flag_number(args: &[String], name: &str) -> Result<Option<u64>, String> {
+    flag_value(args, name).map(|v| v.parse::<u64>().map_err(|e| format!("{name} {v}: {e}"))).transpose()
+}
+
+// splitmix64; one independent value per (sample seed, index) so sampling parallelizes.
+#[inline(always)]
+fn mix64(mut z: u64) -> u64 {
+    z = z.wrapping_add(0x9E3779B97F4A7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+/// A Balatro seed: 1 to 8 characters from [`CHARSET`], stored inline.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Seed {
+    bytes: [u8; MAX_SEED_LEN],
+    len: u8,
+}
+
+impl Seed {
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len as usize]
+    }
+
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        // Only CHARSET bytes are ever stored.
+        std::str::from_utf8(self.as_bytes()).unwrap()
+    }
+
+    /// Zero-padded 8-byte form used for GPU seed buffers.
+    #[inline]
+    pub fn padded(&self) -> [u8; MAX_SEED_LEN] {
+        let mut out = [0u8; MAX_SEED_LEN];
+        out[..self.len as usize].copy_from_slice(self.as_bytes());
+        out
+    }
+}
+
+impl FromStr for Seed {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() || s.len() > MAX_SEED_LEN {
+            return Err(format!("seed '{s}' must be 1 to {MAX_SEED_LEN} characters"));
+        }
+        let mut bytes = [0u8; MAX_SEED_LEN];
+        for (i, c) in s.bytes().enumerate() {
+            let c = c.to_ascii_uppercase();
+            if charset_index(c).is_none() {
+                return Err(format!("seed '{s}' contains '{}', which is not a seed character", c as char));
+            }
+            bytes[i] = c;
+        }
+        Ok(Seed { bytes, len: s.len() as u8 })
+    }
+}
+
+impl fmt::Display for Seed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Debug for Seed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Seed({})", self.as_str())
+    }
+}
+
+/// The set of seeds a search runs over. Every source is indexable by
+/// `0..len()`, so the scheduler can hand out index chunks regardless of kind.
+pub enum SeedSource {
+    /// Seeds `start..start + total` of the enumeration in [`common::walk`]:
+    /// shortest first, and within a length the first character fastest.
+    Range { start: u64, total: u64 },
+    /// Explicit seeds, e.g. a candidate list from the community.
+    List(Vec<Seed>),
+    /// Every `length`-character seed starting with `prefix` and ending with `suffix`.
+    Pattern { prefix: Vec<u8>, suffix: Vec<u8>, length: usize },
+    /// `count` uniformly random `length`-character seeds, reproducible from `rng_seed`.
+    Sample { count: u64, length: usize, rng_seed: u64 },
+}
+
+impl SeedSource {
+    /// Read one seed per line. Blank lines and `#` comments are skipped, and only
+    /// the first comma/whitespace separated field is used so CSV exports work.
+    pub fn from_file(path: &str) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("reading {path}: {e}"))?;
+        let mut seeds = Vec::new();
+        for (line_no, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("");
+            let Some(field) = line.split(|c: char| c == ',' || c.is_whitespace()).find(|f| !f.is_empty()) else {
+                continue;
+            };
+            seeds.push(field.parse::<Seed>().map_err(|e| format!("{path}:{}: {e}", line_no + 1))?);
+        }
+        Ok(SeedSource::List(seeds))
+    }
+
+    pub fn pattern(prefix: &str, suffix: &str, length: usize) -> Result<Self, String> {
+        if length == 0 || length > MAX_SEED_LEN {
+            return Err(format!("seed length must be 1 to {MAX_SEED_LEN}"));
+        }
+        let prefix = Self::pattern_part(prefix)?;
+        let suffix = Self::pattern_part(suffix)?;
+        if prefix.len() + suffix.len() > length {
+            return Err(format!("prefix and suffix do not fit in {length} characters"));
+        }
+        Ok(SeedSource::Pattern { prefix, suffix, length })
+    }
+
+    fn pattern_part(s: &str) -> Result<Vec<u8>, String> {
+        if s.is_empty() {
+            return Ok(Vec::new());
+        }
+        Ok(s.parse::<Seed>()?.as_bytes().to_vec())
+    }
+
+    pub fn sample(count: u64, length: usize, rng_seed: Option<u64>) -> Result<Self, String> {
+        if length == 0 || length > MAX_SEED_LEN {
+            return Err(format!("seed length must be 1 to {MAX_SEED_LEN}"));
+        }
+        let rng_seed = rng_seed.unwrap_or_else(|| {
+            SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos() as u64).unwrap_or(0)
+        });
+        Ok(SeedSource::Sample { count, length, rng_seed })
+    }
+
+    /// Build a source from command-line flags, or `None` if no source flag was given:
+    /// `--seeds FILE`, `--sample N`, `--prefix P`, `--suffix S`, `--length L`,
+    /// `--rng-seed X`, `--start N --total N`.
+    pub fn from_args(args: &[String]) -> Result<Option<Self>, String> {
+        let value = |name: &str| flag_value(args, name);
+        let number = |name: &str| flag_number(args, name);
+        let length = number("--length")?.map(|l| l as usize);
+
+        if let Some(path) = value("--seeds") {
+            return Self::from_file(path).map(Some);
+        }
+        if let Some(count) = number("--sample")? {
+            return Self::sample(count, length.unwrap_or(MAX_SEED_LEN), number("--rng-seed")?).map(Some);
+        }
+        let prefix = value("--prefix");
+        let suffix = value("--suffix");
+        if prefix.is_some() || suffix.is_some() || length.is_some() {
+            return Self::pattern(prefix.unwrap_or(""), suffix.unwrap_or(""), length.unwrap_or(MAX_SEED_LEN))
+                .map(Some);
+        }
+        if let Some(total) = number("--total")? {
+            return Ok(Some(SeedSource::Range { start: number("--start")?.unwrap_or(0), total }));
+        }
+        Ok(None)
+    }
+
+    /// Number of seeds in the source.
+    pub fn len(&self) -> u64 {
+        match self {
+            SeedSource::Range { total, .. } => *total,
+            SeedSource::List(seeds) => seeds.len() as u64,
+            SeedSource::Pattern { prefix, suffix, length } => {
+                35u64.pow((length - prefix.len() - suffix.len()) as u32)
+            }
+            SeedSource::Sample { count, .. } => *count,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Write seed `i` into `out`, returning `(start, len)` of the seed bytes like
+    /// [`encode_seed`].
+    #[inline]
+    pub fn write_seed(&self, i: u64, out: &mut [u8; 8]) -> (usize, usize) {
+        match self {
+            SeedSource::Range { start, .. } => encode_seed(start + i, out),
+            SeedSource::List(seeds) => {
+                let seed = &seeds[i as usize];
+                let len = seed.len as usize;
+                out[..len].copy_from_slice(seed.as_bytes());
+                (0, len)
+            }
+            SeedSource::Pattern { prefix, suffix, length } => {
+                let mid_end = length - suffix.len();
+                out[..prefix.len()].copy_from_slice(prefix);
+                write_digits(i, &mut out[prefix.len()..mid_end]);
+                out[mid_end..*length].copy_from_slice(suffix);
+                (0, *length)
+            }
+            SeedSource::Sample { length, rng_seed, .. } => {
+                let mut bits = mix64(rng_seed ^ mix64(i));
+                for b in out[..*length].iter_mut() {
+                    // 35^8 < 2^42, so one 64-bit draw covers a full seed with little bias.
+                    *b = CHARSET[(bits % 35) as usize];
+                    bits /= 35;
+                }
+                (0, *length)
+            }
+        }
+    }
+
+    #[inline]
+    pub fn seed(&self, i: u64) -> Seed {
+        let mut bytes = [0u8; MAX_SEED_LEN];
+        let (off, len) = self.write_seed(i, &mut bytes);
+        bytes.copy_within(off..off + len, 0);
+        bytes[len..].fill(0);
+        Seed { bytes, len: len as u8 }
+    }
+
+    /// Start of the contiguous base-35 range backing this source, if any. GPUs
+    /// enumerate such ranges themselves; other sources are uploaded as seed buffers.
+    pub fn range_start(&self) -> Option<u64> {
+        match self {
+            SeedSource::Range { start, .. } => Some(*start),
+            _ => None,
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            SeedSource::Range { start, total } => format!("range {start}..{}", start + total),
+            SeedSource::List(seeds) => format!("{} listed seeds", seeds.len()),
+            SeedSource::Pattern { prefix, suffix, length } => format!(
+                "{} seeds of length {length} matching {}{}{}",
+                self.len(),
+                String::from_utf8_lossy(prefix),
+                "*".repeat(length - prefix.len() - suffix.len()),
+                String::from_utf8_lossy(suffix)
+            ),
+            SeedSource::Sample { count, length, rng_seed } => {
+                format!("{count} random seeds of length {length} (rng seed {rng_seed})")
+            }
+        }
+    }
+}
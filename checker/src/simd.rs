@@ -0,0 +1,45 @@
+use crate::seeds::SeedSource;
+use common::batch::{pseudohash_lanes, LANES};
+use common::util::pseudohash_bytes;
+use std::ops::Range;
+
+/// Hashed seeds of [`LANES`] seeds of one length, on AVX2 when the CPU has it.
+/// Other targets get whatever vector width the baseline offers (SSE2, NEON).
+#[inline]
+fn hash_lanes(strings: [&[u8]; LANES]) -> [f64; LANES] {
+    #[cfg(target_arch = "x86_64")]
+    if std::arch::is_x86_feature_detected!("avx2") {
+        // SAFETY: the CPU supports AVX2.
+        return unsafe { hash_lanes_avx2(strings) };
+    }
+    pseudohash_lanes(strings)
+}
+
+// The same loop over lanes, inlined into a function compiled with AVX2 so
+// the four lanes fit one 256-bit register; the baseline build only has SSE2.
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn hash_lanes_avx2(strings: [&[u8]; LANES]) -> [f64; LANES] {
+    pseudohash_lanes(strings)
+}
+
+/// Sum of the hashed seeds of `block`, at most [`LANES`] indices of `source`.
+/// Full blocks of one seed length are hashed side by side; the tail of a range
+/// and blocks straddling a change of length fall back to one seed at a time.
+pub fn hashed_seed_sum(source: &SeedSource, block: Range<u64>) -> f64 {
+    let mut bufs = [[0u8; 8]; LANES];
+    let mut spans = [(0, 0); LANES];
+    let n = block.end.saturating_sub(block.start) as usize;
+    debug_assert!(n <= LANES);
+    for (k, i) in block.enumerate() {
+        spans[k] = source.write_seed(i, &mut bufs[k]);
+    }
+    let seed = |k: usize| {
+        let (off, len) = spans[k];
+        &bufs[k][off..off + len]
+    };
+    if n == LANES && spans.iter().all(|&(_, len)| len == spans[0].1) {
+        return hash_lanes(std::array::from_fn(seed)).iter().sum();
+    }
+    (0..n).map(|k| pseudohash_bytes([seed(k)])).sum()
+}
@@ -0,0 +1,212 @@
+use crate::seeds::{Seed, SeedSource};
+use common::filter::{Clause, SeedView};
+use common::game::RunConfig;
+use rayon::prelude::*;
+use std::ops::Range;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+// Clause evaluations between timing samples; reading the clock every time
+// would cost about as much as a cheap clause.
+const TIMING_SAMPLE: u64 = 64;
+// Evaluations needed before measured numbers replace the static estimates.
+const MIN_EVALUATIONS: u64 = 1024;
+
+#[derive(Default)]
+struct ClauseStats {
+    evaluated: AtomicU64,
+    rejected: AtomicU64,
+    timed: AtomicU64,
+    nanos: AtomicU64,
+}
+
+/// Per-thread counters, folded into the shared [`StagedFilter`] stats once per chunk.
+#[derive(Clone)]
+pub struct LocalStats {
+    evaluated: Vec<u64>,
+    rejected: Vec<u64>,
+    timed: Vec<u64>,
+    nanos: Vec<u64>,
+}
+
+impl LocalStats {
+    fn new(clauses: usize) -> Self {
+        Self { evaluated: vec![0; clauses], rejected: vec![0; clauses], timed: vec![0; clauses], nanos: vec![0; clauses] }
+    }
+
+    /// Count a whole stage at once, as the GPU pipeline reports it: `evaluated`
+    /// candidates reached clause `i` and `rejected` of them failed it.
+    #[cfg_attr(not(feature = "gpu"), allow(dead_code))]
+    pub fn add_stage(&mut self, i: usize, evaluated: u64, rejected: u64) {
+        self.evaluated[i] += evaluated;
+        self.rejected[i] += rejected;
+    }
+
+    pub fn merged(mut self, other: LocalStats) -> LocalStats {
+        for (a, b) in [
+            (&mut self.evaluated, &other.evaluated),
+            (&mut self.rejected, &other.rejected),
+            (&mut self.timed, &other.timed),
+            (&mut self.nanos, &other.nanos),
+        ] {
+            for (x, y) in a.iter_mut().zip(b) {
+                *x += y;
+            }
+        }
+        self
+    }
+}
+
+/// A conjunction of clauses evaluated cheapest-and-most-selective first. Each
+/// seed stops at its first failing clause, and the evaluation order is re-ranked
+/// from measured cost and rejection rate after every chunk.
+pub struct StagedFilter {
+    clauses: Vec<Clause>,
+    stats: Vec<ClauseStats>,
+    order: Mutex<Vec<usize>>,
+}
+
+impl StagedFilter {
+    pub fn new(clauses: Vec<Clause>) -> Self {
+        let stats = clauses.iter().map(|_| ClauseStats::default()).collect();
+        let filter = Self { order: Mutex::new((0..clauses.len()).collect()), clauses, stats };
+        filter.reorder();
+        filter
+    }
+
+    pub fn clauses(&self) -> &[Clause] {
+        &self.clauses
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.clauses.is_empty()
+    }
+
+    /// Current evaluation order; workers take a copy at the start of each chunk.
+    pub fn order(&self) -> Vec<usize> {
+        self.order.lock().unwrap().clone()
+    }
+
+    pub fn local_stats(&self) -> LocalStats {
+        LocalStats::new(self.clauses.len())
+    }
+
+    /// Evaluate clauses in `order` on `seed` under `config`, stopping at the
+    /// first one that fails.
+    #[inline]
+    pub fn matches(&self, order: &[usize], seed: &str, config: RunConfig, stats: &mut LocalStats) -> bool {
+        let mut view = SeedView::with_config(seed, config);
+        for &i in order {
+            stats.evaluated[i] += 1;
+            let passed = if stats.evaluated[i] % TIMING_SAMPLE == 0 {
+                let t = Instant::now();
+                let passed = self.clauses[i].matches(&mut view);
+                stats.timed[i] += 1;
+                stats.nanos[i] += t.elapsed().as_nanos() as u64;
+                passed
+            } else {
+                self.clauses[i].matches(&mut view)
+            };
+            if !passed {
+                stats.rejected[i] += 1;
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Fold a chunk's counters into the shared stats and re-rank the clauses.
+    pub fn record(&self, local: &LocalStats) {
+        for (i, s) in self.stats.iter().enumerate() {
+            s.evaluated.fetch_add(local.evaluated[i], Ordering::Relaxed);
+            s.rejected.fetch_add(local.rejected[i], Ordering::Relaxed);
+            s.timed.fetch_add(local.timed[i], Ordering::Relaxed);
+            s.nanos.fetch_add(local.nanos[i], Ordering::Relaxed);
+        }
+        self.reorder();
+    }
+
+    // Mean cost in nanoseconds, or a static guess by clause kind until measured.
+    // Costs are relative to whatever ran earlier for the same seed, since
+    // SeedView caches draws shared between clauses.
+    fn cost(&self, i: usize) -> f64 {
+        let s = &self.stats[i];
+        let timed = s.timed.load(Ordering::Relaxed);
+        if s.evaluated.load(Ordering::Relaxed) >= MIN_EVALUATIONS && timed > 0 {
+            return s.nanos.load(Ordering::Relaxed) as f64 / timed as f64;
+        }
+        match self.clauses[i] {
+            // Bosses of later antes need every earlier boss drawn first.
+            Clause::Boss { ante, .. } => 100.0 * ante as f64,
+            Clause::Voucher { .. } => 100.0,
+            Clause::Tag { .. } => 200.0,
+            // Both draw the ante's first shop: a card type roll per slot, then
+            // a rarity, a joker from that rarity's pool and an edition for
+            // each slot holding a joker.
+            Clause::ShopJoker { .. } | Clause::ShopEdition { .. } => 400.0,
+        }
+    }
+
+    fn rejection_rate(&self, i: usize) -> Option<f64> {
+        let s = &self.stats[i];
+        let evaluated = s.evaluated.load(Ordering::Relaxed);
+        (evaluated > 0).then(|| s.rejected.load(Ordering::Relaxed) as f64 / evaluated as f64)
+    }
+
+    // For independent clauses, the expected cost per seed is minimized by running
+    // them in increasing order of cost / rejection probability.
+    fn reorder(&self) {
+        let rank = |i: usize| {
+            let evaluated = self.stats[i].evaluated.load(Ordering::Relaxed);
+            let reject = if evaluated >= MIN_EVALUATIONS { self.rejection_rate(i).unwrap_or(0.5) } else { 0.5 };
+            self.cost(i) / reject.max(1e-6)
+        };
+        let mut order: Vec<usize> = (0..self.clauses.len()).collect();
+        order.sort_by(|&a, &b| rank(a).total_cmp(&rank(b)));
+        *self.order.lock().unwrap() = order;
+    }
+
+    /// Per-clause rejection rates and costs, in final evaluation order.
+    pub fn print_stats(&self) {
+        println!("filter stages (final order):");
+        println!("  {:<32} {:>12} {:>12} {:>9} {:>9}", "clause", "evaluated", "rejected", "reject%", "ns/eval");
+        for i in self.order() {
+            let s = &self.stats[i];
+            println!(
+                "  {:<32} {:>12} {:>12} {:>8.3}% {:>9.0}",
+                self.clauses[i].to_string(),
+                s.evaluated.load(Ordering::Relaxed),
+                s.rejected.load(Ordering::Relaxed),
+                100.0 * self.rejection_rate(i).unwrap_or(0.0),
+                self.cost(i)
+            );
+        }
+    }
+}
+
+/// CPU filter pass over `range` of `source` under `config`: the matching seeds, in order.
+pub fn filter_seeds_cpu(source: &SeedSource, range: Range<u64>, filter: &StagedFilter, config: RunConfig) -> Vec<Seed> {
+    let order = filter.order();
+    let (matches, stats) = range
+        .into_par_iter()
+        .fold(
+            || (Vec::new(), filter.local_stats()),
+            |(mut matches, mut stats), i| {
+                let seed = source.seed(i);
+                if filter.matches(&order, seed.as_str(), config, &mut stats) {
+                    matches.push(seed);
+                }
+                (matches, stats)
+            },
+        )
+        .reduce(
+            || (Vec::new(), filter.local_stats()),
+            |(mut a, sa), (b, sb)| {
+                a.extend(b);
+                (a, sa.merged(sb))
+            },
+        );
+    filter.record(&stats);
+    matches
+}
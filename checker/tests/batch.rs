@@ -0,0 +1,44 @@
+use common::batch::{pseudohash_lanes, LANES};
+use common::util::pseudohash_bytes;
+
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        self.0 >> 11
+    }
+
+    fn bytes(&mut self, len: usize, alphabet: &[u8]) -> Vec<u8> {
+        (0..len).map(|_| alphabet[(self.next() % alphabet.len() as u64) as usize]).collect()
+    }
+}
+
+fn check_pseudohash<const N: usize>(rng: &mut Lcg, len: usize, alphabet: &[u8]) {
+    let strings: Vec<Vec<u8>> = (0..N).map(|_| rng.bytes(len, alphabet)).collect();
+    let lanes = pseudohash_lanes(std::array::from_fn::<_, N, _>(|l| strings[l].as_slice()));
+    for (s, lane) in strings.iter().zip(lanes) {
+        assert_eq!(lane.to_bits(), pseudohash_bytes([s.as_slice()]).to_bits(), "pseudohash({s:?})");
+    }
+}
+
+#[test]
+fn pseudohash_lanes_match_scalar() {
+    let mut rng = Lcg(1);
+    let seeds = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ123456789";
+    let printable: Vec<u8> = (b' '..=b'~').collect();
+    for _ in 0..20_000 {
+        let len = 1 + (rng.next() % 8) as usize;
+        check_pseudohash::<LANES>(&mut rng, len, seeds);
+        check_pseudohash::<8>(&mut rng, len, seeds);
+        let len = 1 + (rng.next() % 32) as usize;
+        check_pseudohash::<3>(&mut rng, len, &printable);
+    }
+    check_pseudohash::<LANES>(&mut rng, 0, seeds);
+}
+
+#[test]
+#[should_panic(expected = "one length")]
+fn pseudohash_lanes_reject_mixed_lengths() {
+    pseudohash_lanes([b"AB".as_slice(), b"ABC".as_slice()]);
+}
@@ -0,0 +1,165 @@
+mod support;
+
+use common::blind::{BlindRound, PlayError};
+use common::game::GameState;
+use common::items::{Bosses, CardTypes, JokerTypes};
+use common::random::Random;
+use common::run::RunState;
+use support::{joker, SEEDS};
+
+fn start(seed: &str, boss: Bosses) -> (Random, RunState, BlindRound) {
+    let mut rng = Random::new(seed.as_bytes());
+    let mut run = RunState::new();
+    let mut round = BlindRound::new(&mut rng, &run, Some(boss), 1);
+    round.draw(&mut rng, &mut run);
+    (rng, run, round)
+}
+
+#[test]
+fn the_deck_is_shuffled_and_drawn_from_the_top() {
+    for seed in SEEDS {
+        let (_, run, round) = start(seed, Bosses::TheWall);
+        assert_eq!((run.hand.len(), round.deck.len()), (8, 44));
+        let mut all: Vec<usize> = run.hand.iter().chain(&round.deck).copied().collect();
+        all.sort_unstable();
+        assert_eq!(all, (0..52).collect::<Vec<_>>());
+        assert!(round.face_down.is_empty() && round.forced.is_none());
+    }
+}
+
+#[test]
+fn face_down_draws() {
+    for seed in SEEDS {
+        let (mut rng, mut run, mut round) = start(seed, Bosses::TheHouse);
+        assert_eq!(round.face_down, run.hand);
+        let selected = run.hand[..2].to_vec();
+        round.discard(&mut run, &selected).unwrap();
+        round.draw(&mut rng, &mut run);
+        assert_eq!(round.face_down.len(), 6);
+
+        let (_, run, round) = start(seed, Bosses::TheMark);
+        let faces: Vec<usize> = run
+            .hand
+            .iter()
+            .copied()
+            .filter(|&id| matches!(run.card(id).unwrap().rank, CardTypes::Jack | CardTypes::Queen | CardTypes::King))
+            .collect();
+        assert_eq!(round.face_down, faces);
+
+        // The Fish flips what a played hand draws, not what a discard draws.
+        let (mut rng, mut run, mut round) = start(seed, Bosses::TheFish);
+        let selected = run.hand[..2].to_vec();
+        round.discard(&mut run, &selected).unwrap();
+        round.draw(&mut rng, &mut run);
+        assert!(round.face_down.is_empty());
+        let selected = run.hand[..3].to_vec();
+        round.play(&mut rng, &mut run, &selected).unwrap();
+        let drawn = round.draw(&mut rng, &mut run);
+        assert_eq!(round.face_down, drawn);
+    }
+}
+
+#[test]
+fn the_serpent_draws_three() {
+    let (mut rng, mut run, mut round) = start("ABCD1234", Bosses::TheSerpent);
+    let selected = run.hand[..5].to_vec();
+    round.play(&mut rng, &mut run, &selected).unwrap();
+    assert_eq!(round.draw(&mut rng, &mut run).len(), 3);
+    let selected = run.hand[..1].to_vec();
+    round.discard(&mut run, &selected).unwrap();
+    assert_eq!(round.draw(&mut rng, &mut run).len(), 3);
+    assert_eq!(run.hand.len(), 8);
+}
+
+#[test]
+fn the_hook_discards_held_cards() {
+    for seed in SEEDS {
+        let (mut rng, mut run, mut round) = start(seed, Bosses::TheHook);
+        let played = run.hand[..3].to_vec();
+        let held = run.hand[3..].to_vec();
+        let hooked = round.play(&mut rng, &mut run, &played).unwrap();
+        assert_eq!(hooked.len(), 2);
+        assert!(hooked.iter().all(|id| held.contains(id) && !run.hand.contains(id)));
+        assert_eq!(run.hand.len(), 3);
+
+        let selected = run.hand[..2].to_vec();
+        let hooked = round.play(&mut rng, &mut run, &selected).unwrap();
+        assert_eq!(hooked.len(), 1);
+        assert!(run.hand.is_empty());
+    }
+}
+
+#[test]
+fn cerulean_bell_forces_a_card() {
+    for seed in SEEDS {
+        let (mut rng, mut run, mut round) = start(seed, Bosses::CeruleanBell);
+        let forced = round.forced.unwrap();
+        assert!(run.hand.contains(&forced));
+        let others: Vec<usize> = run.hand.iter().copied().filter(|&id| id != forced).take(2).collect();
+        assert_eq!(round.play(&mut rng, &mut run, &others), Err(PlayError::Forced(forced)));
+        assert_eq!(round.discard(&mut run, &[]), Err(PlayError::Selection));
+        assert_eq!(round.discard(&mut run, &[99]), Err(PlayError::NotInHand(99)));
+
+        // The forced card stays forced until it leaves the hand.
+        round.draw(&mut rng, &mut run);
+        assert_eq!(round.forced, Some(forced));
+        round.play(&mut rng, &mut run, &[forced, others[0]]).unwrap();
+        round.draw(&mut rng, &mut run);
+        assert!(round.forced.is_some_and(|id| id != forced && run.hand.contains(&id)));
+    }
+}
+
+#[test]
+fn crimson_heart_moves_between_jokers() {
+    for seed in SEEDS {
+        let mut rng = Random::new(seed.as_bytes());
+        let mut run = RunState::new();
+        run.jokers = vec![joker(JokerTypes::Joker), joker(JokerTypes::Baron)];
+        let mut round = BlindRound::new(&mut rng, &run, Some(Bosses::CrimsonHeart), 1);
+        round.draw(&mut rng, &mut run);
+        assert_eq!(round.disabled_joker, None);
+        let mut last = None;
+        for _ in 0..4 {
+            let selected = run.hand[..1].to_vec();
+            round.play(&mut rng, &mut run, &selected).unwrap();
+            round.draw(&mut rng, &mut run);
+            assert!(round.disabled_joker.is_some() && round.disabled_joker != last);
+            last = round.disabled_joker;
+        }
+    }
+}
+
+#[test]
+fn chicot_disables_the_boss() {
+    for seed in SEEDS {
+        let mut rng = Random::new(seed.as_bytes());
+        rng.enable_trace();
+        let mut run = RunState::new();
+        run.jokers = vec![joker(JokerTypes::Chicot)];
+        let mut round = BlindRound::new(&mut rng, &run, Some(Bosses::TheWheel), 1);
+        assert!(round.disabled);
+        round.draw(&mut rng, &mut run);
+        assert!(round.face_down.is_empty());
+        let ids: Vec<String> = rng.take_trace().into_iter().map(|e| e.id).collect();
+        assert_eq!(ids, ["nr1"]);
+    }
+}
+
+#[test]
+fn game_state_plays_its_own_run() {
+    for seed in SEEDS {
+        let mut game = GameState::new(seed, 3);
+        let mut round = game.new_blind_round(Some(Bosses::TheHook));
+        game.draw_to_hand(&mut round);
+        let selected = game.run.hand[..4].to_vec();
+        let hooked = game.play_hand(&mut round, &selected).unwrap();
+
+        let mut rng = Random::new(seed.as_bytes());
+        let mut run = RunState::new();
+        let mut again = BlindRound::new(&mut rng, &run, Some(Bosses::TheHook), 3);
+        again.draw(&mut rng, &mut run);
+        let selected = run.hand[..4].to_vec();
+        assert_eq!(again.play(&mut rng, &mut run, &selected), Ok(hooked));
+        assert_eq!((run, again), (game.run.clone(), round));
+    }
+}
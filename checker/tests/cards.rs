@@ -0,0 +1,132 @@
+mod support;
+
+use common::cards::{
+    certificate_card, familiar_cards, grim_cards, incantation_cards, marble_card, shop_card, standard_pack_card,
+    FRONTS,
+};
+use common::items::{CardSuits, CardTypes, Editions, EnhancementTypes, SealTypes};
+use common::random::Random;
+use common::reference::PseudoRandom;
+use common::util::LuaRandom;
+use std::collections::HashSet;
+use support::{MORE_SEEDS, SEEDS};
+
+fn key(rank: CardTypes, suit: CardSuits) -> String {
+    let suit = match suit {
+        CardSuits::Clubs => 'C',
+        CardSuits::Diamonds => 'D',
+        CardSuits::Hearts => 'H',
+        CardSuits::Spades => 'S',
+    };
+    let rank = match rank {
+        CardTypes::Ten => 'T',
+        CardTypes::Jack => 'J',
+        CardTypes::Queen => 'Q',
+        CardTypes::King => 'K',
+        CardTypes::Ace => 'A',
+        r => char::from(b'1' + r as u8),
+    };
+    format!("{suit}_{rank}")
+}
+
+#[test]
+fn fronts_follow_sorted_card_keys() {
+    let keys: Vec<String> = FRONTS.iter().map(|&(r, s)| key(r, s)).collect();
+    let mut sorted = keys.clone();
+    sorted.sort();
+    assert_eq!(keys, sorted);
+    assert_eq!(keys.iter().collect::<HashSet<_>>().len(), 52);
+    assert_eq!(keys[..3], ["C_2", "C_3", "C_4"]);
+    assert_eq!(keys[51], "S_T");
+}
+
+// pseudorandom(key) from the plain transcription of the game's seeding.
+fn lua_draw(lua: &mut PseudoRandom, key: &str) -> f64 {
+    LuaRandom::new(lua.pseudoseed(key)).random()
+}
+
+#[test]
+fn standard_pack_draws_the_game_streams() {
+    for seed in SEEDS.into_iter().chain(MORE_SEEDS) {
+        for ante in 1..=3 {
+            let mut rng = Random::new(seed.as_bytes());
+            rng.enable_trace();
+            let card = standard_pack_card(&mut rng, ante, 1.0);
+            let ids: Vec<String> = rng.take_trace().into_iter().map(|e| e.id).collect();
+
+            let mut lua = PseudoRandom::new(seed);
+            let mut expected = vec![format!("stdset{ante}")];
+            let enhanced = lua_draw(&mut lua, &expected[0]) > 0.6;
+            if enhanced {
+                expected.push(format!("Enhancedsta{ante}"));
+            }
+            expected.push(format!("frontsta{ante}"));
+            expected.push(format!("standard_edition{ante}"));
+            expected.push(format!("stdseal{ante}"));
+            let sealed = {
+                let mut lua = PseudoRandom::new(seed);
+                lua_draw(&mut lua, &format!("stdseal{ante}")) > 0.8
+            };
+            if sealed {
+                expected.push(format!("stdsealtype{ante}"));
+            }
+            assert_eq!(ids, expected, "seed {seed} ante {ante}");
+
+            let front = FRONTS[(lua_draw(&mut lua, &format!("frontsta{ante}")) * 52.0) as usize];
+            assert_eq!((card.rank, card.suit), front, "seed {seed} ante {ante}");
+            assert_eq!(card.enhancement != EnhancementTypes::None, enhanced);
+            assert_eq!(card.seal != SealTypes::None, sealed);
+        }
+    }
+}
+
+#[test]
+fn standard_pack_rates() {
+    let (mut enhanced, mut sealed, mut editioned) = (0, 0, 0);
+    let n = 4000;
+    for i in 0..n {
+        let mut rng = Random::new(format!("S{i}").as_bytes());
+        let card = standard_pack_card(&mut rng, 1, 1.0);
+        assert_ne!(card.edition, Editions::Negative);
+        enhanced += (card.enhancement != EnhancementTypes::None) as i32;
+        sealed += (card.seal != SealTypes::None) as i32;
+        editioned += (card.edition != Editions::None) as i32;
+    }
+    assert!((1400..1800).contains(&enhanced), "{enhanced} enhanced of {n}");
+    assert!((600..1000).contains(&sealed), "{sealed} sealed of {n}");
+    assert!((200..450).contains(&editioned), "{editioned} with an edition of {n}");
+}
+
+#[test]
+fn shop_cards_are_plain_without_illusion() {
+    for seed in SEEDS.into_iter().chain(MORE_SEEDS) {
+        let mut rng = Random::new(seed.as_bytes());
+        let card = shop_card(&mut rng, 2, false);
+        assert_eq!(card.enhancement, EnhancementTypes::None);
+        assert_eq!(card.edition, Editions::None);
+        let mut lua = PseudoRandom::new(seed);
+        assert_eq!((card.rank, card.suit), FRONTS[(lua_draw(&mut lua, "frontsho2") * 52.0) as usize]);
+    }
+}
+
+#[test]
+fn spectral_cards_keep_their_ranks() {
+    for seed in SEEDS.into_iter().chain(MORE_SEEDS) {
+        let mut rng = Random::new(seed.as_bytes());
+        for card in familiar_cards(&mut rng) {
+            assert!(matches!(card.rank, CardTypes::Jack | CardTypes::Queen | CardTypes::King));
+            assert_ne!(card.enhancement, EnhancementTypes::None);
+            assert_ne!(card.enhancement, EnhancementTypes::Stone);
+        }
+        for card in grim_cards(&mut rng) {
+            assert_eq!(card.rank, CardTypes::Ace);
+            assert_ne!(card.enhancement, EnhancementTypes::Stone);
+        }
+        for card in incantation_cards(&mut rng) {
+            assert!(!matches!(card.rank, CardTypes::Jack | CardTypes::Queen | CardTypes::King | CardTypes::Ace));
+            assert_ne!(card.enhancement, EnhancementTypes::Stone);
+        }
+        assert_ne!(certificate_card(&mut rng).seal, SealTypes::None);
+        assert_eq!(marble_card(&mut rng).enhancement, EnhancementTypes::Stone);
+    }
+}
@@ -0,0 +1,185 @@
+mod support;
+
+use common::challenge::{Ban, Challenge, CHALLENGES};
+use common::filter::{Clause, SeedView};
+use common::game::{GameState, RunConfig};
+use common::items::{Bosses, CardTypes, Consumable, EnhancementTypes, JokerTypes, RandomSource, Spectral, Tags, Vouchers};
+use common::random::ChoiceError;
+use common::run::RunState;
+use support::{all, SEEDS};
+
+fn challenge(name: &str) -> &'static Challenge {
+    Challenge::from_name(name).unwrap_or_else(|| panic!("no challenge {name}"))
+}
+
+#[test]
+fn challenges_are_found_by_name_or_key() {
+    for c in &CHALLENGES {
+        assert_eq!(Challenge::from_name(c.name), Some(c));
+        assert_eq!(Challenge::from_name(c.id), Some(c));
+    }
+    assert_eq!(challenge("rich get richer").id, "c_rich_1");
+    assert_eq!(challenge("Five Card Draw").id, "c_five_card_1");
+    assert_eq!(Challenge::from_name("Jokerful"), None);
+}
+
+#[test]
+fn runs_start_as_the_challenge_says() {
+    let run = RunState::for_challenge(challenge("Fragile"));
+    assert_eq!(run.deck.len(), 52);
+    assert!(run.deck.iter().all(|c| c.enhancement == EnhancementTypes::Glass));
+    assert!(run.jokers.iter().all(|j| j.joker == JokerTypes::OopsAllSixes && j.stickers[0]));
+
+    let run = RunState::for_challenge(challenge("Mad World"));
+    assert_eq!(run.deck.len(), 36);
+    assert!(run.deck.iter().all(|c| !matches!(c.rank, CardTypes::Jack | CardTypes::Queen | CardTypes::King | CardTypes::Ace)));
+    assert!(run.deck.iter().enumerate().all(|(i, c)| c.sort_id == i));
+
+    let run = RunState::for_challenge(challenge("Five-Card Draw"));
+    assert_eq!((run.hand_size, run.joker_slots, run.dollars), (5, 7, 4));
+    assert_eq!(RunState::for_challenge(challenge("Rich get Richer")).dollars, 100);
+    assert_eq!(RunState::for_challenge(challenge("The Omelette")).jokers.len(), 5);
+    assert_eq!(RunState::for_challenge(challenge("Jokerless")).free_joker_slots(), 0);
+}
+
+#[test]
+fn bans_stay_out_of_the_pools() {
+    for seed in SEEDS {
+        let mut game = GameState::with_challenge(seed, 1, challenge("Non-Perishable"));
+        for _ in 0..300 {
+            let joker = game.next_joker_from_at_ante(RandomSource::Shop, 1).joker;
+            assert!(!game.run.is_banned(Ban::Joker(joker)), "seed {seed}: {joker:?}");
+        }
+
+        let mut game = GameState::with_challenge(seed, 1, challenge("Fragile"));
+        for _ in 0..100 {
+            let card = game.next_tarot_card_at_ante(RandomSource::Arcana, 1);
+            assert!(!game.run.is_banned(Ban::Consumable(card)), "seed {seed}: {card:?}");
+            let spectral = game.next_spectral_card_at_ante(RandomSource::SpectralPack, 1);
+            assert!(!game.run.is_banned(Ban::Consumable(Consumable::Spectral(spectral))), "seed {seed}: {spectral:?}");
+        }
+
+        let mut game = GameState::with_challenge(seed, 1, challenge("Jokerless"));
+        for ante in 1..=8 {
+            let tag = game.next_tag_from_at_ante(ante);
+            assert!(!game.run.is_banned(Ban::Tag(tag)), "seed {seed}: {tag:?}");
+            let spectral = game.next_spectral_card_at_ante(RandomSource::Shop, ante);
+            assert_ne!(spectral, Spectral::Wraith, "seed {seed}");
+        }
+    }
+}
+
+#[test]
+fn banned_and_starting_vouchers_never_show() {
+    for seed in SEEDS {
+        let mut game = GameState::with_challenge(seed, 1, challenge("Rich get Richer"));
+        let mut inflation = GameState::with_challenge(seed, 1, challenge("Inflation"));
+        for ante in 1..=8 {
+            let voucher = game.next_voucher_from_at_ante(RandomSource::Shop, ante);
+            assert!(!matches!(voucher, Vouchers::SeedMoney | Vouchers::MoneyTree), "seed {seed}");
+            let voucher = inflation.next_voucher_from_at_ante(RandomSource::Shop, ante);
+            assert!(!matches!(voucher, Vouchers::ClearanceSale | Vouchers::Liquidation), "seed {seed}");
+            inflation.activate_voucher(voucher);
+        }
+    }
+}
+
+#[test]
+fn a_challenge_without_bans_draws_as_a_plain_run() {
+    for seed in SEEDS {
+        let mut plain = GameState::new(seed, 1);
+        let mut xray = GameState::with_challenge(seed, 1, challenge("X-ray Vision"));
+        for ante in 1..=4 {
+            assert_eq!(xray.next_tag_from_at_ante(ante), plain.next_tag_from_at_ante(ante));
+            assert_eq!(
+                xray.next_voucher_from_at_ante(RandomSource::Shop, ante),
+                plain.next_voucher_from_at_ante(RandomSource::Shop, ante)
+            );
+            assert_eq!(
+                xray.next_joker_from_at_ante(RandomSource::Shop, ante),
+                plain.next_joker_from_at_ante(RandomSource::Shop, ante)
+            );
+        }
+    }
+}
+
+#[test]
+fn reset_seed_keeps_the_challenge() {
+    let mut game = GameState::with_challenge("ABCD1234", 1, challenge("Jokerless"));
+    game.reset_seed("HELLO1");
+    assert_eq!(game.run, RunState::for_challenge(challenge("Jokerless")));
+    for ante in 1..=8 {
+        let tag = game.next_tag_from_at_ante(ante);
+        assert!(!matches!(tag, Tags::RareTag | Tags::UncommonTag | Tags::BuffoonTag), "{tag:?}");
+    }
+}
+
+// A run of X-ray Vision, which bans nothing, with `bans` instead.
+fn banning(bans: Vec<Ban>) -> &'static Challenge {
+    Box::leak(Box::new(Challenge { bans: Box::leak(bans.into_boxed_slice()), ..*challenge("X-ray Vision") }))
+}
+
+#[test]
+fn locked_out_bosses_become_eligible_again() {
+    // At ante 1 The Wall is the only boss left, and it is still locked.
+    let bans = all::<Bosses>().into_iter().filter(|&b| b != Bosses::TheWall).map(Ban::Boss).collect();
+    let only_the_wall = banning(bans);
+    for seed in SEEDS {
+        let mut game = GameState::with_challenge(seed, 1, only_the_wall);
+        assert_eq!(game.try_next_boss(), Ok(Bosses::TheWall), "seed {seed}");
+        assert_eq!(game.next_boss(), Bosses::TheWall, "seed {seed}");
+    }
+}
+
+#[test]
+fn draws_fail_once_bans_cover_the_pool() {
+    let every_boss = banning(all::<Bosses>().into_iter().map(Ban::Boss).collect());
+    let every_voucher = banning(all::<Vouchers>().into_iter().map(Ban::Voucher).collect());
+    for seed in SEEDS {
+        let mut game = GameState::with_challenge(seed, 1, every_boss);
+        assert!(matches!(game.try_next_boss(), Err(ChoiceError::NoneUsable { .. })), "seed {seed}");
+        let mut game = GameState::with_challenge(seed, 1, every_voucher);
+        assert!(matches!(game.try_next_voucher(), Err(ChoiceError::NoneUsable { .. })), "seed {seed}");
+        assert_eq!(game.next_voucher(), Vouchers::Blank, "seed {seed}");
+    }
+}
+
+#[test]
+fn boss_clauses_miss_when_bans_cover_every_boss() {
+    let every_boss = banning(all::<Bosses>().into_iter().map(Ban::Boss).collect());
+    for seed in SEEDS {
+        let mut view = SeedView::with_challenge(seed, every_boss);
+        assert_eq!(view.boss(2), None, "seed {seed}");
+        assert!(!Clause::Boss { ante: 1, boss: Bosses::TheWall }.matches(&mut view), "seed {seed}");
+        // Vouchers still draw, after the bosses before them.
+        view.voucher(2);
+    }
+}
+
+#[test]
+fn seed_views_draw_as_the_challenge_run() {
+    let rich = challenge("Rich get Richer");
+    for seed in SEEDS {
+        let mut view = SeedView::with_challenge(seed, rich);
+        let mut game = GameState::with_challenge(seed, 1, rich);
+        for ante in 1..=4 {
+            let voucher = view.voucher(ante);
+            assert!(!matches!(voucher, Vouchers::SeedMoney | Vouchers::MoneyTree), "seed {seed}");
+            assert_eq!(voucher, game.next_voucher_from_at_ante(RandomSource::Shop, ante), "seed {seed}");
+        }
+        let config = RunConfig { challenge: Some(rich) };
+        let clause = Clause::Voucher { ante: 1, voucher: view.voucher(1) };
+        assert!(clause.matches(&mut SeedView::with_config(seed, config)), "seed {seed}");
+    }
+}
+
+#[test]
+fn run_configs_round_trip_through_gpu_words() {
+    let plain = RunConfig::default();
+    assert_eq!(RunConfig::from_word(plain.to_word()), Some(plain));
+    for c in &CHALLENGES {
+        let config = RunConfig { challenge: Some(c) };
+        assert_eq!(RunConfig::from_word(config.to_word()), Some(config), "{}", c.name);
+    }
+    assert_eq!(RunConfig::from_word(CHALLENGES.len() as u32), None);
+}
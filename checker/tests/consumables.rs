@@ -0,0 +1,147 @@
+mod support;
+
+use common::consumables::{use_consumable, UseError};
+use common::game::GameState;
+use common::items::{
+    CardSuits, CardTypes, Consumable, Editions, EnhancementTypes, HandTypes, JokerRarity, JokerTypes, Planets,
+    SealTypes, Spectral, Tarots,
+};
+use common::random::Random;
+use common::run::RunState;
+use support::{eternal, joker};
+
+fn run_with_hand() -> RunState {
+    let mut run = RunState::new();
+    run.hand = (0..8).collect();
+    run
+}
+
+#[test]
+fn invalid_uses_draw_nothing() {
+    let mut rng = Random::new(b"ABCD1234");
+    rng.enable_trace();
+    let mut run = run_with_hand();
+    let before = run.clone();
+    let cases = [
+        (Consumable::Tarot(Tarots::TheMagician), &[0, 1, 2][..], UseError::Selection { min: 1, max: 2 }),
+        (Consumable::Tarot(Tarots::Death), &[0][..], UseError::Selection { min: 2, max: 2 }),
+        (Consumable::Tarot(Tarots::TheLovers), &[40][..], UseError::NotInHand(40)),
+        (Consumable::Tarot(Tarots::TheFool), &[][..], UseError::NothingToCopy),
+        (Consumable::Tarot(Tarots::TheWheelOfFortune), &[][..], UseError::NoEligibleJoker),
+        (Consumable::Spectral(Spectral::Hex), &[][..], UseError::NoEligibleJoker),
+        (Consumable::Spectral(Spectral::Ankh), &[][..], UseError::NoEligibleJoker),
+    ];
+    for (card, selected, error) in cases {
+        assert_eq!(use_consumable(&mut rng, &mut run, card, 1, selected), Err(error), "{card:?}");
+    }
+    run.hand.truncate(1);
+    let err = use_consumable(&mut rng, &mut run, Consumable::Spectral(Spectral::Immolate), 1, &[]);
+    assert_eq!(err, Err(UseError::HandTooSmall));
+    run.jokers = vec![joker(JokerTypes::Joker); 5];
+    let err = use_consumable(&mut rng, &mut run, Consumable::Tarot(Tarots::Judgement), 1, &[]);
+    assert_eq!(err, Err(UseError::NoRoom));
+    let err = use_consumable(&mut rng, &mut run, Consumable::Spectral(Spectral::TheSoul), 1, &[]);
+    assert_eq!(err, Err(UseError::NoRoom));
+    assert!(rng.take_trace().is_empty());
+    assert_eq!(run.deck, before.deck);
+}
+
+#[test]
+fn card_changes_follow_the_selection() {
+    let mut rng = Random::new(b"ABCD1234");
+    let mut run = run_with_hand();
+    use_consumable(&mut rng, &mut run, Consumable::Tarot(Tarots::TheEmpress), 1, &[0, 1]).unwrap();
+    use_consumable(&mut rng, &mut run, Consumable::Tarot(Tarots::TheSun), 1, &[0]).unwrap();
+    use_consumable(&mut rng, &mut run, Consumable::Spectral(Spectral::Medium), 1, &[1]).unwrap();
+    run.hand.push(8);
+    use_consumable(&mut rng, &mut run, Consumable::Tarot(Tarots::Strength), 1, &[7, 8]).unwrap();
+    let (first, second) = (*run.card(0).unwrap(), *run.card(1).unwrap());
+    assert_eq!((first.enhancement, first.suit), (EnhancementTypes::Mult, CardSuits::Hearts));
+    assert_eq!((second.enhancement, second.seal), (EnhancementTypes::Mult, SealTypes::Purple));
+    // Cards 7 and 8 are the Nine and Ace of Clubs; Aces wrap around to Two.
+    assert_eq!((run.card(7).unwrap().rank, run.card(8).unwrap().rank), (CardTypes::Ten, CardTypes::Two));
+
+    // Death turns the left card into the right one; hand order, not sort_id, decides.
+    run.hand.swap(2, 5);
+    use_consumable(&mut rng, &mut run, Consumable::Tarot(Tarots::Death), 1, &[2, 5]).unwrap();
+    let (two, five) = (*run.card(2).unwrap(), *run.card(5).unwrap());
+    assert_eq!((two.rank, two.sort_id), (five.rank, 2));
+
+    use_consumable(&mut rng, &mut run, Consumable::Spectral(Spectral::Cryptid), 1, &[0]).unwrap();
+    assert_eq!(run.hand.len(), 11);
+    assert!(run.deck[run.deck.len() - 2..].iter().all(|c| c.sort_id >= 52 && c.suit == CardSuits::Hearts));
+
+    use_consumable(&mut rng, &mut run, Consumable::Tarot(Tarots::TheHangedMan), 1, &[3, 4]).unwrap();
+    assert!(run.card(3).is_none() && !run.hand.contains(&4));
+}
+
+#[test]
+fn fool_copies_the_last_tarot_or_planet() {
+    let mut rng = Random::new(b"ABCD1234");
+    let mut run = run_with_hand();
+    use_consumable(&mut rng, &mut run, Consumable::Planet(Planets::Mars), 1, &[]).unwrap();
+    use_consumable(&mut rng, &mut run, Consumable::Spectral(Spectral::BlackHole), 1, &[]).unwrap();
+    assert_eq!(run.hands.get(HandTypes::FourOfAKind).level, 3);
+    use_consumable(&mut rng, &mut run, Consumable::Tarot(Tarots::TheFool), 1, &[]).unwrap();
+    assert_eq!(run.consumables, [Consumable::Planet(Planets::Mars)]);
+    assert_eq!(run.last_tarot_planet, Some(Consumable::Planet(Planets::Mars)));
+}
+
+#[test]
+fn joker_effects_spare_eternal_jokers() {
+    for seed in ["ABCD1234", "7LB2WVPK", "HELLO1"] {
+        let mut rng = Random::new(seed.as_bytes());
+        let mut run = run_with_hand();
+        run.jokers = vec![joker(JokerTypes::Joker), eternal(JokerTypes::Blueprint), joker(JokerTypes::Baron)];
+        use_consumable(&mut rng, &mut run, Consumable::Spectral(Spectral::Hex), 1, &[]).unwrap();
+        assert!(run.jokers.iter().any(|j| j.joker == JokerTypes::Blueprint));
+        assert_eq!(run.jokers.iter().filter(|j| j.edition == Editions::Polychrome).count(), 1);
+
+        run.jokers = vec![joker(JokerTypes::Joker)];
+        run.jokers[0].edition = Editions::Negative;
+        use_consumable(&mut rng, &mut run, Consumable::Spectral(Spectral::Ankh), 1, &[]).unwrap();
+        assert_eq!(run.jokers.iter().map(|j| j.edition).collect::<Vec<_>>(), [Editions::Negative, Editions::None]);
+    }
+}
+
+#[test]
+fn money_and_hand_size_effects() {
+    let mut rng = Random::new(b"ABCD1234");
+    let mut run = run_with_hand();
+    run.dollars = 15;
+    use_consumable(&mut rng, &mut run, Consumable::Tarot(Tarots::TheHermit), 1, &[]).unwrap();
+    assert_eq!(run.dollars, 30);
+    use_consumable(&mut rng, &mut run, Consumable::Tarot(Tarots::TheHermit), 1, &[]).unwrap();
+    assert_eq!(run.dollars, 50);
+    use_consumable(&mut rng, &mut run, Consumable::Spectral(Spectral::Immolate), 1, &[]).unwrap();
+    assert_eq!((run.dollars, run.hand.len(), run.deck.len()), (70, 3, 47));
+
+    run.jokers = vec![joker(JokerTypes::Joker), joker(JokerTypes::Baron)];
+    use_consumable(&mut rng, &mut run, Consumable::Spectral(Spectral::Ectoplasm), 1, &[]).unwrap();
+    use_consumable(&mut rng, &mut run, Consumable::Spectral(Spectral::Ectoplasm), 1, &[]).unwrap();
+    assert_eq!(run.hand_size, 8 - 1 - 2);
+    assert!(run.jokers.iter().all(|j| j.edition == Editions::Negative));
+    assert_eq!(run.free_joker_slots(), 5);
+
+    use_consumable(&mut rng, &mut run, Consumable::Spectral(Spectral::Wraith), 1, &[]).unwrap();
+    assert_eq!((run.dollars, run.jokers[2].rarity), (0, JokerRarity::Rare));
+}
+
+#[test]
+fn game_state_uses_its_own_run() {
+    for seed in ["ABCD1234", "7LB2WVPK", "HELLO1"] {
+        let mut game = GameState::new(seed, 2);
+        game.run.hand = (0..8).collect();
+        let mut run = game.run.clone();
+        let mut rng = Random::new(seed.as_bytes());
+        for card in [Tarots::TheEmperor, Tarots::Judgement, Tarots::TheHighPriestess] {
+            run.consumables.clear();
+            game.run.consumables.clear();
+            game.use_consumable(Consumable::Tarot(card), &[]).unwrap();
+            use_consumable(&mut rng, &mut run, Consumable::Tarot(card), 2, &[]).unwrap();
+        }
+        assert_eq!(game.run, run);
+        assert_eq!(run.consumables.len(), 2);
+        assert!(run.consumables.iter().all(|c| matches!(c, Consumable::Planet(_))));
+    }
+}
@@ -0,0 +1,122 @@
+mod support;
+
+use common::filter::{Clause, Filter, ParseClauseError, SeedView};
+use common::game::GameState;
+use common::items::{Bosses, Editions, JokerTypes, RandomSource, Tags, Vouchers};
+use support::all;
+
+const SEEDS: &[&str] = &["AAAAAAAA", "1ABC23", "ZZZZ", "K9P2QX7", "B"];
+
+#[test]
+fn clauses_parse_and_display() {
+    assert_eq!("boss=The Wall".parse::<Clause>(), Ok(Clause::Boss { ante: 1, boss: Bosses::TheWall }));
+    assert_eq!(
+        "joker@2 = blueprint".parse::<Clause>(),
+        Ok(Clause::ShopJoker { ante: 2, joker: JokerTypes::Blueprint })
+    );
+    assert_eq!("Tag@3=double tag".parse::<Clause>(), Ok(Clause::Tag { ante: 3, tag: Tags::DoubleTag }));
+    let c: Clause = "voucher@1=Overstock".parse().unwrap();
+    assert_eq!(c, Clause::Voucher { ante: 1, voucher: Vouchers::Overstock });
+    assert_eq!(c.to_string(), "voucher@1=Overstock");
+    assert_eq!(c.to_string().parse::<Clause>(), Ok(c));
+}
+
+#[test]
+fn bad_clauses_are_rejected() {
+    assert!(matches!("The Wall".parse::<Clause>(), Err(ParseClauseError::Syntax(_))));
+    assert!(matches!("shop=Blueprint".parse::<Clause>(), Err(ParseClauseError::UnknownKind(_))));
+    assert!(matches!("boss@0=The Wall".parse::<Clause>(), Err(ParseClauseError::BadAnte(_))));
+    assert!(matches!("boss@x=The Wall".parse::<Clause>(), Err(ParseClauseError::BadAnte(_))));
+    assert!(matches!("joker=Not A Joker".parse::<Clause>(), Err(ParseClauseError::Name(_))));
+}
+
+#[test]
+fn seed_view_matches_game_state_draws() {
+    for seed in SEEDS {
+        let mut view = SeedView::new(seed);
+        let mut game = GameState::new(seed, 1);
+        assert_eq!(view.voucher(1), game.next_voucher_from_at_ante(RandomSource::Shop, 1), "{seed}");
+        assert_eq!(view.boss(1), Some(game.next_boss()), "{seed}");
+        let tags = [game.next_tag_from_at_ante(1), game.next_tag_from_at_ante(1)];
+        assert_eq!(view.tags(1), tags, "{seed}");
+        let shop = [game.next_shop_item_at_ante(1, false), game.next_shop_item_at_ante(1, false)];
+        assert_eq!(view.shop(1), shop, "{seed}");
+        assert_eq!(view.shop_jokers(1), shop.map(|item| item.joker().map(|j| j.joker)), "{seed}");
+    }
+}
+
+#[test]
+fn seed_view_caches_draws_regardless_of_order() {
+    for seed in SEEDS {
+        let mut forward = SeedView::new(seed);
+        let bosses: Vec<Option<Bosses>> = (1..=4).map(|a| forward.boss(a)).collect();
+        let tags: Vec<[Tags; 2]> = (1..=4).map(|a| forward.tags(a)).collect();
+
+        let mut backward = SeedView::new(seed);
+        assert_eq!(backward.tags(4), tags[3]);
+        assert_eq!(backward.boss(3), bosses[2]);
+        for a in 1..=4 {
+            assert_eq!(backward.boss(a), bosses[a as usize - 1], "{seed} ante {a}");
+            assert_eq!(backward.tags(a), tags[a as usize - 1], "{seed} ante {a}");
+        }
+    }
+}
+
+#[test]
+fn filter_is_conjunction_of_clauses() {
+    let seed = SEEDS[0];
+    let mut view = SeedView::new(seed);
+    let first = view.boss(1).unwrap();
+    let boss = Clause::Boss { ante: 1, boss: first };
+    let voucher = Clause::Voucher { ante: 1, voucher: view.voucher(1) };
+    let other = if first == Bosses::TheHook { Bosses::TheClub } else { Bosses::TheHook };
+    let wrong_boss = Clause::Boss { ante: 1, boss: other };
+
+    assert!(Filter::new(vec![]).matches_seed(seed));
+    assert!(Filter::new(vec![boss, voucher]).matches_seed(seed));
+    assert!(!Filter::new(vec![boss, wrong_boss]).matches_seed(seed));
+}
+
+#[test]
+fn clauses_round_trip_through_gpu_words() {
+    let mut clauses = Vec::new();
+    for ante in [1, 2, 8, 39] {
+        clauses.extend(all::<Bosses>().into_iter().map(|boss| Clause::Boss { ante, boss }));
+        clauses.extend(all::<Vouchers>().into_iter().map(|voucher| Clause::Voucher { ante, voucher }));
+        clauses.extend(all::<Tags>().into_iter().map(|tag| Clause::Tag { ante, tag }));
+        clauses.extend(all::<JokerTypes>().into_iter().map(|joker| Clause::ShopJoker { ante, joker }));
+        clauses.extend(all::<Editions>().into_iter().map(|edition| Clause::ShopEdition { ante, edition }));
+    }
+    for c in clauses {
+        assert_eq!(Clause::from_word(c.to_word()), Some(c));
+    }
+    assert_eq!(Clause::from_word(u32::MAX), None);
+}
+
+// The GPU filter decodes each clause from its word and checks it on a fresh
+// view per stage; the CPU checks every clause on one shared view, in
+// whatever order the stats pick, latest antes first here. Both must agree.
+#[test]
+fn clauses_agree_on_fresh_and_shared_views() {
+    for i in 0..200 {
+        let seed = format!("P{i}");
+        let mut clauses = Vec::new();
+        let mut view = SeedView::new(&seed);
+        for ante in 1..=3 {
+            clauses.extend(view.boss(ante).map(|boss| Clause::Boss { ante, boss }));
+            clauses.push(Clause::Voucher { ante, voucher: view.voucher(ante) });
+            for joker in view.shop_jokers(ante).into_iter().flatten() {
+                clauses.push(Clause::ShopJoker { ante, joker });
+            }
+            for edition in view.shop_editions(ante).into_iter().flatten() {
+                clauses.push(Clause::ShopEdition { ante, edition });
+            }
+            clauses.push(Clause::ShopJoker { ante, joker: JokerTypes::Blueprint });
+        }
+        let mut shared = SeedView::new(&seed);
+        for c in clauses.iter().rev() {
+            let gpu = Clause::from_word(c.to_word()).unwrap().count(&mut SeedView::new(&seed));
+            assert_eq!(c.count(&mut shared), gpu, "{seed} {c}");
+        }
+    }
+}
@@ -0,0 +1,65 @@
+mod support;
+
+use common::filter::SeedView;
+use common::items::Consumable;
+use common::names::ItemName;
+use common::shop::ShopItem;
+use std::fmt::Write;
+use std::fs;
+use support::{MORE_SEEDS, SEEDS};
+
+const ANTES: i32 = 3;
+
+// A first-shop slot by the name of its card.
+fn slot_name(item: ShopItem) -> &'static str {
+    match item {
+        ShopItem::Joker(j) => j.joker.name(),
+        ShopItem::Consumable(Consumable::Tarot(t)) => t.name(),
+        ShopItem::Consumable(Consumable::Planet(p)) => p.name(),
+        ShopItem::Consumable(Consumable::Spectral(s)) => s.name(),
+        ShopItem::Card(_) => "Playing Card",
+    }
+}
+
+// One line per seed and ante: the boss, voucher, skip tags and first-shop
+// cards `SeedView` draws.
+fn first_antes() -> String {
+    let mut out = String::new();
+    for seed in SEEDS.into_iter().chain(MORE_SEEDS) {
+        let mut view = SeedView::new(seed);
+        for ante in 1..=ANTES {
+            let [t1, t2] = view.tags(ante);
+            let [s1, s2] = view.shop(ante);
+            writeln!(
+                out,
+                "{seed}|{ante}|{}|{}|{},{}|{},{}",
+                view.boss(ante).expect("no bans").name(),
+                view.voucher(ante).name(),
+                t1.name(),
+                t2.name(),
+                slot_name(s1),
+                slot_name(s2)
+            )
+            .unwrap();
+        }
+    }
+    out
+}
+
+/// The draws are pinned in `fixtures/first_antes.txt`, so a change to the
+/// item tables, locks or stream ids shows up here. The file is this crate's
+/// own output, not taken from the game; regenerate it after an intended
+/// change with `UPDATE_FIXTURES=1`.
+#[test]
+fn draws_match_the_pinned_snapshot() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/first_antes.txt");
+    let drawn = first_antes();
+    if std::env::var_os("UPDATE_FIXTURES").is_some() {
+        fs::write(path, &drawn).expect("write fixture");
+    }
+    let pinned = fs::read_to_string(path).unwrap_or_else(|e| panic!("{path}: {e}"));
+    for (line, (got, want)) in drawn.lines().zip(pinned.lines()).enumerate() {
+        assert_eq!(got, want, "line {}", line + 1);
+    }
+    assert_eq!(drawn.lines().count(), pinned.lines().count());
+}
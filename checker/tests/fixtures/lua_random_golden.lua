@@ -0,0 +1,30 @@
+-- Golden vectors for checker/tests/lua_random.rs. Regenerate with
+--   luajit lua_random_golden.lua > lua_random_golden.txt
+-- using LuaJIT 2.1, as bundled with LOVE 11.
+
+local state = 54321
+local function lcg()
+  state = (state * 1103515245 + 12345) % 2147483648
+  return state
+end
+local out = {}
+out[#out+1] = "# Generated by lua_random_golden.lua with LuaJIT 2.1."
+out[#out+1] = "# random SEED four math.random() draws after math.randomseed(SEED)"
+out[#out+1] = "# randint SEED MIN MAX math.random(MIN, MAX) after math.randomseed(SEED)"
+local f = "%.17g"
+local seeds = {0, 0.5, 1e-300, -0.25, 12345.678}
+while #seeds < 1000 do
+  seeds[#seeds+1] = (lcg() * 2147483648 + lcg()) / 4611686018427387904
+end
+for _, seed in ipairs(seeds) do
+  math.randomseed(seed)
+  out[#out+1] = string.format("random "..f.." "..f.." "..f.." "..f.." "..f, seed,
+    math.random(), math.random(), math.random(), math.random())
+end
+for _, seed in ipairs(seeds) do
+  local min = lcg() % 5
+  local max = min + lcg() % 200
+  math.randomseed(seed)
+  out[#out+1] = string.format("randint "..f.." %d %d %d", seed, min, max, math.random(min, max))
+end
+io.write(table.concat(out, "\n").."\n")
@@ -0,0 +1,63 @@
+-- Golden vectors for checker/tests/pseudohash.rs. Regenerate with
+--   luajit pseudohash_golden.lua > pseudohash_golden.txt
+-- using LuaJIT 2.1, as bundled with LOVE 11.
+
+-- From the game's functions/misc_functions.lua
+function pseudohash(str)
+  local num = 1
+  for i=#str, 1, -1 do
+    num = ((1.1239285023/num)*string.byte(str, i)*math.pi + math.pi*i)%1
+  end
+  return num
+end
+local G = {}
+function pseudoseed(key)
+  if not G.pseudorandom[key] then
+    G.pseudorandom[key] = pseudohash(key..(G.pseudorandom.seed or ''))
+  end
+  G.pseudorandom[key] = math.abs(tonumber(string.format("%.13f", (2.134453429141+G.pseudorandom[key]*1.72431234)%1)))
+  return (G.pseudorandom[key] + (G.pseudorandom.hashed_seed or 0))/2
+end
+local charset = "ABCDEFGHIJKLMNOPQRSTUVWXYZ123456789"
+local state = 12345
+local function lcg()
+  state = (state * 1103515245 + 12345) % 2147483648
+  return state
+end
+local out = {}
+out[#out+1] = "# Generated by pseudohash_golden.lua with LuaJIT 2.1."
+out[#out+1] = "# hash SEED pseudohash(SEED) pseudoseed('boss') pseudoseed('boss') pseudoseed('Voucher1') pseudoseed('Joker1sho1')"
+out[#out+1] = "# round13 X tonumber(string.format('%.13f', X))"
+local seeds = {"1", "A", "ABCDEFG0", "HELLO1", "ZZZZZZZZ", "11111111"}
+while #seeds < 2000 do
+  local len = 1 + lcg() % 8
+  if lcg() % 4 ~= 0 then len = 8 end
+  local s = {}
+  for i = 1, len do
+    local c = 1 + lcg() % 35
+    s[i] = charset:sub(c, c)
+  end
+  seeds[#seeds+1] = table.concat(s)
+end
+local f = "%.17g"
+for _, seed in ipairs(seeds) do
+  G.pseudorandom = {seed = seed, hashed_seed = pseudohash(seed)}
+  out[#out+1] = string.format("hash %s "..f.." "..f.." "..f.." "..f.." "..f, seed, G.pseudorandom.hashed_seed,
+    pseudoseed('boss'), pseudoseed('boss'), pseudoseed('Voucher1'), pseudoseed('Joker1sho1'))
+end
+local function r13(x) return tonumber(string.format("%.13f", x)) end
+local xs = {}
+-- exact decimal ties: j/16384 * 1e13 ends in .5 for odd j
+for j = 1, 399, 2 do xs[#xs+1] = j/16384 end
+-- doubles nearest to a tie, just above or below it
+for n = 1, 600 do
+  local k = lcg() * 4657 + lcg() % 4657
+  xs[#xs+1] = (k + 0.5)/1e13
+end
+for n = 1, 1200 do
+  xs[#xs+1] = (lcg() * 2147483648 + lcg()) / 4611686018427387904
+end
+for _, x in ipairs(xs) do
+  out[#out+1] = string.format("round13 "..f.." "..f, x, r13(x))
+end
+io.write(table.concat(out, "\n").."\n")
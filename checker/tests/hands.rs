@@ -0,0 +1,82 @@
+mod support;
+
+use common::game::GameState;
+use common::hands::{orbital_hand, HandLevels};
+use common::items::{HandTypes, Planets, RandomSource};
+use common::random::Random;
+use common::round::to_do_order;
+use support::{all, SEEDS};
+
+#[test]
+fn planets_level_their_hand() {
+    let mut hands = HandLevels::new();
+    assert_eq!((hands.get(HandTypes::Pair).chips, hands.get(HandTypes::Pair).mult), (10, 2));
+    hands.use_planet(Planets::Mercury);
+    hands.use_planet(Planets::Mercury);
+    let pair = hands.get(HandTypes::Pair);
+    assert_eq!((pair.level, pair.chips, pair.mult), (3, 40, 4));
+    for planet in all::<Planets>() {
+        hands.use_planet(planet);
+    }
+    assert_eq!(hands.get(HandTypes::FlushFive).level, 2);
+    assert_eq!(hands.satellite_dollars(), 12);
+    assert_eq!(hands.planets_used().len(), 14);
+
+    // Losing levels stops at level 0 and keeps mult at 1.
+    hands.level_up(HandTypes::HighCard, -5);
+    let high = hands.get(HandTypes::HighCard);
+    assert_eq!((high.level, high.chips, high.mult), (0, 0, 1));
+}
+
+#[test]
+fn black_hole_feeds_neither_satellite_nor_constellation() {
+    let mut hands = HandLevels::new();
+    hands.use_black_hole();
+    assert!(all::<HandTypes>().into_iter().all(|h| hands.get(h).level == 2));
+    assert_eq!(hands.satellite_dollars(), 0);
+    assert_eq!(hands.constellation_xmult(0), 1.0);
+    hands.use_planet(Planets::Pluto);
+    hands.use_planet(Planets::Pluto);
+    hands.use_planet(Planets::Mars);
+    assert_eq!(hands.satellite_dollars(), 2);
+    assert_eq!(hands.constellation_xmult(0), 1.0 + 0.1 + 0.1 + 0.1);
+    assert_eq!(hands.constellation_xmult(2), 1.1);
+    assert_eq!(hands.constellation_xmult(9), 1.0);
+}
+
+#[test]
+fn secret_planets_wait_for_their_hand() {
+    for seed in SEEDS {
+        let mut game = GameState::new(seed, 1);
+        for _ in 0..200 {
+            let planet = game.next_planet_from_at_ante(RandomSource::Shop, 1);
+            assert!(!matches!(planet, Planets::PlanetX | Planets::Ceres | Planets::Eris), "seed {seed}");
+        }
+    }
+    let mut hands = HandLevels::new();
+    assert!(!hands.planet_available(Planets::Eris));
+    hands.play(HandTypes::FlushFive);
+    assert!(hands.planet_available(Planets::Eris));
+    assert!(hands.get(HandTypes::FlushFive).visible);
+    assert!(!hands.planet_available(Planets::Ceres));
+}
+
+#[test]
+fn orbital_picks_visible_hands() {
+    for seed in SEEDS {
+        let mut rng = Random::new(seed.as_bytes());
+        rng.enable_trace();
+        let mut hands = HandLevels::new();
+        for _ in 0..30 {
+            assert!(!orbital_hand(&mut rng, &hands, &to_do_order(3)).is_secret());
+        }
+        hands.play(HandTypes::FiveOfAKind);
+        let mut game = GameState::new(seed, 1);
+        game.run.hands = hands.clone();
+        let mut again = Random::new(seed.as_bytes());
+        for _ in 0..30 {
+            assert_eq!(game.next_orbital_hand(&to_do_order(7)), orbital_hand(&mut again, &hands, &to_do_order(7)));
+        }
+        assert!(rng.take_trace().iter().all(|e| e.id == "orbital"));
+    }
+}
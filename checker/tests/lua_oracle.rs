@@ -0,0 +1,1143 @@
+//! Differential tests against the game's own seeding code running in an
+//! embedded LuaJIT, the interpreter the game ships with. Run with
+//!
+//! ```bash
+//! cargo test --no-default-features --features lua-oracle --test lua_oracle
+//! ```
+//!
+//! Each test fuzzes seeds and keys and panics at the first value that differs,
+//! naming the seed, key and draw so it can be replayed by hand.
+#![cfg(feature = "lua-oracle")]
+
+use common::blind::BlindRound;
+use common::cards::{familiar_cards, standard_pack_card, starting_deck};
+use common::consumables::use_consumable;
+use common::filter::{SeedView, SHOP_SLOTS};
+use common::game::GameState;
+use common::hands::orbital_hand;
+use common::items::{
+    Bosses, Card, CardSuits, CardTypes, Consumable, Editions, EnhancementTypes, HandTypes, Joker, JokerRarity, JokerTypes,
+    RandomSource, SealTypes, Spectral, Tarots,
+};
+use common::names::ItemName;
+use common::odds::{roll, wheel_of_fortune, Probabilities, Roll};
+use common::round::{roll_round_targets, roll_to_do_hand};
+use common::pools::{COMMON_JOKERS, LEGENDARY_JOKERS, RARE_JOKERS, UNCOMMON_JOKERS};
+use common::random::Random;
+use common::run::RunState;
+use common::shop::ShopItem;
+use common::util::LuaRandom;
+use mlua::{Function, Lua};
+
+const SEEDS: usize = 2000;
+const DRAWS: usize = 3;
+
+// functions/misc_functions.lua, with G.GAME.pseudorandom reset per seed.
+const GAME_LUA: &str = r#"
+G = { GAME = {} }
+
+function pseudohash(str)
+  local num = 1
+  for i=#str, 1, -1 do
+    num = ((1.1239285023/num)*string.byte(str, i)*math.pi + math.pi*i)%1
+  end
+  return num
+end
+
+function pseudoseed(key)
+  if not G.GAME.pseudorandom[key] then
+    G.GAME.pseudorandom[key] = pseudohash(key..(G.GAME.pseudorandom.seed or ''))
+  end
+  G.GAME.pseudorandom[key] = math.abs(tonumber(string.format("%.13f", (2.134453429141+G.GAME.pseudorandom[key]*1.72431234)%1)))
+  return (G.GAME.pseudorandom[key] + (G.GAME.pseudorandom.hashed_seed or 0))/2
+end
+
+function pseudorandom(seed, min, max)
+  if type(seed) == 'string' then seed = pseudoseed(seed) end
+  math.randomseed(seed)
+  if min and max then return math.random(min, max)
+  else return math.random() end
+end
+
+function pseudorandom_element(_t, seed)
+  if seed then math.randomseed(seed) end
+  local keys = {}
+  for k, v in pairs(_t) do
+    keys[#keys+1] = {k = k,v = v}
+  end
+  if keys[1] and keys[1].v and type(keys[1].v) == 'table' and keys[1].v.sort_id then
+    table.sort(keys, function (a, b) return a.v.sort_id < b.v.sort_id end)
+  else
+    table.sort(keys, function (a, b) return a.k < b.k end)
+  end
+  local key = keys[math.random(#keys)].k
+  return _t[key], key
+end
+
+function start_run(seed)
+  G.GAME.pseudorandom = { seed = seed, hashed_seed = pseudohash(seed) }
+  G.GAME.edition_rate = 1
+end
+
+-- Card creation from card.lua and common_events.lua, reduced to the draws.
+P_CARDS = {}
+for _, s in ipairs({'H', 'C', 'D', 'S'}) do
+  for _, r in ipairs({'2', '3', '4', '5', '6', '7', '8', '9', 'T', 'J', 'Q', 'K', 'A'}) do
+    P_CARDS[s..'_'..r] = true
+  end
+end
+ENHANCED = {'m_bonus', 'm_mult', 'm_wild', 'm_glass', 'm_steel', 'm_stone', 'm_gold', 'm_lucky'}
+
+function poll_edition(_key, _mod, _no_neg, _guaranteed)
+  _mod = _mod or 1
+  local edition_poll = pseudorandom(pseudoseed(_key))
+  if _guaranteed then
+    if edition_poll > 1 - 0.003*25 and not _no_neg then return 'negative'
+    elseif edition_poll > 1 - 0.006*25 then return 'polychrome'
+    elseif edition_poll > 1 - 0.02*25 then return 'holo'
+    elseif edition_poll > 1 - 0.04*25 then return 'foil'
+    end
+  elseif edition_poll > 1 - 0.003*_mod and not _no_neg then return 'negative'
+  elseif edition_poll > 1 - 0.006*G.GAME.edition_rate*_mod then return 'polychrome'
+  elseif edition_poll > 1 - 0.02*G.GAME.edition_rate*_mod then return 'holo'
+  elseif edition_poll > 1 - 0.04*G.GAME.edition_rate*_mod then return 'foil'
+  end
+  return 'none'
+end
+
+function standard_card(ante)
+  local center = 'c_base'
+  if pseudorandom(pseudoseed('stdset'..ante)) > 0.6 then
+    center = pseudorandom_element(ENHANCED, pseudoseed('Enhancedsta'..ante))
+  end
+  local _, front = pseudorandom_element(P_CARDS, pseudoseed('frontsta'..ante))
+  local edition = poll_edition('standard_edition'..ante, 2, true)
+  local seal = 'none'
+  if pseudorandom(pseudoseed('stdseal'..ante)) > 1 - 0.02*10 then
+    local seal_type = pseudorandom(pseudoseed('stdsealtype'..ante))
+    if seal_type > 0.75 then seal = 'Red'
+    elseif seal_type > 0.5 then seal = 'Blue'
+    elseif seal_type > 0.25 then seal = 'Gold'
+    else seal = 'Purple'
+    end
+  end
+  return center..' '..front..' '..edition..' '..seal
+end
+
+-- The starting deck in start_run's order, every fifth card made Stone, and
+-- listed backwards so only sort_id gives the order.
+function starting_deck()
+  local protos = {}
+  for k, v in pairs(P_CARDS) do protos[#protos+1] = {s = string.sub(k, 1, 1), r = string.sub(k, 3, 3)} end
+  table.sort(protos, function (a, b) return a.s..a.r < b.s..b.r end)
+  local deck = {}
+  for i, v in ipairs(protos) do
+    deck[#protos + 1 - i] = {sort_id = i, suit = v.s, rank = v.r, stone = i % 5 == 0}
+  end
+  return deck
+end
+
+-- reset_idol_card, reset_mail_rank, reset_ancient_card and reset_castle_card.
+function round_targets(ante, previous_ancient)
+  local valid = {}
+  for _, v in ipairs(starting_deck()) do
+    if not v.stone then valid[#valid+1] = v end
+  end
+  local idol = pseudorandom_element(valid, pseudoseed('idol'..ante))
+  local mail = pseudorandom_element(valid, pseudoseed('mail'..ante))
+  local ancient_suits = {}
+  for k, v in ipairs({'Spades','Hearts','Clubs','Diamonds'}) do
+    if v ~= previous_ancient then ancient_suits[#ancient_suits + 1] = v end
+  end
+  local ancient = pseudorandom_element(ancient_suits, pseudoseed('anc'..ante))
+  local castle = pseudorandom_element(valid, pseudoseed('cas'..ante))
+  return idol.suit..'_'..idol.rank..' '..mail.rank..' '..ancient..' '..castle.suit
+end
+
+-- G.GAME.hands as init_game_object builds it, for To Do List.
+HANDS = {
+  ["Flush Five"] = {visible = false, order = 1},
+  ["Flush House"] = {visible = false, order = 2},
+  ["Five of a Kind"] = {visible = false, order = 3},
+  ["Straight Flush"] = {visible = true, order = 4},
+  ["Four of a Kind"] = {visible = true, order = 5},
+  ["Full House"] = {visible = true, order = 6},
+  ["Flush"] = {visible = true, order = 7},
+  ["Straight"] = {visible = true, order = 8},
+  ["Three of a Kind"] = {visible = true, order = 9},
+  ["Two Pair"] = {visible = true, order = 10},
+  ["Pair"] = {visible = true, order = 11},
+  ["High Card"] = {visible = true, order = 12},
+}
+
+function hands_order()
+  local out = {}
+  for k, v in pairs(HANDS) do out[#out+1] = k end
+  return table.concat(out, ',')
+end
+
+function to_do(current)
+  local _poker_hands = {}
+  for k, v in pairs(HANDS) do
+    if v.visible and k ~= current then _poker_hands[#_poker_hands+1] = k end
+  end
+  return pseudorandom_element(_poker_hands, pseudoseed('to_do'))
+end
+
+function reset_hands()
+  for k, v in pairs(HANDS) do
+    v.played = 0
+    v.visible = v.order > 3
+  end
+end
+
+function play_hand(name)
+  HANDS[name].played = HANDS[name].played + 1
+  HANDS[name].visible = true
+end
+
+-- create_UIBox_blind_choice.
+function orbital()
+  local _poker_hands = {}
+  for k, v in pairs(HANDS) do
+    if v.visible then _poker_hands[#_poker_hands+1] = k end
+  end
+  return pseudorandom_element(_poker_hands, pseudoseed('orbital'))
+end
+
+-- create_card('Planet', ...) with get_current_pool's softlocked planets.
+PLANETS = {'c_mercury', 'c_venus', 'c_earth', 'c_mars', 'c_jupiter', 'c_saturn', 'c_uranus', 'c_neptune', 'c_pluto',
+  'c_planet_x', 'c_ceres', 'c_eris'}
+SOFTLOCK = {c_planet_x = 'Five of a Kind', c_ceres = 'Flush House', c_eris = 'Flush Five'}
+
+function shop_planet(ante)
+  local _pool = {}
+  for i, v in ipairs(PLANETS) do
+    if SOFTLOCK[v] and HANDS[SOFTLOCK[v]].played == 0 then _pool[i] = 'UNAVAILABLE' else _pool[i] = v end
+  end
+  local _pool_key = 'Planetsho'..ante
+  local center = pseudorandom_element(_pool, pseudoseed(_pool_key))
+  local it = 1
+  while center == 'UNAVAILABLE' do
+    it = it + 1
+    center = pseudorandom_element(_pool, pseudoseed(_pool_key..'_resample'..it))
+  end
+  return center
+end
+
+-- create_card for a pack's Tarot, Planet or Spectral card: the rolls for The
+-- Soul and Black Hole, then the pool, where Planet X, Ceres and Eris wait for
+-- their hand and The Soul and Black Hole never show.
+POOL_SIZES = {Tarot = 22, Planet = 12, Spectral = 18}
+
+function pack_card(_type, append, ante)
+  local forced_key
+  if _type == 'Tarot' or _type == 'Spectral' then
+    if pseudorandom('soul_'.._type..ante) > 0.997 then forced_key = 'c_soul' end
+  end
+  if _type == 'Planet' or _type == 'Spectral' then
+    if pseudorandom('soul_'.._type..ante) > 0.997 then forced_key = 'c_black_hole' end
+  end
+  if forced_key then return forced_key end
+  local pool = {}
+  for i = 1, POOL_SIZES[_type] do pool[i] = 'c_'..i end
+  if _type == 'Planet' then
+    for i, v in ipairs(PLANETS) do
+      if SOFTLOCK[v] and HANDS[SOFTLOCK[v]].played == 0 then pool[i] = 'UNAVAILABLE' end
+    end
+  elseif _type == 'Spectral' then
+    pool[17], pool[18] = 'UNAVAILABLE', 'UNAVAILABLE'
+  end
+  return 'c_'..pool_draw(pool, _type..append..ante)
+end
+
+function familiar_cards()
+  local out = {}
+  for i = 1, 3 do
+    local _rank = pseudorandom_element({'J', 'Q', 'K'}, pseudoseed('familiar_create'))
+    local _suit = pseudorandom_element({'S','H','D','C'}, pseudoseed('familiar_create'))
+    local cen_pool = {}
+    for k, v in pairs(ENHANCED) do
+      if v ~= 'm_stone' then cen_pool[#cen_pool+1] = v end
+    end
+    out[#out+1] = pseudorandom_element(cen_pool, pseudoseed('spe_card'))..' '.._suit..'_'.._rank..' none none'
+  end
+  return table.concat(out, ' ')
+end
+
+-- Chance rolls from card.lua, G.GAME.probabilities.normal given per call.
+function chance(key, normal, odds)
+  return pseudorandom(key) < normal/odds
+end
+
+-- The Wheel of Fortune in use_consumeable, over `eligible` jokers without an edition.
+function wheel_of_fortune(normal, eligible)
+  if pseudorandom('wheel_of_fortune') < normal/4 then
+    local pool = {}
+    for i = 1, eligible do pool[i] = {sort_id = eligible + 1 - i} end
+    local joker = pseudorandom_element(pool, pseudoseed('wheel_of_fortune'))
+    return (joker.sort_id - 1)..' '..poll_edition('wheel_of_fortune', nil, true, true)
+  end
+  return 'miss'
+end
+
+-- create_card('Joker', ...) reduced to its draws: rarity, pool index, edition.
+JOKER_POOL_SIZES = {61, 64, 20, 5}
+
+-- pseudorandom_element over a pool from get_current_pool, resampling with
+-- `_resample2`, `_resample3`, ... past its 'UNAVAILABLE' entries.
+function pool_draw(pool, key)
+  local center, index = pseudorandom_element(pool, pseudoseed(key))
+  local it = 1
+  while center == 'UNAVAILABLE' do
+    it = it + 1
+    center, index = pseudorandom_element(pool, pseudoseed(key..'_resample'..it))
+  end
+  return index
+end
+
+-- `unavailable` lists the pool entries left out as ',rarity_index,' keys.
+-- Rarity 4 is The Soul's legendary, from one stream for the whole run.
+function create_joker(append, _rarity, ante, unavailable)
+  local rarity = _rarity or pseudorandom('rarity'..ante..append)
+  if rarity ~= 4 then rarity = (rarity > 0.95 and 3) or (rarity > 0.7 and 2) or 1 end
+  local pool = {}
+  for i = 1, JOKER_POOL_SIZES[rarity] do
+    local key = rarity..'_'..i
+    pool[i] = string.find(unavailable, ','..key..',', 1, true) and 'UNAVAILABLE' or key
+  end
+  local index = pool_draw(pool, rarity == 4 and 'Joker4' or 'Joker'..rarity..append..ante)
+  return rarity..' '..index..' '..poll_edition('edi'..append..ante)
+end
+
+-- Consumable effects from use_consumeable, over `n` cards or jokers with
+-- sort_id 1 to n, listed backwards.
+function sort_id_list(n)
+  local list = {}
+  for i = 1, n do list[i] = {sort_id = n + 1 - i} end
+  return list
+end
+
+function pick(key, n)
+  return pseudorandom_element(sort_id_list(n), pseudoseed(key)).sort_id
+end
+
+function aura()
+  return poll_edition('aura', nil, true, true)
+end
+
+function sigil()
+  return pseudorandom_element({'S','H','D','C'}, pseudoseed('sigil'))
+end
+
+function ouija()
+  return pseudorandom_element({'2','3','4','5','6','7','8','9','T','J','Q','K','A'}, pseudoseed('ouija'))
+end
+
+function pseudoshuffle(list, seed)
+  if seed then math.randomseed(seed) end
+  if list[1] and list[1].sort_id then
+    table.sort(list, function (a, b) return (a.sort_id or 1) < (b.sort_id or 2) end)
+  end
+  for i = #list, 2, -1 do
+    local j = math.random(i)
+    list[i], list[j] = list[j], list[i]
+  end
+end
+
+function immolate(n)
+  local temp_hand = sort_id_list(n)
+  pseudoshuffle(temp_hand, pseudoseed('immolate'))
+  local destroyed = {}
+  for i = 1, math.min(5, n) do destroyed[i] = temp_hand[i].sort_id end
+  table.sort(destroyed)
+  return table.concat(destroyed, ',')
+end
+
+-- A round against `boss` on a 52-card deck (sort_ids 1 to 52) with
+-- `jokers` jokers (sort_ids 1 up): draw, then `plays` times play the forced
+-- card and the first held cards, `play_size` in all, and draw again. Logs the
+-- seeded picks: `f` a card drawn face down, `b` Cerulean Bell's card, `c`
+-- Crimson Heart's joker and `h` a card The Hook discards.
+function blind_round(boss, ante, hand_size, jokers, plays, play_size, normal)
+  local deck = sort_id_list(52)
+  pseudoshuffle(deck, pseudoseed('nr'..ante))
+  local J = sort_id_list(jokers)
+  local hand, log, forced, prepped = {}, {}, nil, false
+  local function remove(card)
+    for i, v in ipairs(hand) do
+      if v == card then table.remove(hand, i) return end
+    end
+  end
+  local function held(card)
+    for _, v in ipairs(hand) do
+      if v == card then return true end
+    end
+  end
+  local function draw()
+    for i = 1, math.min(#deck, hand_size - #hand) do
+      local card = table.remove(deck)
+      hand[#hand + 1] = card
+      if boss == 'The Wheel' and pseudorandom(pseudoseed('wheel')) < normal / 7 then
+        log[#log + 1] = 'f'..card.sort_id
+      end
+    end
+    if boss == 'Cerulean Bell' and not (forced and held(forced)) and hand[1] then
+      forced = pseudorandom_element(hand, pseudoseed('cerulean_bell'))
+      log[#log + 1] = 'b'..forced.sort_id
+    end
+    if boss == 'Crimson Heart' and prepped and J[1] then
+      local choices = {}
+      for i = 1, #J do
+        if not J[i].debuff or #J < 2 then choices[#choices + 1] = J[i] end
+        J[i].debuff = false
+      end
+      local joker = pseudorandom_element(choices, pseudoseed('crimson_heart'))
+      joker.debuff = true
+      log[#log + 1] = 'c'..joker.sort_id
+    end
+    prepped = false
+  end
+  draw()
+  for _ = 1, plays do
+    local played = {}
+    if forced and held(forced) then played[1] = forced end
+    for _, v in ipairs(hand) do
+      if #played < play_size and v ~= played[1] then played[#played + 1] = v end
+    end
+    for _, v in ipairs(played) do remove(v) end
+    prepped = true
+    if boss == 'The Hook' then
+      local _cards, hooked = {}, {}
+      for k, v in ipairs(hand) do _cards[k] = v end
+      for i = 1, 2 do
+        if hand[i] then
+          local card, key = pseudorandom_element(_cards, pseudoseed('hook'))
+          table.remove(_cards, key)
+          hooked[#hooked + 1] = card
+          log[#log + 1] = 'h'..card.sort_id
+        end
+      end
+      for _, v in ipairs(hooked) do remove(v) end
+    end
+    draw()
+  end
+  return table.concat(log, ' ')
+end
+
+function emperor(ante)
+  local pool = {}
+  for i = 1, 22 do pool[i] = 'c_'..i end
+  -- The Emperor being used and the cards it made hold their keys.
+  pool[5] = 'UNAVAILABLE'
+  local out = {}
+  for i = 1, 2 do
+    local index = pool_draw(pool, 'Tarotemp'..ante)
+    out[i] = index
+    pool[index] = 'UNAVAILABLE'
+  end
+  return table.concat(out, ',')
+end
+
+-- create_card_for_shop at the base rates (Joker 20, Tarot 4, Planet 4) for
+-- `n` slots. Each card's key stays used for the slots after it.
+function shop_slots(ante, n, unavailable)
+  local used, out = {}, {}
+  for s = 1, n do
+    local polled = pseudorandom(pseudoseed('cdt'..ante))*28
+    if polled <= 20 then
+      local joker = create_joker('sho', nil, ante, unavailable)
+      local rarity, index = string.match(joker, '(%d+) (%d+)')
+      unavailable = unavailable..rarity..'_'..index..','
+      out[s] = 'Joker '..joker
+    else
+      local set, size = 'Tarot', 22
+      if polled > 24 then set, size = 'Planet', 12 end
+      local pool = {}
+      for i = 1, size do
+        local softlocked = set == 'Planet' and SOFTLOCK[PLANETS[i]] and HANDS[SOFTLOCK[PLANETS[i]]].played == 0
+        pool[i] = (used[set..i] or softlocked) and 'UNAVAILABLE' or set..i
+      end
+      local index = pool_draw(pool, set..'sho'..ante)
+      used[set..index] = true
+      out[s] = set..' '..index
+    end
+  end
+  return table.concat(out, '|')
+end
+
+function random_sequence(seed, n)
+  math.randomseed(seed)
+  local out = {}
+  for i = 1, n do out[i] = math.random() end
+  return out
+end
+"#;
+
+// Keys in the shapes the game uses, plus resample suffixes.
+const KEYS: [&str; 10] = [
+    "boss", "Voucher1", "Tag1", "Joker1sho1", "edi1sho1", "cdt1", "shop_pack1", "Tarotsho1", "Joker2sho1_resample2",
+    "soul_Tarot1",
+];
+const CHARSET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ123456789";
+
+struct Oracle {
+    lua: Lua,
+}
+
+impl Oracle {
+    fn new() -> Self {
+        let lua = Lua::new();
+        lua.load(GAME_LUA).exec().expect("load game functions");
+        Self { lua }
+    }
+
+    fn function(&self, name: &str) -> Function<'_> {
+        self.lua.globals().get(name).expect("oracle function")
+    }
+
+    fn start_run(&self, seed: &str) {
+        self.function("start_run").call::<_, ()>(seed).expect("start_run");
+    }
+
+    fn pseudoseed(&self, key: &str) -> f64 {
+        self.function("pseudoseed").call(key).expect("pseudoseed")
+    }
+
+    fn pseudorandom(&self, key: &str) -> f64 {
+        self.function("pseudorandom").call(key).expect("pseudorandom")
+    }
+
+    fn pseudorandom_int(&self, key: &str, min: i32, max: i32) -> i32 {
+        self.function("pseudorandom").call::<_, f64>((key, min, max)).expect("pseudorandom") as i32
+    }
+
+    fn standard_card(&self, ante: i32) -> String {
+        self.function("standard_card").call(ante).expect("standard_card")
+    }
+
+    fn familiar_cards(&self) -> String {
+        self.function("familiar_cards").call(()).expect("familiar_cards")
+    }
+
+    fn round_targets(&self, ante: i32, previous_ancient: Option<&str>) -> String {
+        self.function("round_targets").call((ante, previous_ancient)).expect("round_targets")
+    }
+
+    fn hands_order(&self) -> String {
+        self.function("hands_order").call(()).expect("hands_order")
+    }
+
+    fn to_do(&self, current: Option<&str>) -> String {
+        self.function("to_do").call(current).expect("to_do")
+    }
+
+    fn chance(&self, key: &str, normal: f64, odds: u32) -> bool {
+        self.function("chance").call((key, normal, odds)).expect("chance")
+    }
+
+    fn wheel_of_fortune(&self, normal: f64, eligible: usize) -> String {
+        self.function("wheel_of_fortune").call((normal, eligible)).expect("wheel_of_fortune")
+    }
+
+    fn reset_hands(&self) {
+        self.function("reset_hands").call::<_, ()>(()).expect("reset_hands");
+    }
+
+    fn play_hand(&self, hand: &str) {
+        self.function("play_hand").call::<_, ()>(hand).expect("play_hand");
+    }
+
+    fn orbital(&self) -> String {
+        self.function("orbital").call(()).expect("orbital")
+    }
+
+    fn shop_planet(&self, ante: i32) -> String {
+        self.function("shop_planet").call(ante).expect("shop_planet")
+    }
+
+    fn create_joker(&self, append: &str, rarity: Option<f64>, ante: i32, unavailable: &str) -> String {
+        self.function("create_joker").call((append, rarity, ante, unavailable)).expect("create_joker")
+    }
+
+    fn pack_card(&self, set: &str, append: &str, ante: i32) -> String {
+        self.function("pack_card").call((set, append, ante)).expect("pack_card")
+    }
+
+    fn pick(&self, key: &str, n: usize) -> usize {
+        self.function("pick").call((key, n)).expect("pick")
+    }
+
+    fn aura(&self) -> String {
+        self.function("aura").call(()).expect("aura")
+    }
+
+    fn sigil(&self) -> String {
+        self.function("sigil").call(()).expect("sigil")
+    }
+
+    fn ouija(&self) -> String {
+        self.function("ouija").call(()).expect("ouija")
+    }
+
+    fn immolate(&self, n: usize) -> String {
+        self.function("immolate").call(n).expect("immolate")
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn blind_round(
+        &self,
+        boss: &str,
+        ante: i32,
+        hand_size: i32,
+        jokers: usize,
+        plays: usize,
+        play_size: usize,
+        normal: f64,
+    ) -> String {
+        let args = (boss, ante, hand_size, jokers, plays, play_size, normal);
+        self.function("blind_round").call(args).expect("blind_round")
+    }
+
+    fn emperor(&self, ante: i32) -> String {
+        self.function("emperor").call(ante).expect("emperor")
+    }
+
+    fn shop_slots(&self, ante: i32, n: usize, unavailable: &str) -> String {
+        self.function("shop_slots").call((ante, n, unavailable)).expect("shop_slots")
+    }
+
+    fn random_sequence(&self, seed: f64, n: usize) -> Vec<f64> {
+        self.function("random_sequence").call((seed, n)).expect("random_sequence")
+    }
+}
+
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        self.0 >> 11
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn seed(&mut self) -> String {
+        let len = 1 + self.below(8);
+        (0..len).map(|_| CHARSET[self.below(CHARSET.len())] as char).collect()
+    }
+
+    fn key(&mut self) -> String {
+        if self.below(4) == 0 {
+            // Arbitrary printable keys, to cover bytes the game's keys never use.
+            let len = 1 + self.below(16);
+            (0..len).map(|_| (b' ' + self.below(95) as u8) as char).collect()
+        } else {
+            String::from(KEYS[self.below(KEYS.len())])
+        }
+    }
+}
+
+#[track_caller]
+fn same(what: &str, seed: &str, key: &str, draw: usize, ours: f64, lua: f64) {
+    assert!(
+        ours.to_bits() == lua.to_bits(),
+        "first divergence in {what}: seed {seed:?} key {key:?} draw {draw}: ours {ours:?} ({:#018x}), lua {lua:?} ({:#018x})",
+        ours.to_bits(),
+        lua.to_bits()
+    );
+}
+
+#[test]
+fn pseudoseed_matches_lua() {
+    let oracle = Oracle::new();
+    let mut rng = Lcg(1);
+    for _ in 0..SEEDS {
+        let seed = rng.seed();
+        oracle.start_run(&seed);
+        let mut ours = Random::new(seed.as_bytes());
+        // Interleave keys so each stream is advanced between draws of the others.
+        let keys: Vec<String> = (0..4).map(|_| rng.key()).collect();
+        for draw in 0..DRAWS {
+            for key in &keys {
+                same("pseudoseed", &seed, key, draw, ours.get_node_str(key), oracle.pseudoseed(key));
+            }
+        }
+    }
+}
+
+#[test]
+fn math_random_matches_lua() {
+    let oracle = Oracle::new();
+    let mut rng = Lcg(2);
+    for _ in 0..SEEDS {
+        let seed = rng.next() as f64 / (1u64 << 53) as f64;
+        let expected = oracle.random_sequence(seed, DRAWS);
+        let mut ours = LuaRandom::new(seed);
+        for (draw, &lua) in expected.iter().enumerate() {
+            same("math.random", &format!("{seed:?}"), "", draw, ours.random(), lua);
+        }
+    }
+}
+
+#[test]
+fn pseudorandom_matches_lua() {
+    let oracle = Oracle::new();
+    let mut rng = Lcg(3);
+    for _ in 0..SEEDS {
+        let seed = rng.seed();
+        oracle.start_run(&seed);
+        let mut ours = Random::new(seed.as_bytes());
+        let key = rng.key();
+        for draw in 0..DRAWS {
+            same("pseudorandom", &seed, &key, draw, ours.random_str(&key), oracle.pseudorandom(&key));
+        }
+    }
+}
+
+#[test]
+fn pseudorandom_int_matches_lua() {
+    let oracle = Oracle::new();
+    let mut rng = Lcg(4);
+    for _ in 0..SEEDS {
+        let seed = rng.seed();
+        oracle.start_run(&seed);
+        let mut ours = Random::new(seed.as_bytes());
+        let key = rng.key();
+        let min = rng.below(5) as i32;
+        let max = min + rng.below(200) as i32;
+        for draw in 0..DRAWS {
+            let (a, b) = (ours.rand_int_str(&key, min, max), oracle.pseudorandom_int(&key, min, max));
+            assert_eq!(a, b, "first divergence in pseudorandom({key:?}, {min}, {max}): seed {seed:?} draw {draw}");
+        }
+    }
+}
+
+fn suit_letter(suit: CardSuits) -> char {
+    match suit {
+        CardSuits::Clubs => 'C',
+        CardSuits::Diamonds => 'D',
+        CardSuits::Hearts => 'H',
+        CardSuits::Spades => 'S',
+    }
+}
+
+fn rank_letter(rank: CardTypes) -> char {
+    match rank {
+        CardTypes::Ten => 'T',
+        CardTypes::Jack => 'J',
+        CardTypes::Queen => 'Q',
+        CardTypes::King => 'K',
+        CardTypes::Ace => 'A',
+        r => char::from(b'1' + r as u8),
+    }
+}
+
+fn edition_key(edition: Editions) -> &'static str {
+    match edition {
+        Editions::Negative => "negative",
+        Editions::Polychrome => "polychrome",
+        Editions::Holographic => "holo",
+        Editions::Foil => "foil",
+        Editions::None => "none",
+    }
+}
+
+// A card in the oracle's form: `center front edition seal`.
+fn card_key(card: &Card) -> String {
+    let center = match card.enhancement {
+        EnhancementTypes::None => "c_base".to_string(),
+        e => format!("m_{}", format!("{e:?}").to_lowercase()),
+    };
+    let (suit, rank) = (suit_letter(card.suit), rank_letter(card.rank));
+    let edition = edition_key(card.edition);
+    let seal = match card.seal {
+        SealTypes::None => "none".to_string(),
+        s => format!("{s:?}"),
+    };
+    format!("{center} {suit}_{rank} {edition} {seal}")
+}
+
+#[test]
+fn standard_pack_cards_match_lua() {
+    let oracle = Oracle::new();
+    let mut rng = Lcg(5);
+    for _ in 0..SEEDS {
+        let seed = rng.seed();
+        oracle.start_run(&seed);
+        let mut ours = Random::new(seed.as_bytes());
+        let ante = 1 + rng.below(8) as i32;
+        for draw in 0..DRAWS {
+            let card = standard_pack_card(&mut ours, ante, 1.0);
+            assert_eq!(card_key(&card), oracle.standard_card(ante), "standard pack card: seed {seed:?} ante {ante} draw {draw}");
+        }
+        let familiar: Vec<String> = familiar_cards(&mut ours).iter().map(card_key).collect();
+        assert_eq!(familiar.join(" "), oracle.familiar_cards(), "Familiar: seed {seed:?}");
+    }
+}
+
+#[test]
+fn round_targets_match_lua() {
+    let oracle = Oracle::new();
+    let mut deck = starting_deck();
+    for card in deck.iter_mut().filter(|c| (c.sort_id + 1) % 5 == 0) {
+        card.enhancement = EnhancementTypes::Stone;
+    }
+    deck.reverse();
+    // LuaJIT seeds its string hash per state; ask this one for its order.
+    let order = hands_order(&oracle);
+    let mut rng = Lcg(6);
+    for _ in 0..SEEDS {
+        let seed = rng.seed();
+        oracle.start_run(&seed);
+        let mut ours = Random::new(seed.as_bytes());
+        let mut ancient = None;
+        let mut to_do = None;
+        for draw in 0..DRAWS {
+            let ante = 1 + draw as i32 / 2;
+            let t = roll_round_targets(&mut ours, ante, &deck, ancient);
+            let key = format!(
+                "{}_{} {} {:?} {}",
+                suit_letter(t.idol.1),
+                rank_letter(t.idol.0),
+                rank_letter(t.mail),
+                t.ancient,
+                suit_letter(t.castle)
+            );
+            let previous = ancient.map(|s: CardSuits| format!("{s:?}"));
+            assert_eq!(key, oracle.round_targets(ante, previous.as_deref()), "round targets: seed {seed:?} draw {draw}");
+            ancient = Some(t.ancient);
+
+            let hand = roll_to_do_hand(&mut ours, to_do, |h| !h.is_secret(), &order);
+            let previous = to_do.map(|h: HandTypes| h.name());
+            assert_eq!(hand.name(), oracle.to_do(previous), "To Do List: seed {seed:?} draw {draw}");
+            to_do = Some(hand);
+        }
+    }
+}
+
+#[test]
+fn chance_rolls_match_lua() {
+    const ROLLS: [Roll; 12] = [
+        Roll::LuckyMult,
+        Roll::LuckyMoney,
+        Roll::WheelOfFortune,
+        Roll::Glass,
+        Roll::Bloodstone,
+        Roll::EightBall,
+        Roll::Space,
+        Roll::Business,
+        Roll::ReservedParking,
+        Roll::Hallucination,
+        Roll::GrosMichel,
+        Roll::Cavendish,
+    ];
+    let oracle = Oracle::new();
+    let mut rng = Lcg(7);
+    for _ in 0..SEEDS {
+        let seed = rng.seed();
+        oracle.start_run(&seed);
+        let mut ours = Random::new(seed.as_bytes());
+        let probabilities = Probabilities::with_oops_all_sixes(rng.below(3) as u32);
+        let ante = 1 + rng.below(8) as i32;
+        for draw in 0..DRAWS {
+            for r in ROLLS {
+                let lua = oracle.chance(&r.stream(ante), probabilities.normal, r.odds());
+                assert_eq!(roll(&mut ours, r, ante, probabilities), lua, "{r:?}: seed {seed:?} draw {draw}");
+            }
+            let eligible = 1 + rng.below(5);
+            let hit = wheel_of_fortune(&mut ours, eligible, probabilities);
+            let key = hit.map_or("miss".to_string(), |h| format!("{} {}", h.joker, edition_key(h.edition)));
+            assert_eq!(key, oracle.wheel_of_fortune(probabilities.normal, eligible), "Wheel of Fortune: seed {seed:?} draw {draw}");
+        }
+    }
+}
+
+// The order `pairs(G.GAME.hands)` walks in this oracle.
+fn hands_order(oracle: &Oracle) -> Vec<HandTypes> {
+    let order = oracle.hands_order();
+    order.split(',').map(|name| HandTypes::from_name(name).unwrap_or_else(|| panic!("unknown hand {name}"))).collect()
+}
+
+#[test]
+fn orbital_hands_and_planet_pool_match_lua() {
+    let oracle = Oracle::new();
+    let order = hands_order(&oracle);
+    let mut rng = Lcg(8);
+    for _ in 0..SEEDS {
+        let seed = rng.seed();
+        oracle.start_run(&seed);
+        oracle.reset_hands();
+        let mut game = GameState::new(&seed, 1);
+        let mut ours = Random::new(seed.as_bytes());
+        // Play a random subset of the secret hands, unlocking their planets.
+        for hand in [HandTypes::FlushFive, HandTypes::FlushHouse, HandTypes::FiveOfAKind] {
+            if rng.below(3) == 0 {
+                game.run.hands.play(hand);
+                oracle.play_hand(hand.name());
+            }
+        }
+        let ante = 1 + rng.below(8) as i32;
+        for draw in 0..DRAWS {
+            let hand = orbital_hand(&mut ours, &game.run.hands, &order);
+            assert_eq!(hand.name(), oracle.orbital(), "Orbital Tag: seed {seed:?} draw {draw}");
+        }
+        for draw in 0..DRAWS * 4 {
+            let planet = game.next_planet_from_at_ante(RandomSource::Shop, ante);
+            let key = format!("c_{}", planet.name().to_lowercase().replace(' ', "_"));
+            assert_eq!(key, oracle.shop_planet(ante), "shop planet: seed {seed:?} ante {ante} draw {draw}");
+        }
+    }
+}
+
+// The jokers get_current_pool leaves out on a fresh deck holding `held`, as
+// ',rarity_index,' keys: those waiting on an enhancement, Cavendish, and the held ones.
+fn unavailable_jokers(held: &[Joker]) -> String {
+    let gated = [
+        JokerTypes::SteelJoker,
+        JokerTypes::StoneJoker,
+        JokerTypes::LuckyCat,
+        JokerTypes::GoldenTicket,
+        JokerTypes::GlassJoker,
+        JokerTypes::Cavendish,
+    ];
+    let mut keys = String::from(",");
+    for (rarity, pool) in [(1, &COMMON_JOKERS[..]), (2, &UNCOMMON_JOKERS), (3, &RARE_JOKERS), (4, &LEGENDARY_JOKERS)] {
+        for (i, joker) in pool.iter().enumerate() {
+            if gated.contains(joker) || held.iter().any(|h| h.joker == *joker) {
+                keys.push_str(&format!("{rarity}_{},", i + 1));
+            }
+        }
+    }
+    keys
+}
+
+// A joker in the oracle's form: `rarity index edition`, index into its rarity pool from 1.
+fn joker_key(joker: &Joker) -> String {
+    let (rarity, pool): (u8, &[_]) = match joker.rarity {
+        JokerRarity::Common => (1, &COMMON_JOKERS),
+        JokerRarity::Uncommon => (2, &UNCOMMON_JOKERS),
+        JokerRarity::Rare => (3, &RARE_JOKERS),
+        JokerRarity::Legendary => (4, &LEGENDARY_JOKERS),
+    };
+    let index = pool.iter().position(|&j| j == joker.joker).expect("joker in its pool") + 1;
+    format!("{rarity} {index} {}", edition_key(joker.edition))
+}
+
+fn plain_jokers(n: usize) -> Vec<Joker> {
+    let joker = |i| Joker { joker: COMMON_JOKERS[i], rarity: JokerRarity::Common, edition: Editions::None, stickers: [false; 3] };
+    (0..n).map(joker).collect()
+}
+
+#[test]
+fn consumable_effects_match_lua() {
+    let oracle = Oracle::new();
+    let mut rng = Lcg(9);
+    for _ in 0..SEEDS {
+        let seed = rng.seed();
+        oracle.start_run(&seed);
+        let mut ours = Random::new(seed.as_bytes());
+        let ante = 1 + rng.below(8) as i32;
+        let mut run = RunState::new();
+        run.hand = (0..8).rev().collect();
+        run.consumable_slots = 4;
+        let mut using = |run: &mut RunState, card, selected: &[usize]| {
+            use_consumable(&mut ours, run, card, ante, selected).expect("usable");
+        };
+
+        using(&mut run, Consumable::Tarot(Tarots::Judgement), &[]);
+        assert_eq!(joker_key(&run.jokers[0]), oracle.create_joker("jud", None, ante, &unavailable_jokers(&run.jokers[..0])), "Judgement: seed {seed:?}");
+        using(&mut run, Consumable::Spectral(Spectral::Wraith), &[]);
+        assert_eq!(joker_key(&run.jokers[1]), oracle.create_joker("wra", Some(0.99), ante, &unavailable_jokers(&run.jokers[..1])), "Wraith: seed {seed:?}");
+
+        using(&mut run, Consumable::Spectral(Spectral::Aura), &[3]);
+        assert_eq!(edition_key(run.card(3).unwrap().edition), oracle.aura(), "Aura: seed {seed:?}");
+        using(&mut run, Consumable::Spectral(Spectral::Sigil), &[]);
+        assert_eq!(suit_letter(run.card(5).unwrap().suit).to_string(), oracle.sigil(), "Sigil: seed {seed:?}");
+        using(&mut run, Consumable::Spectral(Spectral::Ouija), &[]);
+        assert_eq!(rank_letter(run.card(5).unwrap().rank).to_string(), oracle.ouija(), "Ouija: seed {seed:?}");
+
+        // Positions from 1 in sort_id order of the hand before the card was used.
+        let destroyed = |before: &[usize], after: &RunState| {
+            let mut before = before.to_vec();
+            before.sort_unstable();
+            let gone: Vec<String> = (0..before.len())
+                .filter(|&i| !after.hand.contains(&before[i]))
+                .map(|i| (i + 1).to_string())
+                .collect();
+            gone.join(",")
+        };
+        let before = run.hand.clone();
+        using(&mut run, Consumable::Spectral(Spectral::Familiar), &[]);
+        assert_eq!(destroyed(&before, &run), oracle.pick("random_destroy", before.len()).to_string(), "Familiar: seed {seed:?}");
+        let created: Vec<String> = run.deck[run.deck.len() - 3..].iter().map(card_key).collect();
+        assert_eq!(created.join(" "), oracle.familiar_cards(), "Familiar cards: seed {seed:?}");
+        let before = run.hand.clone();
+        using(&mut run, Consumable::Spectral(Spectral::Immolate), &[]);
+        assert_eq!(destroyed(&before, &run), oracle.immolate(before.len()), "Immolate: seed {seed:?}");
+
+        run.jokers = plain_jokers(4);
+        using(&mut run, Consumable::Spectral(Spectral::Hex), &[]);
+        let chosen = plain_jokers(4).iter().position(|j| j.joker == run.jokers[0].joker).unwrap() + 1;
+        assert_eq!(chosen, oracle.pick("hex", 4), "Hex: seed {seed:?}");
+        run.jokers = plain_jokers(4);
+        using(&mut run, Consumable::Spectral(Spectral::Ankh), &[]);
+        let chosen = plain_jokers(4).iter().position(|j| j.joker == run.jokers[0].joker).unwrap() + 1;
+        assert_eq!(chosen, oracle.pick("ankh_choice", 4), "Ankh: seed {seed:?}");
+
+        run.consumables.clear();
+        using(&mut run, Consumable::Tarot(Tarots::TheEmperor), &[]);
+        let tarots: Vec<String> = run
+            .consumables
+            .iter()
+            .map(|c| match c {
+                Consumable::Tarot(t) => (*t as usize + 1).to_string(),
+                other => panic!("The Emperor made {other:?}"),
+            })
+            .collect();
+        assert_eq!(tarots.join(","), oracle.emperor(ante), "The Emperor: seed {seed:?}");
+    }
+}
+
+// A pack card in the oracle's form: its index in its set's pool from 1, or
+// `c_soul` and `c_black_hole`.
+fn pack_card_key(card: Consumable) -> String {
+    match card {
+        Consumable::Spectral(Spectral::TheSoul) => "c_soul".to_string(),
+        Consumable::Spectral(Spectral::BlackHole) => "c_black_hole".to_string(),
+        Consumable::Tarot(t) => format!("c_{}", t as usize + 1),
+        Consumable::Planet(p) => format!("c_{}", p as usize + 1),
+        Consumable::Spectral(s) => format!("c_{}", s as usize + 1),
+    }
+}
+
+#[test]
+fn soul_and_black_hole_match_lua() {
+    let oracle = Oracle::new();
+    let mut rng = Lcg(10);
+    let mut legendaries = 0;
+    for _ in 0..SEEDS {
+        let seed = rng.seed();
+        oracle.start_run(&seed);
+        oracle.reset_hands();
+        let mut game = GameState::new(&seed, 1);
+        let ante = 1 + rng.below(8) as i32;
+        for draw in 0..DRAWS {
+            let cards = [
+                ("Tarot", RandomSource::Arcana, game.next_tarot_card_at_ante(RandomSource::Arcana, ante)),
+                ("Planet", RandomSource::Celestial, game.next_planet_card_at_ante(RandomSource::Celestial, ante)),
+                (
+                    "Spectral",
+                    RandomSource::SpectralPack,
+                    Consumable::Spectral(game.next_spectral_card_at_ante(RandomSource::SpectralPack, ante)),
+                ),
+            ];
+            for (set, source, card) in cards {
+                let lua = oracle.pack_card(set, source.code(), ante);
+                assert_eq!(pack_card_key(card), lua, "{set}: seed {seed:?} ante {ante} draw {draw}");
+                if card == Consumable::Spectral(Spectral::TheSoul) {
+                    let joker = game.next_legendary_joker(ante);
+                    let lua = oracle.create_joker("sou", Some(4.0), ante, ",");
+                    assert_eq!(joker_key(&joker), lua, "legendary: seed {seed:?} ante {ante} draw {draw}");
+                    legendaries += 1;
+                }
+            }
+        }
+    }
+    assert!(legendaries > 0);
+}
+
+// A shop slot in the oracle's form: `Joker` and the joker's key, or the set
+// and the card's index in it from 1.
+fn shop_key(item: &ShopItem) -> String {
+    match item {
+        ShopItem::Joker(joker) => format!("Joker {}", joker_key(joker)),
+        ShopItem::Consumable(Consumable::Tarot(t)) => format!("Tarot {}", *t as usize + 1),
+        ShopItem::Consumable(Consumable::Planet(p)) => format!("Planet {}", *p as usize + 1),
+        other => panic!("no {other:?} at the base shop rates"),
+    }
+}
+
+#[test]
+fn first_shops_match_lua() {
+    let oracle = Oracle::new();
+    let mut rng = Lcg(12);
+    let mut kinds = [0; 3];
+    for _ in 0..SEEDS {
+        let seed = rng.seed();
+        oracle.start_run(&seed);
+        oracle.reset_hands();
+        let ante = 1 + rng.below(8) as i32;
+        let shop = SeedView::new(&seed).shop(ante);
+        let ours: Vec<String> = shop.iter().map(shop_key).collect();
+        let lua = oracle.shop_slots(ante, SHOP_SLOTS, &unavailable_jokers(&[]));
+        assert_eq!(ours.join("|"), lua, "seed {seed:?} ante {ante}");
+        for item in shop {
+            match item {
+                ShopItem::Joker(_) => kinds[0] += 1,
+                ShopItem::Consumable(Consumable::Tarot(_)) => kinds[1] += 1,
+                _ => kinds[2] += 1,
+            }
+        }
+    }
+    assert!(kinds.iter().all(|&k| k > 0), "{kinds:?}");
+}
+
+// Draw for `round`, logging its seeded picks in the oracle's form.
+fn logged_draw(round: &mut BlindRound, ours: &mut Random, run: &mut RunState, log: &mut Vec<String>) {
+    let forced = round.forced;
+    for id in round.draw(ours, run) {
+        if round.face_down.contains(&id) {
+            log.push(format!("f{}", id + 1));
+        }
+    }
+    if round.forced != forced {
+        log.push(format!("b{}", round.forced.unwrap() + 1));
+    }
+    if round.boss == Some(Bosses::CrimsonHeart) && round.hands_played > 0 && !run.jokers.is_empty() {
+        log.push(format!("c{}", round.disabled_joker.unwrap() + 1));
+    }
+}
+
+#[test]
+fn boss_blind_picks_match_lua() {
+    let oracle = Oracle::new();
+    let mut rng = Lcg(11);
+    let bosses = [Bosses::TheWheel, Bosses::TheHook, Bosses::CeruleanBell, Bosses::CrimsonHeart];
+    for _ in 0..SEEDS {
+        let seed = rng.seed();
+        oracle.start_run(&seed);
+        let boss = bosses[rng.below(bosses.len())];
+        let ante = 1 + rng.below(8) as i32;
+        let mut run = RunState::new();
+        run.hand_size = 5 + rng.below(4) as i32;
+        run.jokers = plain_jokers(rng.below(4));
+        run.probabilities = Probabilities::with_oops_all_sixes(rng.below(2) as u32);
+        let (plays, play_size) = (1 + rng.below(4), 1 + rng.below(5));
+
+        let mut ours = Random::new(seed.as_bytes());
+        let mut round = BlindRound::new(&mut ours, &run, Some(boss), ante);
+        let mut log = Vec::new();
+        logged_draw(&mut round, &mut ours, &mut run, &mut log);
+        for _ in 0..plays {
+            let mut played: Vec<usize> = round.forced.into_iter().collect();
+            played.extend(run.hand.iter().copied().filter(|id| Some(*id) != round.forced));
+            played.truncate(play_size);
+            let hooked = round.play(&mut ours, &mut run, &played).expect("playable");
+            log.extend(hooked.iter().map(|id| format!("h{}", id + 1)));
+            logged_draw(&mut round, &mut ours, &mut run, &mut log);
+        }
+        let lua = oracle.blind_round(
+            boss.name(),
+            ante,
+            run.hand_size,
+            run.jokers.len(),
+            plays,
+            play_size,
+            run.probabilities.normal,
+        );
+        assert_eq!(log.join(" "), lua, "{}: seed {seed:?} ante {ante}", boss.name());
+    }
+}
@@ -0,0 +1,53 @@
+use common::util::LuaRandom;
+
+// Golden vectors from LuaJIT; see fixtures/lua_random_golden.lua.
+const GOLDEN: &str = include_str!("fixtures/lua_random_golden.txt");
+
+fn golden(kind: &str) -> impl Iterator<Item = Vec<&'static str>> + '_ {
+    GOLDEN
+        .lines()
+        .map(|l| l.split(' ').collect::<Vec<_>>())
+        .filter(move |f| f[0] == kind)
+}
+
+fn num(s: &str) -> f64 {
+    s.parse().expect("golden value")
+}
+
+#[test]
+fn random_matches_luajit() {
+    let mut tested = 0;
+    for f in golden("random") {
+        let seed = num(f[1]);
+        let mut rng = LuaRandom::new(seed);
+        for (draw, expected) in f[2..].iter().enumerate() {
+            let (ours, expected) = (rng.random(), num(expected));
+            assert_eq!(ours.to_bits(), expected.to_bits(), "draw {draw} after math.randomseed({seed:?})");
+        }
+        tested += 1;
+    }
+    assert!(tested >= 1000);
+}
+
+#[test]
+fn randint_matches_luajit() {
+    for f in golden("randint") {
+        let seed = num(f[1]);
+        let (min, max, expected): (i32, i32, i32) = (f[2].parse().unwrap(), f[3].parse().unwrap(), f[4].parse().unwrap());
+        assert_eq!(LuaRandom::new(seed).randint(min, max), expected, "math.random({min}, {max}) after seed {seed:?}");
+    }
+}
+
+#[test]
+fn debug_helpers_describe_the_first_draw() {
+    for f in golden("random").take(100) {
+        let seed = num(f[1]);
+        let (states, r) = LuaRandom::debug_first_rand_steps(seed);
+        let (bits, value) = LuaRandom::debug_first_rand_bits(seed);
+        // The output is the xor of the four components, its low 52 bits the mantissa.
+        assert_eq!(states.iter().fold(0, |acc, s| acc ^ s), r);
+        assert_eq!(bits, (r & 0xFFFFFFFFFFFFF) | 0x3FF0000000000000);
+        assert_eq!(value.to_bits(), num(f[2]).to_bits());
+        assert_ne!(LuaRandom::debug_seed_states(seed), states);
+    }
+}
@@ -0,0 +1,56 @@
+use common::items::{Bosses, Editions, HandTypes, JokerTypes, Pack, Planets, Spectral, Tags, Tarots, Vouchers};
+use common::names::ItemName;
+
+fn assert_round_trip<T: ItemName + PartialEq + std::fmt::Debug>() {
+    let mut seen: Vec<&'static str> = Vec::new();
+    for item in T::iter() {
+        let name = item.name();
+        assert!(!seen.contains(&name), "duplicate {} name '{}'", T::KIND, name);
+        seen.push(name);
+        assert_eq!(T::from_name(name), Some(item), "{} '{}' did not round-trip", T::KIND, name);
+        assert_eq!(T::from_name(&name.to_uppercase()), Some(item));
+    }
+}
+
+#[test]
+fn every_item_name_round_trips() {
+    assert_round_trip::<Bosses>();
+    assert_round_trip::<Tags>();
+    assert_round_trip::<Tarots>();
+    assert_round_trip::<Planets>();
+    assert_round_trip::<Vouchers>();
+    assert_round_trip::<JokerTypes>();
+    assert_round_trip::<Spectral>();
+    assert_round_trip::<Pack>();
+    assert_round_trip::<Editions>();
+    assert_round_trip::<HandTypes>();
+}
+
+#[test]
+fn parsing_tolerates_case_and_punctuation() {
+    assert_eq!("drivers license".parse::<JokerTypes>(), Ok(JokerTypes::DriversLicense));
+    assert_eq!("Oops All 6s".parse::<JokerTypes>(), Ok(JokerTypes::OopsAllSixes));
+    assert_eq!("mr bones".parse::<JokerTypes>(), Ok(JokerTypes::MrBones));
+    assert_eq!("riff raff".parse::<JokerTypes>(), Ok(JokerTypes::RiffRaff));
+    assert_eq!("  top up tag ".parse::<Tags>(), Ok(Tags::TopUpTag));
+    assert_eq!("directors-cut".parse::<Vouchers>(), Ok(Vouchers::DirectorsCut));
+    assert_eq!("deja vu".parse::<Spectral>(), Ok(Spectral::DejaVu));
+    assert_eq!("planet x".parse::<Planets>(), Ok(Planets::PlanetX));
+}
+
+#[test]
+fn parsing_allows_omitting_leading_the() {
+    assert_eq!("wheel of fortune".parse::<Tarots>(), Ok(Tarots::TheWheelOfFortune));
+    assert_eq!("Idol".parse::<JokerTypes>(), Ok(JokerTypes::TheIdol));
+    assert_eq!("wall".parse::<Bosses>(), Ok(Bosses::TheWall));
+    // An exact match still wins over the stripped form.
+    assert_eq!("Soul".parse::<Spectral>(), Ok(Spectral::TheSoul));
+}
+
+#[test]
+fn unknown_names_report_kind_and_input() {
+    let err = "Not A Joker".parse::<JokerTypes>().unwrap_err();
+    assert_eq!(err.kind, "joker");
+    assert_eq!(err.input, "Not A Joker");
+    assert_eq!(err.to_string(), "unknown joker name 'Not A Joker'");
+}
@@ -0,0 +1,81 @@
+mod support;
+
+use common::game::GameState;
+use common::items::Editions;
+use common::odds::{roll, rolls, wheel_of_fortune, Probabilities, Roll};
+use common::random::Random;
+use support::SEEDS;
+
+#[test]
+fn rolls_compare_against_normal_over_odds() {
+    for seed in SEEDS {
+        let mut rng = Random::new(seed.as_bytes());
+        let mut draws = Random::new(seed.as_bytes());
+        for r in [Roll::LuckyMult, Roll::LuckyMoney, Roll::Glass, Roll::Hallucination] {
+            for _ in 0..10 {
+                let poll = draws.random_str(&r.stream(3));
+                assert_eq!(roll(&mut rng, r, 3, Probabilities::default()), poll < 1.0 / r.odds() as f64);
+            }
+        }
+    }
+    assert_eq!(Roll::Hallucination.stream(2), "halu2");
+    assert_eq!(Roll::EightBall.stream(2), "8ball");
+}
+
+#[test]
+fn oops_all_sixes_only_adds_hits() {
+    assert_eq!(Probabilities::with_oops_all_sixes(0), Probabilities::default());
+    assert_eq!(Probabilities::with_oops_all_sixes(2).normal, 4.0);
+    for seed in SEEDS {
+        let base = rolls(&mut Random::new(seed.as_bytes()), Roll::LuckyMult, 1, Probabilities::default(), 50);
+        let oops = rolls(&mut Random::new(seed.as_bytes()), Roll::LuckyMult, 1, Probabilities::with_oops_all_sixes(1), 50);
+        assert!(base.iter().zip(&oops).all(|(&b, &o)| !b || o), "seed {seed}");
+        // Two Oops! make 1 in 4 certain.
+        let sure = rolls(&mut Random::new(seed.as_bytes()), Roll::Glass, 1, Probabilities::with_oops_all_sixes(2), 20);
+        assert!(sure.iter().all(|&hit| hit));
+    }
+}
+
+#[test]
+fn wheel_of_fortune_draws_joker_and_edition_only_on_a_hit() {
+    let mut hits = 0;
+    for seed in SEEDS {
+        let mut rng = Random::new(seed.as_bytes());
+        rng.enable_trace();
+        assert_eq!(wheel_of_fortune(&mut rng, 0, Probabilities::default()), None);
+        assert!(rng.take_trace().is_empty());
+        for _ in 0..20 {
+            let result = wheel_of_fortune(&mut rng, 3, Probabilities::default());
+            let draws = rng.take_trace().len();
+            match result {
+                Some(hit) => {
+                    hits += 1;
+                    assert_eq!(draws, 3);
+                    assert!(hit.joker < 3);
+                    assert_ne!(hit.edition, Editions::Negative);
+                    assert_ne!(hit.edition, Editions::None);
+                }
+                None => assert_eq!(draws, 1),
+            }
+        }
+    }
+    assert!(hits > 0);
+}
+
+#[test]
+fn game_state_rolls_match_the_module() {
+    for seed in SEEDS {
+        let mut game = GameState::new(seed, 1);
+        let mut rng = Random::new(seed.as_bytes());
+        for _ in 0..10 {
+            assert_eq!(
+                game.next_roll(Roll::Bloodstone, 1, Probabilities::default()),
+                roll(&mut rng, Roll::Bloodstone, 1, Probabilities::default())
+            );
+            assert_eq!(
+                game.next_wheel_of_fortune(2, Probabilities::default()),
+                wheel_of_fortune(&mut rng, 2, Probabilities::default())
+            );
+        }
+    }
+}
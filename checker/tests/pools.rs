@@ -0,0 +1,120 @@
+mod support;
+
+use common::consumables::use_consumable;
+use common::game::GameState;
+use common::items::{
+    Card, Consumable, EnhancementTypes, JokerRarity, JokerTypes, Planets, RandomSource, Spectral, Tarots,
+};
+use common::pools::next_joker;
+use common::random::{ItemChoice, Random};
+use common::run::RunState;
+use support::{joker, SEEDS};
+
+const GATED: [JokerTypes; 5] =
+    [JokerTypes::SteelJoker, JokerTypes::StoneJoker, JokerTypes::LuckyCat, JokerTypes::GoldenTicket, JokerTypes::GlassJoker];
+
+#[test]
+fn pool_flags_and_enhancements_gate_jokers() {
+    let mut cavendish = 0;
+    for seed in SEEDS {
+        let mut rng = Random::new(seed.as_bytes());
+        let mut run = RunState::new();
+        for _ in 0..300 {
+            let joker = next_joker(&mut rng, &run, RandomSource::Shop, 1, false).joker;
+            assert!(!GATED.contains(&joker) && joker != JokerTypes::Cavendish, "seed {seed}: {joker:?}");
+        }
+        run.gros_michel_extinct = true;
+        run.deck[0] = Card { enhancement: EnhancementTypes::Steel, ..run.deck[0] };
+        assert!(!JokerTypes::SteelJoker.retry_in(&run) && JokerTypes::StoneJoker.retry_in(&run));
+        for _ in 0..300 {
+            let joker = next_joker(&mut rng, &run, RandomSource::Shop, 1, false).joker;
+            assert_ne!(joker, JokerTypes::GrosMichel, "seed {seed}");
+            cavendish += (joker == JokerTypes::Cavendish) as u32;
+        }
+    }
+    assert!(cavendish > 0);
+}
+
+#[test]
+fn held_cards_stay_out_unless_showman() {
+    for seed in SEEDS {
+        let mut game = GameState::new(seed, 1);
+        game.run.jokers = [JokerTypes::Joker, JokerTypes::Blueprint, JokerTypes::Misprint].map(joker).to_vec();
+        game.run.consumables = vec![Consumable::Tarot(Tarots::TheFool), Consumable::Planet(Planets::Pluto)];
+        for _ in 0..200 {
+            let joker = game.next_joker_from_at_ante(RandomSource::Shop, 1).joker;
+            assert!(!game.run.holds_joker(joker), "seed {seed}: {joker:?}");
+            assert_ne!(game.next_tarot_from_at_ante(RandomSource::Shop, 1), Tarots::TheFool, "seed {seed}");
+            assert_ne!(game.next_planet_from_at_ante(RandomSource::Shop, 1), Planets::Pluto, "seed {seed}");
+        }
+        game.run.jokers.push(joker(JokerTypes::Showman));
+        assert!(!JokerTypes::Blueprint.retry_in(&game.run) && !Tarots::TheFool.retry_in(&game.run));
+        assert!(!JokerTypes::Showman.retry_in(&game.run));
+    }
+}
+
+#[test]
+fn the_emperor_never_makes_itself() {
+    for seed in SEEDS {
+        let mut rng = Random::new(seed.as_bytes());
+        let mut run = RunState::new();
+        let mut made = Vec::new();
+        for _ in 0..100 {
+            run.consumables.clear();
+            use_consumable(&mut rng, &mut run, Consumable::Tarot(Tarots::TheEmperor), 1, &[]).unwrap();
+            assert_ne!(run.consumables[0], run.consumables[1], "seed {seed}");
+            made.extend_from_slice(&run.consumables);
+        }
+        assert!(!made.contains(&Consumable::Tarot(Tarots::TheEmperor)), "seed {seed}");
+
+        run.jokers.push(joker(JokerTypes::Showman));
+        let mut made = Vec::new();
+        for _ in 0..100 {
+            run.consumables.clear();
+            use_consumable(&mut rng, &mut run, Consumable::Tarot(Tarots::TheEmperor), 1, &[]).unwrap();
+            made.extend_from_slice(&run.consumables);
+        }
+        assert!(made.contains(&Consumable::Tarot(Tarots::TheEmperor)), "seed {seed}");
+    }
+}
+
+#[test]
+fn soul_and_black_hole_never_come_from_the_pool() {
+    let run = RunState::new();
+    assert!(Spectral::TheSoul.retry() && Spectral::BlackHole.retry_in(&run));
+    assert!(!Spectral::Hex.retry_in(&run));
+    assert!(!Planets::Eris.retry() && Planets::Eris.retry_in(&run));
+}
+
+#[test]
+fn only_packs_roll_for_the_soul() {
+    for seed in SEEDS {
+        let mut game = GameState::new(seed, 1);
+        game.enable_trace();
+        game.next_tarot_card_at_ante(RandomSource::Shop, 1);
+        game.next_planet_card_at_ante(RandomSource::Shop, 1);
+        let ids: Vec<String> = game.take_trace().into_iter().map(|e| e.id).collect();
+        assert_eq!(ids, ["Tarotsho1", "Planetsho1"], "seed {seed}");
+
+        game.next_spectral_card_at_ante(RandomSource::SpectralPack, 1);
+        let ids: Vec<String> = game.take_trace().into_iter().map(|e| e.id).collect();
+        assert_eq!(ids[..3], ["soul_Spectral1", "soul_Spectral1", "Spectralspe1"], "seed {seed}");
+
+        // A held Soul is not rolled for again.
+        game.run.consumables.push(Consumable::Spectral(Spectral::TheSoul));
+        game.next_tarot_card_at_ante(RandomSource::Arcana, 1);
+        assert_eq!(game.take_trace()[0].id, "Tarotar11", "seed {seed}");
+    }
+}
+
+#[test]
+fn the_soul_makes_a_legendary() {
+    for seed in SEEDS {
+        let mut rng = Random::new(seed.as_bytes());
+        let mut run = RunState::new();
+        use_consumable(&mut rng, &mut run, Consumable::Spectral(Spectral::TheSoul), 2, &[]).unwrap();
+        let mut game = GameState::new(seed, 2);
+        assert_eq!(run.jokers, [game.next_legendary_joker(2)]);
+        assert_eq!(run.jokers[0].rarity, JokerRarity::Legendary);
+    }
+}
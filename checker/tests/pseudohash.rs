@@ -0,0 +1,100 @@
+use common::random::Random;
+use common::reference::{self, PseudoRandom};
+use common::util::{pseudohash, pseudohash_bytes, round13};
+
+// Golden vectors from LuaJIT; see fixtures/pseudohash_golden.lua.
+const GOLDEN: &str = include_str!("fixtures/pseudohash_golden.txt");
+const KEYS: [&str; 4] = ["boss", "boss", "Voucher1", "Joker1sho1"];
+
+fn golden(kind: &str) -> impl Iterator<Item = Vec<&'static str>> + '_ {
+    GOLDEN
+        .lines()
+        .map(|l| l.split(' ').collect::<Vec<_>>())
+        .filter(move |f| f[0] == kind)
+}
+
+fn num(s: &str) -> f64 {
+    s.parse().expect("golden value")
+}
+
+#[test]
+fn hashed_seeds_match_lua() {
+    let mut tested = 0;
+    for f in golden("hash") {
+        let (seed, expected) = (f[1], num(f[2]));
+        assert_eq!(pseudohash([seed]).to_bits(), expected.to_bits(), "pseudohash({seed})");
+        assert_eq!(reference::pseudohash(seed).to_bits(), expected.to_bits(), "reference pseudohash({seed})");
+        assert_eq!(Random::new(seed.as_bytes()).hashed_seed.to_bits(), expected.to_bits(), "hashed seed {seed}");
+        tested += 1;
+    }
+    assert!(tested >= 2000);
+}
+
+#[test]
+fn pseudoseeds_match_lua() {
+    for f in golden("hash") {
+        let seed = f[1];
+        let mut rng = Random::new(seed.as_bytes());
+        let mut lua = PseudoRandom::new(seed);
+        for (key, expected) in KEYS.iter().zip(&f[3..]) {
+            let expected = num(expected);
+            assert_eq!(rng.get_node_str(key).to_bits(), expected.to_bits(), "pseudoseed({key}) for {seed}");
+            assert_eq!(lua.pseudoseed(key).to_bits(), expected.to_bits(), "reference pseudoseed({key}) for {seed}");
+        }
+    }
+}
+
+#[test]
+fn round13_matches_lua() {
+    let mut tested = 0;
+    for f in golden("round13") {
+        let (x, expected) = (num(f[1]), num(f[2]));
+        assert_eq!(round13(x).to_bits(), expected.to_bits(), "round13({x:e})");
+        assert_eq!(reference::round13(x).to_bits(), expected.to_bits(), "reference round13({x:e})");
+        tested += 1;
+    }
+    assert!(tested >= 2000);
+}
+
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        self.0 >> 11
+    }
+
+    fn unit(&mut self) -> f64 {
+        self.next() as f64 / (1u64 << 53) as f64
+    }
+}
+
+#[test]
+fn round13_matches_reference() {
+    let mut rng = Lcg(1);
+    for _ in 0..50_000 {
+        let x = rng.unit();
+        assert_eq!(round13(x).to_bits(), reference::round13(x).to_bits(), "round13({x:e})");
+        // The doubles around a decimal half are where fast roundings go wrong.
+        let near = ((rng.next() % 10_000_000_000_000) as f64 + 0.5) / 1e13;
+        for y in [near, f64::from_bits(near.to_bits() - 1), f64::from_bits(near.to_bits() + 1)] {
+            assert_eq!(round13(y).to_bits(), reference::round13(y).to_bits(), "round13({y:e})");
+        }
+    }
+}
+
+#[test]
+fn multi_part_pseudohash_hashes_the_concatenation() {
+    let mut rng = Lcg(2);
+    for _ in 0..10_000 {
+        let mut parts = [String::new(), String::new(), String::new()];
+        for part in &mut parts {
+            for _ in 0..rng.next() % 12 {
+                part.push((b' ' + (rng.next() % 95) as u8) as char);
+            }
+        }
+        let whole = parts.concat();
+        let fast = pseudohash_bytes([parts[0].as_bytes(), parts[1].as_bytes(), parts[2].as_bytes()]);
+        assert_eq!(fast.to_bits(), reference::pseudohash(&whole).to_bits(), "pseudohash({whole:?})");
+    }
+}
@@ -1,6 +1,6 @@
 use common::random::Random;
 use common::random::ItemChoice;
-use common::random::RESAMPLE_IDS_LEN;
+use common::random::{ChoiceError, RESAMPLE_IDS_LEN};
 use std::ptr;
 
 fn approx_eq(a: f64, b: f64, eps: f64) -> bool { (a - b).abs() <= eps }
@@ -60,6 +60,40 @@ fn rand_choice_panics_when_no_usable_item() {
     let _ = rng.rand_choice(0, &items);
 }
 
+#[test]
+fn try_rand_choice_reports_why_nothing_was_found() {
+    let mut rng = Random::new(b"ABCDEFG0");
+    assert_eq!(rng.try_rand_choice::<DummyItem>(0, &[]).unwrap_err(), ChoiceError::Empty);
+    assert_eq!(rng.try_rand_choice_str::<DummyItem>("Joker1sho1", &[]).unwrap_err(), ChoiceError::Empty);
+
+    let items: Vec<DummyItem> = (0..32).map(|_| DummyItem { allow: false }).collect();
+    let first = Random::new(b"ABCDEFG0").rand_int_str("Joker1sho1", 0, 31) as usize;
+    let err = rng.try_rand_choice_str("Joker1sho1", &items).unwrap_err();
+    assert_eq!(err, ChoiceError::NoneUsable { first });
+
+    // A pool with nothing usable falls back after a single draw, as the game does.
+    let fallback = DummyItem { allow: true };
+    let mut a = Random::new(b"ABCDEFG0");
+    assert!(ptr::eq(a.rand_choice_str_or("Tarotsho1", &items, &fallback), &fallback));
+    let mut b = Random::new(b"ABCDEFG0");
+    let _ = b.random_str("Tarotsho1");
+    assert_eq!(a.random_str("Tarotsho1"), b.random_str("Tarotsho1"));
+    assert!(ptr::eq(a.rand_choice_or(0, &items, &fallback), &fallback));
+}
+
+#[test]
+fn string_resampling_continues_past_the_numeric_group() {
+    // One usable item in 150 is rarely found within RESAMPLE_IDS_LEN draws.
+    let len = 150;
+    for seed in [&b"ABCDEFG0"[..], b"HELLO1", b"7LB2WVPK", b"1"] {
+        let mut items: Vec<DummyItem> = (0..len).map(|_| DummyItem { allow: false }).collect();
+        items[len - 1].allow = true;
+        let mut rng = Random::new(seed);
+        let chosen = rng.try_rand_choice_str("Joker3sho1", &items).expect("the usable item is found");
+        assert_eq!(index_of(&items, chosen), len - 1);
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 struct DummyItem {
     allow: bool,
@@ -152,11 +186,11 @@ fn random_determinism_fixed_seeds() {
         "seed1: n10={:.16}, n11={:.16}, r10={:.16}, r11={:.16}, ri1={}",
         n10, n11, r10, r11, ri1
     );
-    assert!(approx_eq(n10, 0.3974688476399526, 1e-15));
-    assert!(approx_eq(n11, 0.1700901308279026, 1e-15));
-    assert!(approx_eq(r10, 0.2248987741373998, 1e-15));
-    assert!(approx_eq(r11, 0.7881045785525129, 1e-15));
-    assert_eq!(ri1, 3);
+    assert!(approx_eq(n10, 0.3213413285411764, 1e-15));
+    assert!(approx_eq(n11, 0.1199261905869264, 1e-15));
+    assert!(approx_eq(r10, 0.1789531204429466, 1e-15));
+    assert!(approx_eq(r11, 0.2740572391468188, 1e-15));
+    assert_eq!(ri1, 2);
 
     // Seed 2: "HELLO1"
     let seed2: [u8; 6] = *b"HELLO1";
@@ -182,9 +216,9 @@ fn random_determinism_fixed_seeds() {
         "seed2: n20={:.16}, n21={:.16}, r20={:.16}, r21={:.16}, ri2={}",
         n20, n21, r20, r21, ri2
     );
-    assert!(approx_eq(n20, 0.4166009355118860, 1e-15));
-    assert!(approx_eq(n21, 0.1865191996113360, 1e-15));
-    assert!(approx_eq(r20, 0.9532318186945516, 1e-15));
-    assert!(approx_eq(r21, 0.0110093296313114, 1e-15));
-    assert_eq!(ri2, 10);
+    assert!(approx_eq(n20, 0.2758876240888993, 1e-15));
+    assert!(approx_eq(n21, 0.4870044222918993, 1e-15));
+    assert!(approx_eq(r20, 0.5582104985163128, 1e-15));
+    assert!(approx_eq(r21, 0.8886500708719007, 1e-15));
+    assert_eq!(ri2, 6);
 }
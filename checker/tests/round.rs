@@ -0,0 +1,84 @@
+mod support;
+
+use common::cards::starting_deck;
+use common::game::GameState;
+use common::items::{CardSuits, CardTypes, EnhancementTypes, HandTypes};
+use common::random::Random;
+use common::round::{roll_round_targets, roll_to_do_hand, to_do_order};
+use support::SEEDS;
+
+#[test]
+fn targets_use_the_per_ante_streams() {
+    let mut rng = Random::new(b"ABCD1234");
+    rng.enable_trace();
+    let _ = roll_round_targets(&mut rng, 3, &starting_deck(), None);
+    let ids: Vec<String> = rng.take_trace().into_iter().map(|e| e.id).collect();
+    assert_eq!(ids, ["idol3", "mail3", "anc3", "cas3"]);
+}
+
+#[test]
+fn deck_order_comes_from_sort_id() {
+    let deck = starting_deck();
+    let mut shuffled = deck.clone();
+    shuffled.reverse();
+    shuffled.swap(3, 40);
+    for seed in SEEDS {
+        let a = roll_round_targets(&mut Random::new(seed.as_bytes()), 1, &deck, None);
+        let b = roll_round_targets(&mut Random::new(seed.as_bytes()), 1, &shuffled, None);
+        assert_eq!(a, b, "seed {seed}");
+    }
+}
+
+#[test]
+fn stone_cards_are_never_targets() {
+    let mut deck = starting_deck();
+    for card in deck.iter_mut().filter(|c| c.suit != CardSuits::Hearts || c.rank != CardTypes::Seven) {
+        card.enhancement = EnhancementTypes::Stone;
+    }
+    for seed in SEEDS {
+        let t = roll_round_targets(&mut Random::new(seed.as_bytes()), 2, &deck, None);
+        assert_eq!(t.idol, (CardTypes::Seven, CardSuits::Hearts));
+        assert_eq!(t.mail, CardTypes::Seven);
+        assert_eq!(t.castle, CardSuits::Hearts);
+    }
+
+    // With nothing to pick the game keeps its defaults.
+    let t = roll_round_targets(&mut Random::new(b"ABCD1234"), 1, &[], None);
+    assert_eq!(t.idol, (CardTypes::Ace, CardSuits::Spades));
+    assert_eq!(t.mail, CardTypes::Ace);
+    assert_eq!(t.castle, CardSuits::Spades);
+}
+
+#[test]
+fn ancient_joker_changes_suit_every_round() {
+    for seed in SEEDS {
+        let mut game = GameState::new(seed, 1);
+        let deck = starting_deck();
+        let mut previous = game.next_round_targets(1, &deck).ancient;
+        for round in 1..12 {
+            let ancient = game.next_round_targets(1 + round / 3, &deck).ancient;
+            assert_ne!(ancient, previous, "seed {seed} round {round}");
+            previous = ancient;
+        }
+    }
+}
+
+#[test]
+fn to_do_list_skips_hidden_and_current_hands() {
+    assert_eq!(to_do_order(0)[0], HandTypes::FlushFive);
+    assert_eq!(to_do_order(1)[11], HandTypes::FlushFive);
+    assert_eq!(to_do_order(13), to_do_order(1));
+    for seed in SEEDS {
+        let mut rng = Random::new(seed.as_bytes());
+        let mut current = None;
+        for _ in 0..20 {
+            let hand = roll_to_do_hand(&mut rng, current, |h| !h.is_secret(), &to_do_order(5));
+            assert!(!hand.is_secret());
+            assert_ne!(Some(hand), current);
+            current = Some(hand);
+        }
+    }
+    let only_pair = |h| h == HandTypes::Pair;
+    let mut rng = Random::new(b"ABCD1234");
+    assert_eq!(roll_to_do_hand(&mut rng, Some(HandTypes::Pair), only_pair, &to_do_order(0)), HandTypes::Pair);
+}
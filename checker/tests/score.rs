@@ -0,0 +1,78 @@
+use common::filter::{Clause, SeedView};
+use common::items::{Editions, JokerTypes, Tags};
+use common::score::{score_words, Criterion, ParseCriterionError, Scorer};
+
+const SEEDS: &[&str] = &["AAAAAAAA", "1ABC23", "ZZZZ", "K9P2QX7", "B", "TUTORIAL"];
+
+fn scorer() -> Scorer {
+    Scorer::new(
+        ["joker@1=Blueprint:10", "edition@1=Negative:+5", "tag=Double Tag:3", "boss=The Needle:-4", "voucher=Overstock"]
+            .iter()
+            .map(|c| c.parse().unwrap())
+            .collect(),
+    )
+}
+
+#[test]
+fn criteria_parse_with_optional_weight() {
+    assert_eq!(
+        "joker@1=Blueprint:10".parse::<Criterion>(),
+        Ok(Criterion { clause: Clause::ShopJoker { ante: 1, joker: JokerTypes::Blueprint }, weight: 10 })
+    );
+    assert_eq!(
+        "edition=Negative".parse::<Criterion>(),
+        Ok(Criterion { clause: Clause::ShopEdition { ante: 1, edition: Editions::Negative }, weight: 1 })
+    );
+    assert_eq!("tag=Double Tag:-3".parse::<Criterion>().unwrap().weight, -3);
+    assert!(matches!("tag=Double Tag:lots".parse::<Criterion>(), Err(ParseCriterionError::BadWeight(_))));
+    assert!(matches!("tag=Nope:2".parse::<Criterion>(), Err(ParseCriterionError::Clause(_))));
+}
+
+#[test]
+fn criteria_round_trip_through_words() {
+    for c in scorer().criteria {
+        assert_eq!(Criterion::from_words(c.to_words()), Some(c));
+        assert_eq!(c.to_string().parse::<Criterion>(), Ok(c));
+    }
+}
+
+#[test]
+fn encoded_scoring_matches_scorer() {
+    let scorer = scorer();
+    let words = scorer.to_words();
+    for seed in SEEDS {
+        assert_eq!(score_words(&words, &mut SeedView::new(seed)), scorer.score_seed(seed), "{seed}");
+    }
+}
+
+#[test]
+fn score_counts_every_occurrence() {
+    for seed in SEEDS {
+        let mut view = SeedView::new(seed);
+        let tags = view.tags(1);
+        let editions = view.shop_editions(1);
+        let double = tags.iter().filter(|&&t| t == Tags::DoubleTag).count() as i32;
+        let negative = editions.iter().filter(|&&e| e == Some(Editions::Negative)).count() as i32;
+        let scorer = Scorer::new(vec!["tag=Double Tag:3".parse().unwrap(), "edition=Negative:5".parse().unwrap()]);
+        assert_eq!(scorer.score(&mut view), 3 * double + 5 * negative, "{seed}");
+    }
+}
+
+#[test]
+fn shop_editions_follow_base_rates() {
+    // 0.3% negative, 0.3% polychrome, 1.4% holographic, 2% foil at the base edition rate.
+    let mut counts = [0usize; 5];
+    let n = 20_000;
+    for i in 0..n {
+        let seed = format!("E{i}");
+        for e in SeedView::new(&seed).shop_editions(1).into_iter().flatten() {
+            counts[e as usize] += 1;
+        }
+    }
+    let jokers: usize = counts.iter().sum();
+    let rate = |e: Editions| counts[e as usize] as f64 / jokers as f64;
+    assert!((rate(Editions::Negative) - 0.003).abs() < 0.0015, "{counts:?}");
+    assert!((rate(Editions::Holographic) - 0.014).abs() < 0.004, "{counts:?}");
+    assert!((rate(Editions::Foil) - 0.02).abs() < 0.005, "{counts:?}");
+    assert!((rate(Editions::None) - 0.96).abs() < 0.01, "{counts:?}");
+}
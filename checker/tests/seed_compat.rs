@@ -3,7 +3,8 @@ use serde::Deserialize;
 use std::fs;
 use common::game::GameState;
 use common::items::RandomSource;
-use common::names::{boss_name, voucher_name, tarot_name, planet_name, tag_name};
+use common::names::{ItemName, voucher_name, tarot_name, planet_name, tag_name};
+use common::trace::to_json_lines;
 
 #[derive(Deserialize)]
 struct AnalyzeState {
@@ -110,9 +111,9 @@ fn ante1_boss_and_voucher_match() {
             }
         }
 
-        // Joker diagnostic (not asserting yet as rarity path not mirrored)
+        // Joker diagnostic (not asserted: the queue's first joker need not sit in the first shop slot)
         if let Some(expected_joker) = first_joker_json {
-            let _ours_joker = game.next_joker_from_at_ante(RandomSource::Shop, 1);
+            let _ours_joker = game.next_shop_item_at_ante(1, false);
             // We could log debug, but keep noise minimal for now.
             let _ = expected_joker;
         }
@@ -131,17 +132,15 @@ fn ante1_boss_and_voucher_match() {
             if let Some(tags_arr) = ante_val.get("tags").and_then(|t| t.as_array()) {
                 // Fresh game state for tag-only generation
                 let mut tag_game = GameState::new(&parsed.analyze_state.seed, 1);
+                tag_game.enable_trace();
                 // Draw only tags for this ante; tags are independent (no locking/resample)
-                let mut ours: Vec<String> = Vec::new();
-                for _draw in 0..tags_arr.len() {
-                    // debug line with node id, mixed, rand, idx, and choice name
-                    let (id, mixed, rand, idx, name) = tag_game.debug_tag_once(ante_num);
-                    println!(
-                        "RS_TAG ante={} id={} mixed={:.13} rand={:.13} idx={} name={}",
-                        ante_num, id, mixed, rand, idx, name
-                    );
-                    // Use this debug choice as authoritative to avoid a second advancement
-                    ours.push(name.to_string());
+                let ours: Vec<String> = (0..tags_arr.len())
+                    .map(|_| tag_name(&tag_game.next_tag_from_at_ante(ante_num)).to_string())
+                    .collect();
+                let expected: Vec<&str> = tags_arr.iter().filter_map(|v| v.as_str()).collect();
+                if ours != expected {
+                    // Stream-by-stream trace, to diff against one logged from Blueprint
+                    print!("{}", to_json_lines(&tag_game.take_trace()));
                 }
                 for (i, v) in tags_arr.iter().enumerate() {
                     if let Some(exp) = v.as_str() {
@@ -156,14 +155,11 @@ fn ante1_boss_and_voucher_match() {
             }
         }
 
-        let boss_str = boss_name(&boss).to_string();
-        let voucher_str = voucher_name(&voucher).to_string();
-
-        if boss_str != ante1.boss {
-            errors.push(format!("seed {}: boss mismatch: ours='{}' expected='{}'", parsed.analyze_state.seed, boss_str, ante1.boss));
+        if boss.name() != ante1.boss {
+            errors.push(format!("seed {}: boss mismatch: ours='{}' expected='{}'", parsed.analyze_state.seed, boss.name(), ante1.boss));
         }
-        if voucher_str != ante1.voucher {
-            errors.push(format!("seed {}: voucher mismatch: ours='{}' expected='{}'", parsed.analyze_state.seed, voucher_str, ante1.voucher));
+        if voucher.name() != ante1.voucher {
+            errors.push(format!("seed {}: voucher mismatch: ours='{}' expected='{}'", parsed.analyze_state.seed, voucher.name(), ante1.voucher));
         }
 
         tested += 1;
@@ -0,0 +1,67 @@
+use common::filter::SeedView;
+use common::game::GameState;
+use common::items::{Consumable, JokerRarity};
+use common::shop::{ShopItem, ShopRates};
+
+#[test]
+fn slot_types_follow_the_rates() {
+    let mut counts = [0usize; 3];
+    let n = 20_000;
+    for i in 0..n {
+        for item in SeedView::new(&format!("S{i}")).shop(1) {
+            match item {
+                ShopItem::Joker(_) => counts[0] += 1,
+                ShopItem::Consumable(Consumable::Tarot(_)) => counts[1] += 1,
+                ShopItem::Consumable(Consumable::Planet(_)) => counts[2] += 1,
+                other => panic!("no {other:?} at the base rates"),
+            }
+        }
+    }
+    let rate = |i: usize| counts[i] as f64 / (2 * n) as f64;
+    assert!((rate(0) - 20.0 / 28.0).abs() < 0.01, "{counts:?}");
+    assert!((rate(1) - 4.0 / 28.0).abs() < 0.01, "{counts:?}");
+    assert!((rate(2) - 4.0 / 28.0).abs() < 0.01, "{counts:?}");
+}
+
+#[test]
+fn shop_jokers_come_from_the_rarity_pools() {
+    let mut rarities = [0usize; 3];
+    for i in 0..5_000 {
+        let mut view = SeedView::new(&format!("R{i}"));
+        for ante in 1..=2 {
+            let [a, b] = view.shop(ante).map(|item| item.joker());
+            for joker in [a, b].into_iter().flatten() {
+                match joker.rarity {
+                    JokerRarity::Common => rarities[0] += 1,
+                    JokerRarity::Uncommon => rarities[1] += 1,
+                    JokerRarity::Rare => rarities[2] += 1,
+                    JokerRarity::Legendary => panic!("legendary {joker:?} in a shop"),
+                }
+            }
+            if let (Some(a), Some(b)) = (a, b) {
+                assert_ne!(a.joker, b.joker, "seed R{i} ante {ante}");
+            }
+        }
+    }
+    assert!(rarities[0] > rarities[1] && rarities[1] > rarities[2] && rarities[2] > 0, "{rarities:?}");
+}
+
+#[test]
+fn only_weighted_types_show() {
+    let mut game = GameState::new("ABCD1234", 1);
+    let none = ShopRates { joker_rate: 0.0, tarot_rate: 0.0, planet_rate: 0.0, playing_card_rate: 0.0, spectral_rate: 0.0 };
+    game.run.shop_rates = ShopRates { playing_card_rate: 4.0, ..none };
+    for _ in 0..50 {
+        assert!(matches!(game.next_shop_item_at_ante(1, false), ShopItem::Card(_)));
+    }
+    game.run.shop_rates = ShopRates { spectral_rate: 2.0, ..none };
+    for _ in 0..10 {
+        let item = game.next_shop_item_at_ante(1, false);
+        assert!(matches!(item, ShopItem::Consumable(Consumable::Spectral(_))), "{item:?}");
+    }
+    // Each spectral stays on show, so ten slots hold ten different cards.
+    let shown = &game.run.shop[50..];
+    for (i, item) in shown.iter().enumerate() {
+        assert!(!shown[..i].contains(item), "{item:?}");
+    }
+}
@@ -0,0 +1,25 @@
+//! Seeds and fixtures shared by the integration tests. Each test crate uses
+//! only some of them.
+#![allow(dead_code)]
+
+use common::items::{Editions, Joker, JokerRarity, JokerTypes};
+use common::names::ItemName;
+
+/// Eight-character, shorter and single-character seeds.
+pub const SEEDS: [&str; 5] = ["ABCD1234", "7LB2WVPK", "HELLO1", "1", "9Z9Z9Z9Z"];
+/// Seeds checked after [`SEEDS`] where each seed is cheap.
+pub const MORE_SEEDS: [&str; 7] = ["TUTORIAL", "AAAAAAAA", "K9P2", "SEEDSEED", "Q", "ZZZZZZZZ", "3N4PL8YW"];
+
+/// `joker` as a run holds it: common, no edition, no stickers.
+pub fn joker(joker: JokerTypes) -> Joker {
+    Joker { joker, rarity: JokerRarity::Common, edition: Editions::None, stickers: [false; 3] }
+}
+
+/// [`joker`] with the Eternal sticker.
+pub fn eternal(joker: JokerTypes) -> Joker {
+    Joker { stickers: [true, false, false], ..self::joker(joker) }
+}
+
+pub fn all<T: ItemName>() -> Vec<T> {
+    T::iter().collect()
+}
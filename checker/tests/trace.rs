@@ -0,0 +1,88 @@
+use common::game::GameState;
+use common::random::Random;
+use common::reference::{self, PseudoRandom};
+use common::trace::{to_json_lines, TraceEvent};
+use common::util::LuaRandom;
+
+#[test]
+fn trace_records_each_stream_use() {
+    let mut rng = Random::new(b"ABCD1234");
+    let _ = rng.get_node_str("boss");
+    rng.enable_trace();
+    let boss = rng.random_str("boss");
+    let tag = rng.get_node_str("Tag1");
+    let _ = rng.rand_int_str("Tag1", 1, 10);
+    let _ = rng.get_node(3);
+
+    let mut lua = PseudoRandom::new("ABCD1234");
+    let _ = lua.pseudoseed("boss");
+    let events = rng.take_trace();
+    let ids: Vec<&str> = events.iter().map(|e| e.id.as_str()).collect();
+    assert_eq!(ids, ["boss", "Tag1", "Tag1"]);
+    for event in &events {
+        assert_eq!(event.seed, "ABCD1234");
+        assert_eq!(event.mixed.to_bits(), lua.pseudoseed(&event.id).to_bits(), "{}", event.id);
+        assert_eq!(event.node.to_bits(), lua.nodes[&event.id].to_bits());
+        if let Some(draw) = event.draw {
+            assert_eq!(draw.to_bits(), LuaRandom::new(event.mixed).random().to_bits());
+        }
+    }
+    assert_eq!(events[0].initial, None, "boss was first used before tracing");
+    assert_eq!(events[0].draw, Some(boss));
+    let initial = reference::pseudohash("Tag1ABCD1234");
+    assert_eq!(events[1].initial.map(f64::to_bits), Some(initial.to_bits()));
+    assert_eq!(events[1].mixed, tag);
+    assert_eq!(events[1].draw, None);
+    assert_eq!(events[2].initial, None);
+    assert!(events[2].draw.is_some());
+
+    assert!(rng.take_trace().is_empty());
+}
+
+#[test]
+fn untraced_streams_record_nothing() {
+    let mut rng = Random::new(b"ABCD1234");
+    let _ = rng.random_str("boss");
+    assert!(rng.take_trace().is_empty());
+}
+
+#[test]
+fn json_lines_round_trip() {
+    let mut game = GameState::new("7LB2WVPK", 1);
+    game.enable_trace();
+    let _ = game.next_boss();
+    let _ = game.next_tag_from_at_ante(2);
+    let events = game.take_trace();
+    assert!(events.len() >= 2);
+
+    let lines = to_json_lines(&events);
+    assert_eq!(lines.lines().count(), events.len());
+    for (line, event) in lines.lines().zip(&events) {
+        let v: serde_json::Value = serde_json::from_str(line).expect("valid JSON");
+        assert_eq!(v["seed"], "7LB2WVPK");
+        assert_eq!(v["id"], event.id.as_str());
+        assert_eq!(v["initial"].is_null(), event.initial.is_none());
+        assert_eq!(v["draw"].is_null(), event.draw.is_none());
+        // Numbers are written in shortest round-trip form.
+        assert!(line.contains(&format!("\"mixed\":{:?},", event.mixed)), "{line}");
+        let node: f64 = line.split("\"node\":").nth(1).unwrap().split(',').next().unwrap().parse().unwrap();
+        assert_eq!(node.to_bits(), event.node.to_bits());
+    }
+}
+
+#[test]
+fn json_escapes_strings() {
+    let event = TraceEvent {
+        seed: "a\"b\\c".into(),
+        id: "x\ny".into(),
+        initial: None,
+        node: 0.5,
+        mixed: 0.25,
+        draw: Some(f64::NAN),
+    };
+    let v: serde_json::Value = serde_json::from_str(&event.to_json()).expect("valid JSON");
+    assert_eq!(v["seed"], "a\"b\\c");
+    assert_eq!(v["id"], "x\ny");
+    assert!(v["initial"].is_null());
+    assert!(v["draw"].is_null());
+}
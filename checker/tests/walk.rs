@@ -0,0 +1,63 @@
+use common::util::pseudohash_bytes;
+use common::walk::{encode_seed, length_indices, walk_hashed_seeds, CHARSET, MAX_SEED_LEN, SEED_SPACE};
+use std::collections::HashSet;
+
+fn seed(n: u64) -> String {
+    let mut buf = [0u8; MAX_SEED_LEN];
+    let (off, len) = encode_seed(n, &mut buf);
+    String::from_utf8(buf[off..off + len].to_vec()).unwrap()
+}
+
+#[test]
+fn first_character_varies_fastest() {
+    assert_eq!(seed(0), "A");
+    assert_eq!(seed(34), "9");
+    assert_eq!(seed(35), "BA");
+    assert_eq!(seed(36), "CA");
+    assert_eq!(seed(35 + 33), "9A");
+    assert_eq!(seed(35 + 34), "BB");
+    assert_eq!(seed(35 * 35 - 1), "99");
+    assert_eq!(seed(35 * 35), "BAA");
+    assert_eq!(seed(SEED_SPACE - 1), "99999999");
+}
+
+#[test]
+fn each_length_is_enumerated_once() {
+    for len in 1..=4 {
+        let indices = length_indices(len);
+        let seeds: HashSet<String> = indices.clone().map(seed).collect();
+        assert_eq!(seeds.len() as u64, indices.end - indices.start, "length {len} repeats a seed");
+        for s in &seeds {
+            assert_eq!(s.len(), len);
+            assert!(s.bytes().all(|b| CHARSET.contains(&b)));
+            assert!(len == 1 || !s.starts_with('A'), "{s} has a leading zero digit");
+        }
+    }
+    assert_eq!(length_indices(2), 35..35 * 35);
+    assert_eq!(length_indices(MAX_SEED_LEN).end, SEED_SPACE);
+}
+
+fn check_walk(start: u64, end: u64) {
+    let mut next = start;
+    walk_hashed_seeds(start..end, |n, s, hashed| {
+        assert_eq!(n, next, "walk skipped or repeated an index");
+        assert_eq!(std::str::from_utf8(s).unwrap(), seed(n));
+        assert_eq!(hashed.to_bits(), pseudohash_bytes([s]).to_bits(), "hash of {}", seed(n));
+        next += 1;
+    });
+    assert_eq!(next, end.min(SEED_SPACE));
+}
+
+#[test]
+fn walk_matches_independent_hashing() {
+    // Across every length boundary, and long runs in the widest lengths.
+    let mut boundary = 35;
+    while boundary < SEED_SPACE {
+        check_walk(boundary.saturating_sub(3000), boundary + 3000);
+        boundary *= 35;
+    }
+    check_walk(0, 100_000);
+    check_walk(35u64.pow(7) + 123_456, 35u64.pow(7) + 523_456);
+    check_walk(SEED_SPACE - 50_000, SEED_SPACE + 10);
+    check_walk(1000, 1000);
+}
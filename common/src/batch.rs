@@ -0,0 +1,41 @@
+//! Lane-parallel seed hashing, the part of the CPU checksum search that runs
+//! per seed. [`pseudohash_lanes`] runs the scalar algorithm on `N` strings at
+//! once, written as plain loops over lanes with no data-dependent branches so
+//! the compiler can keep every lane in one SIMD register: AVX2 holds 4
+//! doubles, SSE2 and NEON hold 2.
+//!
+//! Results are bit-for-bit those of the scalar function. The only operation
+//! that differs is `floor`, done with the 2^52 rounding trick, which is exact
+//! over the inputs the game produces.
+
+use core::f64::consts::PI;
+
+/// Lanes per batch on the widest common target (AVX2).
+pub const LANES: usize = 4;
+
+const TWO_52: f64 = 4503599627370496.0;
+
+/// `floor(t)` for `t >= 0`. Adding 2^52 rounds `t` to an integer, which is
+/// stepped back by one where it rounded up; from 2^52 on `t` is an integer.
+#[inline(always)]
+fn floor_non_negative(t: f64) -> f64 {
+    let rounded = (t + TWO_52) - TWO_52;
+    let floored = if rounded > t { rounded - 1.0 } else { rounded };
+    if t >= TWO_52 { t } else { floored }
+}
+
+/// [`crate::util::pseudohash_bytes`] of `N` strings of the same length.
+#[inline(always)]
+pub fn pseudohash_lanes<const N: usize>(strings: [&[u8]; N]) -> [f64; N] {
+    let len = strings[0].len();
+    assert!(strings.iter().all(|s| s.len() == len), "lanes must hold strings of one length");
+    let mut num = [1.0f64; N];
+    for i in (1..=len).rev() {
+        let pi_i = PI * i as f64;
+        for (num, s) in num.iter_mut().zip(strings) {
+            let t = (1.1239285023 / *num) * s[i - 1] as f64 * PI + pi_i;
+            *num = t - floor_non_negative(t);
+        }
+    }
+    num
+}
@@ -0,0 +1,189 @@
+//! A round against a blind: the deck shuffled with `nr{ante}`, cards drawn
+//! from its top, and the boss effects that draw from the seed along the way.
+//! The Hook discards two random held cards after each hand (`hook`), The Wheel
+//! draws each card face down 1 time in 7 (`wheel`), Cerulean Bell forces a
+//! random held card into every selection (`cerulean_bell`) and Crimson Heart
+//! disables a random joker after each hand (`crimson_heart`). The House, The
+//! Mark, The Fish and The Serpent draw nothing from the seed but change what
+//! the draws show. Random picks go by `sort_id`, as `pseudorandom_element`
+//! sorts cards.
+
+use crate::cards;
+use crate::items::{Bosses, CardTypes, EnhancementTypes, JokerTypes};
+use crate::random::Random;
+use crate::run::RunState;
+use alloc::format;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Why a play or discard was refused.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlayError {
+    /// Plays and discards take 1 to 5 cards.
+    Selection,
+    /// A selected `sort_id` is not in hand.
+    NotInHand(usize),
+    /// Cerulean Bell's forced card was left out.
+    Forced(usize),
+}
+
+impl fmt::Display for PlayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlayError::Selection => write!(f, "select 1 to 5 cards"),
+            PlayError::NotInHand(id) => write!(f, "card {id} is not in hand"),
+            PlayError::Forced(id) => write!(f, "card {id} is forced into the selection"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlindRound {
+    /// The boss, `None` for the Small and Big Blinds.
+    pub boss: Option<Bosses>,
+    /// Whether the boss is disabled, by Chicot or Luchador.
+    pub disabled: bool,
+    /// `sort_id`s left in the deck, the next card to draw last.
+    pub deck: Vec<usize>,
+    /// Held cards drawn face down.
+    pub face_down: Vec<usize>,
+    /// Cerulean Bell's forced card.
+    pub forced: Option<usize>,
+    /// Index into [`RunState::jokers`] of the joker Crimson Heart disabled.
+    pub disabled_joker: Option<usize>,
+    pub hands_played: u32,
+    pub discards_used: u32,
+    // Set by a hand played and cleared by the next draw; The Fish and Crimson
+    // Heart act on it.
+    prepped: bool,
+}
+
+impl BlindRound {
+    /// Start a round in `ante`: every card not in hand shuffled with
+    /// `nr{ante}`, nothing drawn yet. Chicot disables the boss.
+    pub fn new(random: &mut Random, run: &RunState, boss: Option<Bosses>, ante: i32) -> Self {
+        let mut deck: Vec<usize> = run.deck.iter().map(|c| c.sort_id).filter(|id| !run.hand.contains(id)).collect();
+        cards::pseudoshuffle(random, &format!("nr{ante}"), &mut deck);
+        Self {
+            boss,
+            disabled: run.holds_joker(JokerTypes::Chicot),
+            deck,
+            face_down: Vec::new(),
+            forced: None,
+            disabled_joker: None,
+            hands_played: 0,
+            discards_used: 0,
+            prepped: false,
+        }
+    }
+
+    // The boss while its effect applies.
+    fn active(&self) -> Option<Bosses> {
+        self.boss.filter(|_| !self.disabled)
+    }
+
+    /// Draw up to the hand size, or three cards against The Serpent after a
+    /// play or discard, then apply the boss's `drawn_to_hand`. Returns the
+    /// cards drawn, in order.
+    pub fn draw(&mut self, random: &mut Random, run: &mut RunState) -> Vec<usize> {
+        let mut n = (run.hand_size.max(0) as usize).saturating_sub(run.hand.len());
+        if self.active() == Some(Bosses::TheSerpent) && self.hands_played + self.discards_used > 0 {
+            n = 3;
+        }
+        let mut drawn = Vec::new();
+        for _ in 0..n.min(self.deck.len()) {
+            let id = self.deck.pop().expect("drawn within the deck");
+            run.hand.push(id);
+            if self.stays_flipped(random, run, id) {
+                self.face_down.push(id);
+            }
+            drawn.push(id);
+        }
+        self.drawn_to_hand(random, run);
+        drawn
+    }
+
+    // `Blind:stay_flipped` for a card drawn to hand.
+    fn stays_flipped(&self, random: &mut Random, run: &RunState, id: usize) -> bool {
+        match self.active() {
+            Some(Bosses::TheWheel) => run.probabilities.hits(random.random_str("wheel"), 7),
+            Some(Bosses::TheHouse) => self.hands_played == 0 && self.discards_used == 0,
+            // Stone cards have no rank, but Pareidolia makes every card a face.
+            Some(Bosses::TheMark) => {
+                run.holds_joker(JokerTypes::Pareidolia)
+                    || run.card(id).is_some_and(|c| {
+                        c.enhancement != EnhancementTypes::Stone
+                            && matches!(c.rank, CardTypes::Jack | CardTypes::Queen | CardTypes::King)
+                    })
+            }
+            Some(Bosses::TheFish) => self.prepped,
+            _ => false,
+        }
+    }
+
+    // `Blind:drawn_to_hand`.
+    fn drawn_to_hand(&mut self, random: &mut Random, run: &RunState) {
+        match self.active() {
+            Some(Bosses::CeruleanBell) if !self.forced.is_some_and(|id| run.hand.contains(&id)) => {
+                let mut held = run.hand.clone();
+                held.sort_unstable();
+                self.forced = (!held.is_empty())
+                    .then(|| held[random.rand_int_str("cerulean_bell", 0, held.len() as i32 - 1) as usize]);
+            }
+            // A joker disabled by the last hand is spared, unless it is the only one.
+            Some(Bosses::CrimsonHeart) if self.prepped && !run.jokers.is_empty() => {
+                let jokers: Vec<usize> =
+                    (0..run.jokers.len()).filter(|&i| Some(i) != self.disabled_joker || run.jokers.len() < 2).collect();
+                self.disabled_joker = Some(jokers[random.rand_int_str("crimson_heart", 0, jokers.len() as i32 - 1) as usize]);
+            }
+            _ => {}
+        }
+        self.prepped = false;
+    }
+
+    fn check(&self, run: &RunState, cards: &[usize]) -> Result<(), PlayError> {
+        if cards.is_empty() || cards.len() > 5 {
+            return Err(PlayError::Selection);
+        }
+        if let Some(&id) = cards.iter().find(|id| !run.hand.contains(id)) {
+            return Err(PlayError::NotInHand(id));
+        }
+        match self.forced {
+            Some(id) if self.active() == Some(Bosses::CeruleanBell) && !cards.contains(&id) => Err(PlayError::Forced(id)),
+            _ => Ok(()),
+        }
+    }
+
+    fn leave_hand(&mut self, run: &mut RunState, cards: &[usize]) {
+        run.hand.retain(|id| !cards.contains(id));
+        self.face_down.retain(|id| !cards.contains(id));
+    }
+
+    /// Play `cards` from hand. The Hook then discards up to two random held
+    /// cards, which this returns; The Fish and Crimson Heart act on the next
+    /// draw.
+    pub fn play(&mut self, random: &mut Random, run: &mut RunState, cards: &[usize]) -> Result<Vec<usize>, PlayError> {
+        self.check(run, cards)?;
+        self.leave_hand(run, cards);
+        self.hands_played += 1;
+        self.prepped = true;
+        let mut hooked = Vec::new();
+        if self.active() == Some(Bosses::TheHook) {
+            let mut held = run.hand.clone();
+            held.sort_unstable();
+            for _ in 0..held.len().min(2) {
+                hooked.push(held.remove(random.rand_int_str("hook", 0, held.len() as i32 - 1) as usize));
+            }
+            self.leave_hand(run, &hooked);
+        }
+        Ok(hooked)
+    }
+
+    /// Discard `cards` from hand.
+    pub fn discard(&mut self, run: &mut RunState, cards: &[usize]) -> Result<(), PlayError> {
+        self.check(run, cards)?;
+        self.leave_hand(run, cards);
+        self.discards_used += 1;
+        Ok(())
+    }
+}
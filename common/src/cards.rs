@@ -0,0 +1,241 @@
+//! Playing cards as the game creates them outside the starting deck: Standard
+//! packs, shop slots, and the cards added by Familiar, Grim, Incantation,
+//! Certificate and Marble Joker. Every function draws from the same named
+//! streams as the game, in the same order.
+//!
+//! The game picks fronts with `pseudorandom_element(G.P_CARDS, ...)`, which
+//! sorts the card keys as strings: suits C, D, H, S and within a suit the ranks
+//! 2 to 9, then A, J, K, Q, T. [`FRONTS`] keeps that order.
+
+use crate::items::{Card, CardSuits, CardTypes, Editions, EnhancementTypes, SealTypes};
+use crate::random::Random;
+use crate::util::LuaRandom;
+use alloc::vec::Vec;
+
+const SUITS: [CardSuits; 4] = [CardSuits::Clubs, CardSuits::Diamonds, CardSuits::Hearts, CardSuits::Spades];
+const RANKS: [CardTypes; 13] = [
+    CardTypes::Two,
+    CardTypes::Three,
+    CardTypes::Four,
+    CardTypes::Five,
+    CardTypes::Six,
+    CardTypes::Seven,
+    CardTypes::Eight,
+    CardTypes::Nine,
+    CardTypes::Ace,
+    CardTypes::Jack,
+    CardTypes::King,
+    CardTypes::Queen,
+    CardTypes::Ten,
+];
+
+/// Every card front in the game's `G.P_CARDS` key order.
+pub const FRONTS: [(CardTypes, CardSuits); 52] = {
+    let mut out = [(CardTypes::Two, CardSuits::Clubs); 52];
+    let mut i = 0;
+    while i < 52 {
+        out[i] = (RANKS[i % 13], SUITS[i / 13]);
+        i += 1;
+    }
+    out
+};
+
+/// The standard 52-card deck as `start_run` creates it: sorted by suit and
+/// rank key, which is [`FRONTS`] order, with `sort_id` counting up from 0.
+pub fn starting_deck() -> Vec<Card> {
+    FRONTS
+        .iter()
+        .enumerate()
+        .map(|(i, &front)| Card { sort_id: i, ..card(front, EnhancementTypes::None) })
+        .collect()
+}
+
+/// `G.P_CENTER_POOLS.Enhanced`, in the game's order.
+pub const ENHANCEMENTS: [EnhancementTypes; 8] = [
+    EnhancementTypes::Bonus,
+    EnhancementTypes::Mult,
+    EnhancementTypes::Wild,
+    EnhancementTypes::Glass,
+    EnhancementTypes::Steel,
+    EnhancementTypes::Stone,
+    EnhancementTypes::Gold,
+    EnhancementTypes::Lucky,
+];
+
+// The Spectral cards that add enhanced cards leave Stone out of the pool.
+const SPECTRAL_ENHANCEMENTS: [EnhancementTypes; 7] = [
+    EnhancementTypes::Bonus,
+    EnhancementTypes::Mult,
+    EnhancementTypes::Wild,
+    EnhancementTypes::Glass,
+    EnhancementTypes::Steel,
+    EnhancementTypes::Gold,
+    EnhancementTypes::Lucky,
+];
+
+// Suit lists written out in the Spectral card effects.
+const SPECTRAL_SUITS: [CardSuits; 4] = [CardSuits::Spades, CardSuits::Hearts, CardSuits::Diamonds, CardSuits::Clubs];
+
+/// Where a Standard pack or shop card came from; the game appends it to the
+/// `Enhanced` and `front` stream ids.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CardSource {
+    StandardPack,
+    Shop,
+}
+
+impl CardSource {
+    fn code(self) -> &'static str {
+        match self {
+            CardSource::StandardPack => "sta",
+            CardSource::Shop => "sho",
+        }
+    }
+}
+
+// `pseudorandom_element(list, pseudoseed(id))`.
+#[inline]
+fn element<T: Copy>(random: &mut Random, id: &str, items: &[T]) -> T {
+    items[random.rand_int_str(id, 0, items.len() as i32 - 1) as usize]
+}
+
+fn card(front: (CardTypes, CardSuits), enhancement: EnhancementTypes) -> Card {
+    // Callers adding the card to a deck give it the next `sort_id`.
+    Card { rank: front.0, suit: front.1, enhancement, edition: Editions::None, seal: SealTypes::None, sort_id: 0 }
+}
+
+/// The game's `poll_edition(id, rate_mod, no_negative)`. `edition_rate` is
+/// `G.GAME.edition_rate`: 1, or 2 with Hone and 4 with Glow Up.
+pub fn poll_edition(random: &mut Random, id: &str, rate_mod: f64, edition_rate: f64, no_negative: bool) -> Editions {
+    let poll = random.random_str(id);
+    if poll > 1.0 - 0.003 * rate_mod && !no_negative {
+        Editions::Negative
+    } else if poll > 1.0 - 0.006 * edition_rate * rate_mod {
+        Editions::Polychrome
+    } else if poll > 1.0 - 0.02 * edition_rate * rate_mod {
+        Editions::Holographic
+    } else if poll > 1.0 - 0.04 * edition_rate * rate_mod {
+        Editions::Foil
+    } else {
+        Editions::None
+    }
+}
+
+/// `create_card('Base' | 'Enhanced', ...)`: an enhancement from
+/// `Enhanced{source}{ante}` when `enhanced`, then a front from
+/// `front{source}{ante}`.
+pub fn create_card(random: &mut Random, source: CardSource, ante: i32, enhanced: bool) -> Card {
+    let enhancement = if enhanced {
+        element(random, &alloc::format!("Enhanced{}{ante}", source.code()), &ENHANCEMENTS)
+    } else {
+        EnhancementTypes::None
+    };
+    card(element(random, &alloc::format!("front{}{ante}", source.code()), &FRONTS), enhancement)
+}
+
+// Seals split the poll into quarters, Purple lowest.
+fn seal_from_poll(poll: f64) -> SealTypes {
+    if poll > 0.75 {
+        SealTypes::Red
+    } else if poll > 0.5 {
+        SealTypes::Blue
+    } else if poll > 0.25 {
+        SealTypes::Gold
+    } else {
+        SealTypes::Purple
+    }
+}
+
+/// One card of a Standard pack opened in `ante`: enhanced 40% of the time,
+/// an edition at twice the usual rate (never Negative) and a seal 20% of the time.
+pub fn standard_pack_card(random: &mut Random, ante: i32, edition_rate: f64) -> Card {
+    let enhanced = random.random_str(&alloc::format!("stdset{ante}")) > 0.6;
+    let mut card = create_card(random, CardSource::StandardPack, ante, enhanced);
+    card.edition = poll_edition(random, &alloc::format!("standard_edition{ante}"), 2.0, edition_rate, true);
+    if random.random_str(&alloc::format!("stdseal{ante}")) > 1.0 - 0.02 * 10.0 {
+        card.seal = seal_from_poll(random.random_str(&alloc::format!("stdsealtype{ante}")));
+    }
+    card
+}
+
+/// A shop slot already known to hold a playing card. Magic Trick only makes
+/// such slots possible. With Illusion the card is enhanced 40% of the time
+/// and gets an edition 20% of the time.
+///
+/// The game polls the `illusion` stream for the card type whenever it fills
+/// any shop slot while Illusion is owned, so callers tracking a whole shop
+/// must draw it for the other slots too.
+pub fn shop_card(random: &mut Random, ante: i32, illusion: bool) -> Card {
+    let enhanced = illusion && random.random_str("illusion") > 0.6;
+    let mut card = create_card(random, CardSource::Shop, ante, enhanced);
+    if illusion && random.random_str("illusion") > 0.8 {
+        let poll = random.random_str("illusion");
+        card.edition = if poll > 1.0 - 0.15 {
+            Editions::Polychrome
+        } else if poll > 0.5 {
+            Editions::Holographic
+        } else {
+            Editions::Foil
+        };
+    }
+    card
+}
+
+// The Spectral cards that add enhanced cards draw a rank and suit from their
+// own stream, then the enhancement from `spe_card`. The card they destroy
+// depends on the hand and is not drawn here.
+fn spectral_card(random: &mut Random, id: &str, ranks: &[CardTypes]) -> Card {
+    let rank = if ranks.len() == 1 { ranks[0] } else { element(random, id, ranks) };
+    let suit = element(random, id, &SPECTRAL_SUITS);
+    card((rank, suit), element(random, "spe_card", &SPECTRAL_ENHANCEMENTS))
+}
+
+/// The three enhanced face cards Familiar adds.
+pub fn familiar_cards(random: &mut Random) -> [Card; 3] {
+    let faces = [CardTypes::Jack, CardTypes::Queen, CardTypes::King];
+    core::array::from_fn(|_| spectral_card(random, "familiar_create", &faces))
+}
+
+/// The two enhanced Aces Grim adds.
+pub fn grim_cards(random: &mut Random) -> [Card; 2] {
+    core::array::from_fn(|_| spectral_card(random, "grim_create", &[CardTypes::Ace]))
+}
+
+/// The four enhanced numbered cards Incantation adds.
+pub fn incantation_cards(random: &mut Random) -> [Card; 4] {
+    let numbers = [
+        CardTypes::Two,
+        CardTypes::Three,
+        CardTypes::Four,
+        CardTypes::Five,
+        CardTypes::Six,
+        CardTypes::Seven,
+        CardTypes::Eight,
+        CardTypes::Nine,
+        CardTypes::Ten,
+    ];
+    core::array::from_fn(|_| spectral_card(random, "incantation_create", &numbers))
+}
+
+/// The sealed card Certificate adds at the start of a round.
+pub fn certificate_card(random: &mut Random) -> Card {
+    let mut card = card(element(random, "cert_fr", &FRONTS), EnhancementTypes::None);
+    card.seal = seal_from_poll(random.random_str("certsl"));
+    card
+}
+
+/// The Stone card Marble Joker adds when a blind is selected.
+pub fn marble_card(random: &mut Random) -> Card {
+    card(element(random, "marb_fr", &FRONTS), EnhancementTypes::Stone)
+}
+
+/// The game's `pseudoshuffle` over cards named by `sort_id`: sort them, seed
+/// `math.random` once from `pseudoseed(id)`, then swap from the back.
+pub fn pseudoshuffle(random: &mut Random, id: &str, sort_ids: &mut [usize]) {
+    sort_ids.sort_unstable();
+    let mut lua = LuaRandom::new(random.get_node_str(id));
+    for i in (1..sort_ids.len()).rev() {
+        let j = lua.randint(1, i as i32 + 1) as usize - 1;
+        sort_ids.swap(i, j);
+    }
+}
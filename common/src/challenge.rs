@@ -0,0 +1,307 @@
+//! The game's challenges, from `challenges.lua`: the jokers, consumables,
+//! vouchers and deck each run starts with, the starting modifiers, and the
+//! cards, tags and bosses it bans. Bans take cards out of their pools the way
+//! held cards are ([`crate::random::ItemChoice::retry_in`]), and bosses and
+//! vouchers out through [`crate::lock::Lock`]. Custom rules that draw nothing
+//! from the seed (no interest, debuffed cards, inflation, ...) are left out.
+
+use crate::items::{
+    Bosses, CardTypes, Consumable, Editions, EnhancementTypes, Joker, JokerTypes, Pack, SealTypes, Spectral, Tags, Tarots, Vouchers,
+};
+use crate::names::{self, boss_name, joker_name, pack_name, planet_name, spectral_name, tag_name, tarot_name, voucher_name};
+use crate::pools;
+
+/// A card, tag or boss a challenge keeps out of the run.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Ban {
+    Joker(JokerTypes),
+    Consumable(Consumable),
+    Voucher(Vouchers),
+    /// Every size of the pack.
+    Pack(Pack),
+    Tag(Tags),
+    Boss(Bosses),
+}
+
+impl Ban {
+    /// The display name, as [`crate::lock::Lock`] keys items.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Ban::Joker(j) => joker_name(j),
+            Ban::Consumable(Consumable::Tarot(t)) => tarot_name(t),
+            Ban::Consumable(Consumable::Planet(p)) => planet_name(p),
+            Ban::Consumable(Consumable::Spectral(s)) => spectral_name(s),
+            Ban::Voucher(v) => voucher_name(v),
+            Ban::Pack(p) => pack_name(p),
+            Ban::Tag(t) => tag_name(t),
+            Ban::Boss(b) => boss_name(b),
+        }
+    }
+}
+
+/// A joker a challenge starts with.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct StartingJoker {
+    pub joker: JokerTypes,
+    pub eternal: bool,
+}
+
+impl StartingJoker {
+    pub fn to_joker(self) -> Joker {
+        Joker {
+            joker: self.joker,
+            rarity: pools::rarity(self.joker),
+            edition: Editions::None,
+            stickers: [self.eternal, false, false],
+        }
+    }
+}
+
+/// `rules.modifiers`: starting values that replace the deck's.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Modifiers {
+    pub dollars: Option<i32>,
+    pub hands: Option<i32>,
+    pub discards: Option<i32>,
+    pub hand_size: Option<i32>,
+    pub joker_slots: Option<usize>,
+    pub consumable_slots: Option<usize>,
+}
+
+impl Modifiers {
+    pub const NONE: Modifiers =
+        Modifiers { dollars: None, hands: None, discards: None, hand_size: None, joker_slots: None, consumable_slots: None };
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Challenge {
+    pub name: &'static str,
+    /// The game's key, `c_omelette_1` and so on.
+    pub id: &'static str,
+    pub jokers: &'static [StartingJoker],
+    pub consumables: &'static [Consumable],
+    /// Vouchers the run starts with, redeemed in order.
+    pub vouchers: &'static [Vouchers],
+    pub modifiers: Modifiers,
+    /// Ranks left out of the starting deck.
+    pub removed_ranks: &'static [CardTypes],
+    /// Enhancement and seal put on every starting card.
+    pub enhancement: EnhancementTypes,
+    pub seal: SealTypes,
+    pub bans: &'static [Ban],
+}
+
+impl Challenge {
+    /// The challenge called `s`, by name or key, matched as
+    /// [`crate::names::ItemName::from_name`] matches.
+    pub fn from_name(s: &str) -> Option<&'static Challenge> {
+        CHALLENGES.iter().find(|c| names::names_match(c.name, s) || c.id == s)
+    }
+}
+
+const BASE: Challenge = Challenge {
+    name: "",
+    id: "",
+    jokers: &[],
+    consumables: &[],
+    vouchers: &[],
+    modifiers: Modifiers::NONE,
+    removed_ranks: &[],
+    enhancement: EnhancementTypes::None,
+    seal: SealTypes::None,
+    bans: &[],
+};
+
+const fn joker(joker: JokerTypes) -> StartingJoker {
+    StartingJoker { joker, eternal: false }
+}
+
+const fn eternal(joker: JokerTypes) -> StartingJoker {
+    StartingJoker { joker, eternal: true }
+}
+
+const fn tarot(tarot: Tarots) -> Ban {
+    Ban::Consumable(Consumable::Tarot(tarot))
+}
+
+const fn spectral(spectral: Spectral) -> Ban {
+    Ban::Consumable(Consumable::Spectral(spectral))
+}
+
+/// Every challenge, in the game's order.
+pub const CHALLENGES: [Challenge; 20] = [
+    Challenge {
+        name: "The Omelette",
+        id: "c_omelette_1",
+        jokers: &[joker(JokerTypes::Egg); 5],
+        bans: &[
+            Ban::Voucher(Vouchers::SeedMoney),
+            Ban::Voucher(Vouchers::MoneyTree),
+            Ban::Joker(JokerTypes::ToTheMoon),
+            Ban::Joker(JokerTypes::Rocket),
+            Ban::Joker(JokerTypes::GoldenJoker),
+            Ban::Joker(JokerTypes::Satellite),
+        ],
+        ..BASE
+    },
+    Challenge {
+        name: "The City",
+        id: "c_city_1",
+        jokers: &[eternal(JokerTypes::RideTheBus), eternal(JokerTypes::Shortcut)],
+        ..BASE
+    },
+    Challenge {
+        name: "Rich get Richer",
+        id: "c_rich_1",
+        vouchers: &[Vouchers::SeedMoney, Vouchers::MoneyTree],
+        modifiers: Modifiers { dollars: Some(100), ..Modifiers::NONE },
+        ..BASE
+    },
+    Challenge {
+        name: "On a Knife's Edge",
+        id: "c_knife_1",
+        jokers: &[eternal(JokerTypes::CeremonialDagger)],
+        ..BASE
+    },
+    Challenge { name: "X-ray Vision", id: "c_xray_1", ..BASE },
+    Challenge {
+        name: "Mad World",
+        id: "c_mad_world_1",
+        jokers: &[joker(JokerTypes::Pareidolia), joker(JokerTypes::BusinessCard)],
+        removed_ranks: &[CardTypes::Jack, CardTypes::Queen, CardTypes::King, CardTypes::Ace],
+        ..BASE
+    },
+    Challenge {
+        name: "Luxury Tax",
+        id: "c_luxury_1",
+        modifiers: Modifiers { hand_size: Some(10), ..Modifiers::NONE },
+        ..BASE
+    },
+    Challenge {
+        name: "Non-Perishable",
+        id: "c_non_perishable_1",
+        bans: &[
+            Ban::Joker(JokerTypes::GrosMichel),
+            Ban::Joker(JokerTypes::IceCream),
+            Ban::Joker(JokerTypes::Cavendish),
+            Ban::Joker(JokerTypes::Luchador),
+            Ban::Joker(JokerTypes::TurtleBean),
+            Ban::Joker(JokerTypes::DietCola),
+            Ban::Joker(JokerTypes::Popcorn),
+            Ban::Joker(JokerTypes::Ramen),
+            Ban::Joker(JokerTypes::Seltzer),
+            Ban::Joker(JokerTypes::MrBones),
+            Ban::Joker(JokerTypes::InvisibleJoker),
+            Ban::Boss(Bosses::VerdantLeaf),
+        ],
+        ..BASE
+    },
+    Challenge {
+        name: "Medusa",
+        id: "c_medusa_1",
+        jokers: &[eternal(JokerTypes::MarbleJoker)],
+        ..BASE
+    },
+    Challenge { name: "Double or Nothing", id: "c_double_nothing_1", seal: SealTypes::Red, ..BASE },
+    Challenge { name: "Typecast", id: "c_typecast_1", ..BASE },
+    Challenge {
+        name: "Inflation",
+        id: "c_inflation_1",
+        bans: &[Ban::Voucher(Vouchers::ClearanceSale), Ban::Voucher(Vouchers::Liquidation)],
+        ..BASE
+    },
+    Challenge {
+        name: "Bram Poker",
+        id: "c_bram_poker_1",
+        jokers: &[eternal(JokerTypes::Vampire)],
+        consumables: &[Consumable::Tarot(Tarots::TheEmpress), Consumable::Tarot(Tarots::TheEmperor)],
+        vouchers: &[Vouchers::MagicTrick, Vouchers::Illusion],
+        ..BASE
+    },
+    Challenge {
+        name: "Fragile",
+        id: "c_fragile_1",
+        jokers: &[eternal(JokerTypes::OopsAllSixes), eternal(JokerTypes::OopsAllSixes)],
+        enhancement: EnhancementTypes::Glass,
+        bans: &[
+            tarot(Tarots::TheMagician),
+            tarot(Tarots::TheEmpress),
+            tarot(Tarots::TheHierophant),
+            tarot(Tarots::TheChariot),
+            tarot(Tarots::TheDevil),
+            tarot(Tarots::TheTower),
+            tarot(Tarots::TheLovers),
+            spectral(Spectral::Incantation),
+            spectral(Spectral::Grim),
+            spectral(Spectral::Familiar),
+            Ban::Pack(Pack::Standard),
+            Ban::Joker(JokerTypes::MarbleJoker),
+            Ban::Joker(JokerTypes::Vampire),
+            Ban::Joker(JokerTypes::MidasMask),
+            Ban::Joker(JokerTypes::Certificate),
+            Ban::Voucher(Vouchers::MagicTrick),
+            Ban::Voucher(Vouchers::Illusion),
+            Ban::Tag(Tags::StandardTag),
+        ],
+        ..BASE
+    },
+    Challenge {
+        name: "Monolith",
+        id: "c_monolith_1",
+        jokers: &[eternal(JokerTypes::Obelisk), eternal(JokerTypes::MarbleJoker)],
+        ..BASE
+    },
+    Challenge {
+        name: "Blast Off",
+        id: "c_blast_off_1",
+        jokers: &[eternal(JokerTypes::Constellation), eternal(JokerTypes::Rocket)],
+        vouchers: &[Vouchers::PlanetMerchant, Vouchers::PlanetTycoon],
+        modifiers: Modifiers { hands: Some(2), discards: Some(2), joker_slots: Some(4), ..Modifiers::NONE },
+        bans: &[Ban::Voucher(Vouchers::Grabber), Ban::Voucher(Vouchers::NachoTong), Ban::Joker(JokerTypes::Burglar)],
+        ..BASE
+    },
+    Challenge {
+        name: "Five-Card Draw",
+        id: "c_five_card_1",
+        jokers: &[joker(JokerTypes::CardSharp), joker(JokerTypes::Joker)],
+        modifiers: Modifiers { discards: Some(6), hand_size: Some(5), joker_slots: Some(7), ..Modifiers::NONE },
+        ..BASE
+    },
+    Challenge {
+        name: "Golden Needle",
+        id: "c_golden_needle_1",
+        jokers: &[joker(JokerTypes::CreditCard)],
+        modifiers: Modifiers { dollars: Some(10), hands: Some(1), discards: Some(6), ..Modifiers::NONE },
+        ..BASE
+    },
+    Challenge {
+        name: "Cruelty",
+        id: "c_cruelty_1",
+        modifiers: Modifiers { joker_slots: Some(3), ..Modifiers::NONE },
+        ..BASE
+    },
+    Challenge {
+        name: "Jokerless",
+        id: "c_jokerless_1",
+        modifiers: Modifiers { joker_slots: Some(0), ..Modifiers::NONE },
+        bans: &[
+            tarot(Tarots::Judgement),
+            spectral(Spectral::Wraith),
+            spectral(Spectral::TheSoul),
+            Ban::Voucher(Vouchers::Antimatter),
+            Ban::Pack(Pack::Buffoon),
+            Ban::Tag(Tags::RareTag),
+            Ban::Tag(Tags::UncommonTag),
+            Ban::Tag(Tags::HolographicTag),
+            Ban::Tag(Tags::PolychromeTag),
+            Ban::Tag(Tags::NegativeTag),
+            Ban::Tag(Tags::FoilTag),
+            Ban::Tag(Tags::BuffoonTag),
+            Ban::Tag(Tags::TopUpTag),
+            Ban::Boss(Bosses::AmberAcorn),
+            Ban::Boss(Bosses::CrimsonHeart),
+            Ban::Boss(Bosses::VerdantLeaf),
+        ],
+        ..BASE
+    },
+];
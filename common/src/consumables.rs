@@ -0,0 +1,399 @@
+//! Using Tarot, Planet and Spectral cards on a [`RunState`]. Each effect draws
+//! from the same streams as the game's `use_consumeable`, in the same order, so
+//! a route that uses consumables leaves the streams where the game would.
+//!
+//! `selected` holds the `sort_id`s of the highlighted cards in hand. The used
+//! card must already be out of `run.consumables`, as the game removes it from
+//! its slot before applying it. A card the game would not let you use returns
+//! a [`UseError`] without drawing anything.
+
+use crate::cards;
+use crate::items::{
+    Card, CardSuits, CardTypes, Consumable, Editions, EnhancementTypes, Joker, Planets, RandomSource, SealTypes,
+    Spectral, Tarots,
+};
+use crate::odds;
+use crate::pools;
+use crate::random::Random;
+use crate::run::RunState;
+use alloc::vec::Vec;
+use core::fmt;
+use strum::IntoEnumIterator;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UseError {
+    /// The card needs between `min` and `max` highlighted cards.
+    Selection { min: usize, max: usize },
+    /// A selected `sort_id` is not in hand.
+    NotInHand(usize),
+    /// Not enough cards held in hand.
+    HandTooSmall,
+    /// No free slot for what the card creates.
+    NoRoom,
+    /// No joker the card can apply to.
+    NoEligibleJoker,
+    /// The Fool with nothing to copy.
+    NothingToCopy,
+}
+
+impl fmt::Display for UseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UseError::Selection { min, max } if min == max => write!(f, "select exactly {min} cards"),
+            UseError::Selection { min, max } => write!(f, "select {min} to {max} cards"),
+            UseError::NotInHand(id) => write!(f, "card {id} is not in hand"),
+            UseError::HandTooSmall => write!(f, "not enough cards in hand"),
+            UseError::NoRoom => write!(f, "no free slot"),
+            UseError::NoEligibleJoker => write!(f, "no eligible joker"),
+            UseError::NothingToCopy => write!(f, "no Tarot or Planet to copy"),
+        }
+    }
+}
+
+// Suits and ranks as the Spectral effects list them for `pseudorandom_element`.
+const SIGIL_SUITS: [CardSuits; 4] = [CardSuits::Spades, CardSuits::Hearts, CardSuits::Diamonds, CardSuits::Clubs];
+const OUIJA_RANKS: [CardTypes; 13] = [
+    CardTypes::Two,
+    CardTypes::Three,
+    CardTypes::Four,
+    CardTypes::Five,
+    CardTypes::Six,
+    CardTypes::Seven,
+    CardTypes::Eight,
+    CardTypes::Nine,
+    CardTypes::Ten,
+    CardTypes::Jack,
+    CardTypes::Queen,
+    CardTypes::King,
+    CardTypes::Ace,
+];
+
+fn check_selection(run: &RunState, selected: &[usize], min: usize, max: usize) -> Result<(), UseError> {
+    if selected.len() < min || selected.len() > max {
+        return Err(UseError::Selection { min, max });
+    }
+    match selected.iter().find(|id| !run.hand.contains(id)) {
+        Some(&id) => Err(UseError::NotInHand(id)),
+        None => Ok(()),
+    }
+}
+
+// Cards in hand in `sort_id` order, the order `pseudorandom_element` and
+// `pseudoshuffle` put them in.
+fn hand_by_sort_id(run: &RunState) -> Vec<usize> {
+    let mut hand = run.hand.clone();
+    hand.sort_unstable();
+    hand
+}
+
+// Indices into `run.jokers` of the jokers without an edition.
+fn editionless_jokers(run: &RunState) -> Vec<usize> {
+    (0..run.jokers.len()).filter(|&i| run.jokers[i].edition == Editions::None).collect()
+}
+
+fn for_selected(run: &mut RunState, selected: &[usize], mut f: impl FnMut(&mut Card)) {
+    for &id in selected {
+        if let Some(card) = run.card_mut(id) {
+            f(card);
+        }
+    }
+}
+
+// Destroy every joker but `keep` and the Eternal ones.
+fn destroy_other_jokers(run: &mut RunState, keep: usize) {
+    let mut i = 0;
+    run.jokers.retain(|j| {
+        let kept = i == keep || j.stickers[0];
+        i += 1;
+        kept
+    });
+}
+
+fn rank_up(rank: CardTypes) -> CardTypes {
+    match rank {
+        CardTypes::Two => CardTypes::Three,
+        CardTypes::Three => CardTypes::Four,
+        CardTypes::Four => CardTypes::Five,
+        CardTypes::Five => CardTypes::Six,
+        CardTypes::Six => CardTypes::Seven,
+        CardTypes::Seven => CardTypes::Eight,
+        CardTypes::Eight => CardTypes::Nine,
+        CardTypes::Nine => CardTypes::Ten,
+        CardTypes::Ten => CardTypes::Jack,
+        CardTypes::Jack => CardTypes::Queen,
+        CardTypes::Queen => CardTypes::King,
+        CardTypes::King => CardTypes::Ace,
+        // No game card has rank One.
+        CardTypes::Ace | CardTypes::One => CardTypes::Two,
+    }
+}
+
+/// Create up to two consumables from `draw` into free slots, as The Emperor
+/// and The High Priestess do.
+fn fill_slots(run: &mut RunState, mut draw: impl FnMut(&mut RunState) -> Consumable) {
+    for _ in 0..run.free_consumable_slots().min(2) {
+        let card = draw(run);
+        run.consumables.push(card);
+    }
+}
+
+/// Apply `card` in `ante` to `run`.
+pub fn use_consumable(
+    random: &mut Random,
+    run: &mut RunState,
+    card: Consumable,
+    ante: i32,
+    selected: &[usize],
+) -> Result<(), UseError> {
+    // The card keeps its key out of the pools until it dissolves.
+    run.set_using(Some(card));
+    let used = match card {
+        Consumable::Tarot(tarot) => use_tarot(random, run, tarot, ante, selected),
+        Consumable::Planet(planet) => {
+            run.hands.use_planet(planet);
+            Ok(())
+        }
+        Consumable::Spectral(spectral) => use_spectral(random, run, spectral, ante, selected),
+    };
+    run.set_using(None);
+    used?;
+    if !matches!(card, Consumable::Spectral(_) | Consumable::Tarot(Tarots::TheFool)) {
+        run.last_tarot_planet = Some(card);
+    }
+    Ok(())
+}
+
+fn use_tarot(random: &mut Random, run: &mut RunState, tarot: Tarots, ante: i32, selected: &[usize]) -> Result<(), UseError> {
+    let enhance = match tarot {
+        Tarots::TheMagician => Some((2, EnhancementTypes::Lucky)),
+        Tarots::TheEmpress => Some((2, EnhancementTypes::Mult)),
+        Tarots::TheHierophant => Some((2, EnhancementTypes::Bonus)),
+        Tarots::TheLovers => Some((1, EnhancementTypes::Wild)),
+        Tarots::TheChariot => Some((1, EnhancementTypes::Steel)),
+        Tarots::Justice => Some((1, EnhancementTypes::Glass)),
+        Tarots::TheDevil => Some((1, EnhancementTypes::Gold)),
+        Tarots::TheTower => Some((1, EnhancementTypes::Stone)),
+        _ => None,
+    };
+    if let Some((max, enhancement)) = enhance {
+        check_selection(run, selected, 1, max)?;
+        for_selected(run, selected, |c| c.enhancement = enhancement);
+        return Ok(());
+    }
+    let suit = match tarot {
+        Tarots::TheStar => Some(CardSuits::Diamonds),
+        Tarots::TheMoon => Some(CardSuits::Clubs),
+        Tarots::TheSun => Some(CardSuits::Hearts),
+        Tarots::TheWorld => Some(CardSuits::Spades),
+        _ => None,
+    };
+    if let Some(suit) = suit {
+        check_selection(run, selected, 1, 3)?;
+        for_selected(run, selected, |c| c.suit = suit);
+        return Ok(());
+    }
+
+    match tarot {
+        Tarots::TheFool => {
+            let last = run.last_tarot_planet.ok_or(UseError::NothingToCopy)?;
+            if run.free_consumable_slots() == 0 {
+                return Err(UseError::NoRoom);
+            }
+            run.consumables.push(last);
+        }
+        Tarots::TheHighPriestess => fill_slots(run, |run| {
+            let all: Vec<Planets> = Planets::iter().collect();
+            let id = alloc::format!("Planet{}{ante}", RandomSource::HighPriestess.code());
+            Consumable::Planet(*random.rand_choice_str_in(&id, &all, run, &Planets::Pluto))
+        }),
+        Tarots::TheEmperor => fill_slots(run, |run| {
+            let all: Vec<Tarots> = Tarots::iter().collect();
+            let id = alloc::format!("Tarot{}{ante}", RandomSource::Emperor.code());
+            Consumable::Tarot(*random.rand_choice_str_in(&id, &all, run, &Tarots::Strength))
+        }),
+        Tarots::TheHermit => run.dollars += run.dollars.clamp(0, 20),
+        Tarots::TheWheelOfFortune => {
+            let eligible = editionless_jokers(run);
+            if eligible.is_empty() {
+                return Err(UseError::NoEligibleJoker);
+            }
+            if let Some(hit) = odds::wheel_of_fortune(random, eligible.len(), run.probabilities) {
+                run.jokers[eligible[hit.joker]].edition = hit.edition;
+            }
+        }
+        Tarots::Strength => {
+            check_selection(run, selected, 1, 2)?;
+            for_selected(run, selected, |c| c.rank = rank_up(c.rank));
+        }
+        Tarots::TheHangedMan => {
+            check_selection(run, selected, 1, 2)?;
+            for &id in selected {
+                run.destroy_card(id);
+            }
+        }
+        Tarots::Death => {
+            check_selection(run, selected, 2, 2)?;
+            // The rightmost card in hand is copied onto the other.
+            let position = |id: &usize| run.hand.iter().position(|h| h == id);
+            let (left, right) = if position(&selected[0]) < position(&selected[1]) {
+                (selected[0], selected[1])
+            } else {
+                (selected[1], selected[0])
+            };
+            if let Some(&source) = run.card(right) {
+                for_selected(run, &[left], |c| *c = Card { sort_id: c.sort_id, ..source });
+            }
+        }
+        Tarots::Judgement => {
+            if run.free_joker_slots() == 0 {
+                return Err(UseError::NoRoom);
+            }
+            let joker = pools::next_joker(random, run, RandomSource::Judgement, ante, false);
+            run.jokers.push(joker);
+        }
+        // Temperance pays the jokers' sell value, which is not tracked.
+        Tarots::Temperance => {}
+        _ => unreachable!("handled above"),
+    }
+    Ok(())
+}
+
+fn use_spectral(
+    random: &mut Random,
+    run: &mut RunState,
+    spectral: Spectral,
+    ante: i32,
+    selected: &[usize],
+) -> Result<(), UseError> {
+    let seal = match spectral {
+        Spectral::Talisman => Some(SealTypes::Gold),
+        Spectral::DejaVu => Some(SealTypes::Red),
+        Spectral::Trance => Some(SealTypes::Blue),
+        Spectral::Medium => Some(SealTypes::Purple),
+        _ => None,
+    };
+    if let Some(seal) = seal {
+        check_selection(run, selected, 1, 1)?;
+        for_selected(run, selected, |c| c.seal = seal);
+        return Ok(());
+    }
+
+    match spectral {
+        Spectral::Familiar | Spectral::Grim | Spectral::Incantation => {
+            if run.hand.len() < 2 {
+                return Err(UseError::HandTooSmall);
+            }
+            let hand = hand_by_sort_id(run);
+            let destroyed = hand[random.rand_int_str("random_destroy", 0, hand.len() as i32 - 1) as usize];
+            run.destroy_card(destroyed);
+            let created: Vec<Card> = match spectral {
+                Spectral::Familiar => cards::familiar_cards(random).to_vec(),
+                Spectral::Grim => cards::grim_cards(random).to_vec(),
+                _ => cards::incantation_cards(random).to_vec(),
+            };
+            for card in created {
+                run.add_to_hand(card);
+            }
+        }
+        Spectral::Aura => {
+            check_selection(run, selected, 1, 1)?;
+            if run.card(selected[0]).is_some_and(|c| c.edition != Editions::None) {
+                return Err(UseError::Selection { min: 1, max: 1 });
+            }
+            // poll_edition('aura', nil, true, true): guaranteed, no Negative. A
+            // guaranteed poll scales every threshold by 25 and ignores the edition rate.
+            let edition = cards::poll_edition(random, "aura", 25.0, 1.0, true);
+            for_selected(run, selected, |c| c.edition = edition);
+        }
+        Spectral::Wraith => {
+            if run.free_joker_slots() == 0 {
+                return Err(UseError::NoRoom);
+            }
+            let joker = pools::next_joker(random, run, RandomSource::Wraith, ante, false);
+            run.jokers.push(joker);
+            run.dollars = 0;
+        }
+        Spectral::Sigil => {
+            if run.hand.len() < 2 {
+                return Err(UseError::HandTooSmall);
+            }
+            let suit = SIGIL_SUITS[random.rand_int_str("sigil", 0, 3) as usize];
+            for id in run.hand.clone() {
+                if let Some(c) = run.card_mut(id) {
+                    c.suit = suit;
+                }
+            }
+        }
+        Spectral::Ouija => {
+            if run.hand.len() < 2 {
+                return Err(UseError::HandTooSmall);
+            }
+            let rank = OUIJA_RANKS[random.rand_int_str("ouija", 0, 12) as usize];
+            for id in run.hand.clone() {
+                if let Some(c) = run.card_mut(id) {
+                    c.rank = rank;
+                }
+            }
+            run.hand_size -= 1;
+        }
+        Spectral::Ectoplasm | Spectral::Hex => {
+            let eligible = editionless_jokers(run);
+            if eligible.is_empty() {
+                return Err(UseError::NoEligibleJoker);
+            }
+            let id = if spectral == Spectral::Hex { "hex" } else { "ectoplasm" };
+            let chosen = eligible[random.rand_int_str(id, 0, eligible.len() as i32 - 1) as usize];
+            if spectral == Spectral::Hex {
+                run.jokers[chosen].edition = Editions::Polychrome;
+                destroy_other_jokers(run, chosen);
+            } else {
+                run.jokers[chosen].edition = Editions::Negative;
+                run.hand_size -= run.ecto_minus;
+                run.ecto_minus += 1;
+            }
+        }
+        Spectral::Immolate => {
+            if run.hand.len() < 2 {
+                return Err(UseError::HandTooSmall);
+            }
+            let mut hand = run.hand.clone();
+            cards::pseudoshuffle(random, "immolate", &mut hand);
+            for &id in hand.iter().take(5) {
+                run.destroy_card(id);
+            }
+            run.dollars += 20;
+        }
+        Spectral::Ankh => {
+            if run.jokers.is_empty() {
+                return Err(UseError::NoEligibleJoker);
+            }
+            let chosen = random.rand_int_str("ankh_choice", 0, run.jokers.len() as i32 - 1) as usize;
+            let copy = Joker {
+                edition: match run.jokers[chosen].edition {
+                    Editions::Negative => Editions::None,
+                    edition => edition,
+                },
+                ..run.jokers[chosen]
+            };
+            destroy_other_jokers(run, chosen);
+            run.jokers.push(copy);
+        }
+        Spectral::Cryptid => {
+            check_selection(run, selected, 1, 1)?;
+            if let Some(&card) = run.card(selected[0]) {
+                run.add_to_hand(card);
+                run.add_to_hand(card);
+            }
+        }
+        Spectral::BlackHole => run.hands.use_black_hole(),
+        Spectral::TheSoul => {
+            if run.free_joker_slots() == 0 {
+                return Err(UseError::NoRoom);
+            }
+            let joker = pools::next_joker(random, run, RandomSource::Soul, ante, false);
+            run.jokers.push(joker);
+        }
+        _ => unreachable!("handled above"),
+    }
+    Ok(())
+}
@@ -0,0 +1,283 @@
+use crate::challenge::Challenge;
+use crate::game::{GameState, RunConfig};
+use crate::items::{Bosses, Editions, JokerTypes, RandomSource, Tags, Vouchers};
+use crate::names::{ItemName, ParseNameError};
+use crate::shop::ShopItem;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+
+/// Card slots of the first shop of each ante, each a joker, Tarot or Planet
+/// by the shop's card type roll.
+pub const SHOP_SLOTS: usize = 2;
+/// Skip tags offered per ante (small and big blind).
+pub const TAGS_PER_ANTE: usize = 2;
+
+/// Lazily generated view of one seed. Each kind of draw is made at most once
+/// per ante and cached, so several clauses can inspect the same seed cheaply.
+pub struct SeedView {
+    game: GameState,
+    bosses: Vec<Option<Bosses>>,
+    vouchers: Vec<Option<Vouchers>>,
+    tags: Vec<Option<[Tags; TAGS_PER_ANTE]>>,
+    shops: Vec<Option<[ShopItem; SHOP_SLOTS]>>,
+}
+
+#[inline(always)]
+fn slot<T>(cache: &mut Vec<Option<T>>, ante: i32) -> &mut Option<T> {
+    let i = (ante.max(1) - 1) as usize;
+    if cache.len() <= i {
+        cache.resize_with(i + 1, || None);
+    }
+    &mut cache[i]
+}
+
+impl SeedView {
+    pub fn new(seed: &str) -> Self {
+        Self::with_config(seed, RunConfig::default())
+    }
+
+    /// A view of `seed` in a run of `challenge`, whose bans and starting
+    /// vouchers keep items out of the draws.
+    pub fn with_challenge(seed: &str, challenge: &'static Challenge) -> Self {
+        Self::with_config(seed, RunConfig { challenge: Some(challenge) })
+    }
+
+    pub fn with_config(seed: &str, config: RunConfig) -> Self {
+        Self {
+            game: GameState::with_config(seed, 1, config),
+            bosses: Vec::new(),
+            vouchers: Vec::new(),
+            tags: Vec::new(),
+            shops: Vec::new(),
+        }
+    }
+
+    /// Boss blind of `ante`. Bosses unlock as antes progress, so earlier antes
+    /// are drawn first. `None` when a challenge bans every boss, so a search
+    /// never panics on a seed.
+    pub fn boss(&mut self, ante: i32) -> Option<Bosses> {
+        let ante = ante.max(1);
+        while (self.bosses.len() as i32) < ante {
+            self.game.ante = self.bosses.len() as i32 + 1;
+            let boss = self.game.try_next_boss().ok();
+            self.bosses.push(boss);
+        }
+        self.bosses[(ante - 1) as usize]
+    }
+
+    pub fn voucher(&mut self, ante: i32) -> Vouchers {
+        if let Some(v) = *slot(&mut self.vouchers, ante) {
+            return v;
+        }
+        // The voucher draw applies the ante's unlocks, which the bosses of
+        // earlier antes must not see.
+        if ante > 1 {
+            self.boss(ante - 1);
+        }
+        let v = self.game.next_voucher_from_at_ante(RandomSource::Shop, ante);
+        *slot(&mut self.vouchers, ante) = Some(v);
+        v
+    }
+
+    pub fn tags(&mut self, ante: i32) -> [Tags; TAGS_PER_ANTE] {
+        if let Some(t) = *slot(&mut self.tags, ante) {
+            return t;
+        }
+        let t = [self.game.next_tag_from_at_ante(ante), self.game.next_tag_from_at_ante(ante)];
+        *slot(&mut self.tags, ante) = Some(t);
+        t
+    }
+
+    /// The first shop's card slots in `ante`, filled in order so each slot's
+    /// pool leaves out the cards on show before it.
+    pub fn shop(&mut self, ante: i32) -> [ShopItem; SHOP_SLOTS] {
+        if let Some(s) = *slot(&mut self.shops, ante) {
+            return s;
+        }
+        self.game.run.shop.clear();
+        let s = core::array::from_fn(|_| self.game.next_shop_item_at_ante(ante, false));
+        self.game.run.shop.clear();
+        *slot(&mut self.shops, ante) = Some(s);
+        s
+    }
+
+    /// The jokers of [`SeedView::shop`], `None` for slots holding another card.
+    pub fn shop_jokers(&mut self, ante: i32) -> [Option<JokerTypes>; SHOP_SLOTS] {
+        self.shop(ante).map(|item| item.joker().map(|j| j.joker))
+    }
+
+    /// Editions of the jokers returned by [`SeedView::shop_jokers`], in the same order.
+    pub fn shop_editions(&mut self, ante: i32) -> [Option<Editions>; SHOP_SLOTS] {
+        self.shop(ante).map(|item| item.joker().map(|j| j.edition))
+    }
+}
+
+/// A single condition on what a seed generates. Written as `kind@ante=name`,
+/// e.g. `boss@1=The Wall` or `joker=Blueprint`; the ante defaults to 1.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Clause {
+    Boss { ante: i32, boss: Bosses },
+    Voucher { ante: i32, voucher: Vouchers },
+    /// Either skip tag of the ante.
+    Tag { ante: i32, tag: Tags },
+    /// A joker in one of the [`SHOP_SLOTS`] slots of the ante's first shop.
+    ShopJoker { ante: i32, joker: JokerTypes },
+    /// A first-shop joker of the ante with this edition.
+    ShopEdition { ante: i32, edition: Editions },
+}
+
+#[inline(always)]
+fn item_index<T: ItemName + PartialEq>(item: T) -> u32 {
+    T::iter().position(|x| x == item).unwrap_or(0) as u32
+}
+
+#[inline(always)]
+fn item_at<T: ItemName>(index: u32) -> Option<T> {
+    T::iter().nth(index as usize)
+}
+
+impl Clause {
+    pub fn ante(&self) -> i32 {
+        match *self {
+            Clause::Boss { ante, .. }
+            | Clause::Voucher { ante, .. }
+            | Clause::Tag { ante, .. }
+            | Clause::ShopJoker { ante, .. }
+            | Clause::ShopEdition { ante, .. } => ante,
+        }
+    }
+
+    #[inline]
+    pub fn matches(&self, view: &mut SeedView) -> bool {
+        self.count(view) > 0
+    }
+
+    /// How many times the clause holds: tags and shop slots can match more than once.
+    pub fn count(&self, view: &mut SeedView) -> u32 {
+        match *self {
+            Clause::Boss { ante, boss } => (view.boss(ante) == Some(boss)) as u32,
+            Clause::Voucher { ante, voucher } => (view.voucher(ante) == voucher) as u32,
+            Clause::Tag { ante, tag } => view.tags(ante).iter().filter(|&&t| t == tag).count() as u32,
+            Clause::ShopJoker { ante, joker } => view.shop_jokers(ante).iter().filter(|&&j| j == Some(joker)).count() as u32,
+            Clause::ShopEdition { ante, edition } => {
+                view.shop_editions(ante).iter().filter(|&&e| e == Some(edition)).count() as u32
+            }
+        }
+    }
+
+    /// Pack into one word for GPU kernels: kind in the top 4 bits, ante in the
+    /// next 12, and the item's position in enum order in the low 16.
+    pub fn to_word(&self) -> u32 {
+        let (kind, index) = match *self {
+            Clause::Boss { boss, .. } => (0, item_index(boss)),
+            Clause::Voucher { voucher, .. } => (1, item_index(voucher)),
+            Clause::Tag { tag, .. } => (2, item_index(tag)),
+            Clause::ShopJoker { joker, .. } => (3, item_index(joker)),
+            Clause::ShopEdition { edition, .. } => (4, item_index(edition)),
+        };
+        (kind << 28) | (((self.ante() as u32) & 0xFFF) << 16) | (index & 0xFFFF)
+    }
+
+    pub fn from_word(word: u32) -> Option<Self> {
+        let ante = ((word >> 16) & 0xFFF) as i32;
+        let index = word & 0xFFFF;
+        Some(match word >> 28 {
+            0 => Clause::Boss { ante, boss: item_at(index)? },
+            1 => Clause::Voucher { ante, voucher: item_at(index)? },
+            2 => Clause::Tag { ante, tag: item_at(index)? },
+            3 => Clause::ShopJoker { ante, joker: item_at(index)? },
+            4 => Clause::ShopEdition { ante, edition: item_at(index)? },
+            _ => return None,
+        })
+    }
+}
+
+impl fmt::Display for Clause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (kind, name) = match self {
+            Clause::Boss { boss, .. } => ("boss", boss.name()),
+            Clause::Voucher { voucher, .. } => ("voucher", voucher.name()),
+            Clause::Tag { tag, .. } => ("tag", tag.name()),
+            Clause::ShopJoker { joker, .. } => ("joker", joker.name()),
+            Clause::ShopEdition { edition, .. } => ("edition", edition.name()),
+        };
+        write!(f, "{kind}@{}={name}", self.ante())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseClauseError {
+    /// Not of the form `kind[@ante]=name`.
+    Syntax(String),
+    UnknownKind(String),
+    BadAnte(String),
+    Name(ParseNameError),
+}
+
+impl fmt::Display for ParseClauseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseClauseError::Syntax(s) => write!(f, "clause '{s}' is not of the form kind[@ante]=name"),
+            ParseClauseError::UnknownKind(s) => {
+                write!(f, "unknown clause kind '{s}' (expected boss, voucher, tag, joker or edition)")
+            }
+            ParseClauseError::BadAnte(s) => write!(f, "ante '{s}' must be a number of at least 1"),
+            ParseClauseError::Name(e) => e.fmt(f),
+        }
+    }
+}
+
+impl From<ParseNameError> for ParseClauseError {
+    fn from(e: ParseNameError) -> Self {
+        ParseClauseError::Name(e)
+    }
+}
+
+impl FromStr for Clause {
+    type Err = ParseClauseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (lhs, name) = s.split_once('=').ok_or_else(|| ParseClauseError::Syntax(String::from(s)))?;
+        let (kind, ante) = match lhs.split_once('@') {
+            Some((kind, ante)) => {
+                let ante = ante.trim();
+                match ante.parse::<i32>() {
+                    Ok(a) if a >= 1 => (kind, a),
+                    _ => return Err(ParseClauseError::BadAnte(String::from(ante))),
+                }
+            }
+            None => (lhs, 1),
+        };
+        let name = name.trim();
+        Ok(match kind.trim().to_ascii_lowercase().as_str() {
+            "boss" => Clause::Boss { ante, boss: name.parse()? },
+            "voucher" => Clause::Voucher { ante, voucher: name.parse()? },
+            "tag" => Clause::Tag { ante, tag: name.parse()? },
+            "joker" => Clause::ShopJoker { ante, joker: name.parse()? },
+            "edition" => Clause::ShopEdition { ante, edition: name.parse()? },
+            other => return Err(ParseClauseError::UnknownKind(String::from(other))),
+        })
+    }
+}
+
+/// Conjunction of clauses; a seed matches when every clause holds.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Filter {
+    pub clauses: Vec<Clause>,
+}
+
+impl Filter {
+    pub fn new(clauses: Vec<Clause>) -> Self {
+        Self { clauses }
+    }
+
+    pub fn matches(&self, view: &mut SeedView) -> bool {
+        self.clauses.iter().all(|c| c.matches(view))
+    }
+
+    pub fn matches_seed(&self, seed: &str) -> bool {
+        self.matches(&mut SeedView::new(seed))
+    }
+}
@@ -1,11 +1,20 @@
 use alloc::vec::Vec;
-use crate::items::{Bosses, JokerTypes, Pack, Planets, Tarots, Vouchers, RandomSource, Tags};
-use crate::random::Random;
-use crate::util::LuaRandom;
-use libm::floor;
+use crate::blind::{BlindRound, PlayError};
+use crate::cards;
+use crate::challenge::{Challenge, CHALLENGES};
+use crate::consumables::{self, UseError};
+use crate::hands;
+use crate::pools;
+use crate::run::RunState;
+use crate::shop::{self, ShopItem};
+use crate::odds::{self, Probabilities, Roll, WheelHit};
+use crate::round::{self, RoundTargets};
+use crate::items::{Bosses, Card, CardSuits, Consumable, HandTypes, Joker, JokerTypes, Pack, Planets, Spectral, Tarots, Vouchers, RandomSource, Tags};
+use crate::random::{ChoiceError, Random};
+use crate::trace::TraceEvent;
 use strum::IntoEnumIterator;
 use alloc::string::String;
-use crate::names::voucher_name;
+use crate::names::{boss_name, voucher_name};
 use crate::lock::Lock;
 
 // Streams used to derive deterministic RNG nodes for each generator.
@@ -38,44 +47,94 @@ pub struct GameState {
     rng: Random,
     pub ante: i32,
     pub seen: SeenLog,
+    /// The simulated run: deck, jokers, consumables and hand levels, which
+    /// decide what some pools offer.
+    pub run: RunState,
     lock: Lock,
+    challenge: Option<&'static Challenge>,
+    // Ancient Joker's suit from the last roll of the round targets
+    ancient_suit: Option<CardSuits>,
+}
+
+/// What a run follows besides its seed: for a challenge run, the challenge.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct RunConfig {
+    pub challenge: Option<&'static Challenge>,
+}
+
+impl RunConfig {
+    /// One word for GPU kernels: the challenge's position in
+    /// [`crate::challenge::CHALLENGES`], or `u32::MAX` for none.
+    pub fn to_word(&self) -> u32 {
+        let all: &'static [Challenge] = &CHALLENGES;
+        self.challenge.and_then(|c| all.iter().position(|x| x.id == c.id)).map_or(u32::MAX, |i| i as u32)
+    }
+
+    pub fn from_word(word: u32) -> Option<Self> {
+        let all: &'static [Challenge] = &CHALLENGES;
+        let challenge = match word {
+            u32::MAX => None,
+            i => Some(all.get(i as usize)?),
+        };
+        Some(RunConfig { challenge })
+    }
 }
 
 impl GameState {
     #[inline]
     pub fn new(seed: &str, ante: i32) -> Self {
+        Self::with_config(seed, ante, RunConfig::default())
+    }
+
+    /// [`GameState::new`] for a run of `challenge`: its starting state in
+    /// [`GameState::run`], its vouchers redeemed and its bans locked for good.
+    pub fn with_challenge(seed: &str, ante: i32, challenge: &'static Challenge) -> Self {
+        Self::with_config(seed, ante, RunConfig { challenge: Some(challenge) })
+    }
+
+    pub fn with_config(seed: &str, ante: i32, config: RunConfig) -> Self {
         let mut s = Self {
             rng: Random::new(seed.as_bytes()),
             ante,
             seen: SeenLog::default(),
+            run: config.challenge.map_or_else(RunState::new, RunState::for_challenge),
             lock: Lock::new(),
+            challenge: config.challenge,
+            ancient_suit: None,
         };
         // Initialize locks per Blueprint lifecycle
         // fresh_profile = true, fresh_run = false
         s.lock.init_locks(ante, true, false);
         // Also apply firstLock (level two vouchers and related items)
         s.lock.lock_level_two_vouchers();
+        if let Some(challenge) = config.challenge {
+            for ban in challenge.bans {
+                s.lock.ban(ban.name());
+            }
+            for &v in challenge.vouchers {
+                s.activate_voucher(v);
+                s.lock.ban(voucher_name(&v));
+            }
+        }
         s
     }
 
+    /// The challenge this run follows.
+    pub fn config(&self) -> RunConfig {
+        RunConfig { challenge: self.challenge }
+    }
+
     #[inline(always)]
     fn source_code(source: RandomSource) -> &'static str {
-        match source {
-            RandomSource::Shop => "sho",
-            RandomSource::BuffonPack => "buf",
-            RandomSource::Wraith => "wra",
-            RandomSource::RareTag => "rta",
-            RandomSource::UncommonTag => "uta",
-            RandomSource::Soul => "sou",
-            RandomSource::Arcana => "ar1",
-            RandomSource::Celestial => "pl1",
-        }
+        source.code()
     }
 
     #[inline]
     pub fn reset_seed(&mut self, seed: &str) {
         self.rng.reset_seed(seed.as_bytes());
         self.clear_seen();
+        self.run = self.challenge.map_or_else(RunState::new, RunState::for_challenge);
+        self.ancient_suit = None;
     }
 
     #[inline]
@@ -83,6 +142,15 @@ impl GameState {
         self.seen = SeenLog::default();
     }
 
+    /// Record every draw from the game's named streams, see [`Random::take_trace`].
+    pub fn enable_trace(&mut self) {
+        self.rng.enable_trace();
+    }
+
+    pub fn take_trace(&mut self) -> Vec<TraceEvent> {
+        self.rng.take_trace()
+    }
+
     #[inline]
     pub fn lock_level_two_vouchers(&mut self) {
         self.lock.lock_level_two_vouchers();
@@ -132,6 +200,12 @@ impl GameState {
                 RandomSource::Soul => 7 * GROUP,
                 RandomSource::Arcana => 8 * GROUP,
                 RandomSource::Celestial => 9 * GROUP,
+                // Groups of their own past 14, which the other streams share.
+                RandomSource::Judgement => 15 * GROUP,
+                RandomSource::Emperor => 16 * GROUP,
+                RandomSource::HighPriestess => 17 * GROUP,
+                RandomSource::SpectralPack => 18 * GROUP,
+                RandomSource::OmenGlobe => 19 * GROUP,
             },
             RngStream::Tarot => match source {
                 RandomSource::Shop => 8 * GROUP,
@@ -155,7 +229,7 @@ impl GameState {
     pub fn next_joker(&mut self) -> JokerTypes {
         // Deterministic choice with retry/lock semantics handled by Random
         let all: Vec<JokerTypes> = JokerTypes::iter().collect();
-        let choice = *self.rng.rand_choice(Self::id_base_for(RngStream::Joker, RandomSource::Shop), &all);
+        let choice = *self.rng.rand_choice_or(Self::id_base_for(RngStream::Joker, RandomSource::Shop), &all, &JokerTypes::Joker);
         self.seen.jokers.push(choice);
         choice
     }
@@ -163,7 +237,7 @@ impl GameState {
     #[inline]
     pub fn next_joker_from(&mut self, source: RandomSource) -> JokerTypes {
         let all: Vec<JokerTypes> = JokerTypes::iter().collect();
-        let choice = *self.rng.rand_choice(Self::id_base_for(RngStream::Joker, source), &all);
+        let choice = *self.rng.rand_choice_or(Self::id_base_for(RngStream::Joker, source), &all, &JokerTypes::Joker);
         self.seen.jokers.push(choice);
         choice
     }
@@ -171,7 +245,7 @@ impl GameState {
     #[inline]
     pub fn next_tarot(&mut self) -> Tarots {
         let all: Vec<Tarots> = Tarots::iter().collect();
-        let choice = *self.rng.rand_choice(Self::id_base_for(RngStream::Tarot, RandomSource::Shop), &all);
+        let choice = *self.rng.rand_choice_or(Self::id_base_for(RngStream::Tarot, RandomSource::Shop), &all, &Tarots::Strength);
         self.seen.tarots.push(choice);
         choice
     }
@@ -179,7 +253,7 @@ impl GameState {
     #[inline]
     pub fn next_tarot_from(&mut self, source: RandomSource) -> Tarots {
         let all: Vec<Tarots> = Tarots::iter().collect();
-        let choice = *self.rng.rand_choice(Self::id_base_for(RngStream::Tarot, source), &all);
+        let choice = *self.rng.rand_choice_or(Self::id_base_for(RngStream::Tarot, source), &all, &Tarots::Strength);
         self.seen.tarots.push(choice);
         choice
     }
@@ -188,7 +262,7 @@ impl GameState {
     pub fn next_tarot_from_at_ante(&mut self, source: RandomSource, ante: i32) -> Tarots {
         let all: Vec<Tarots> = Tarots::iter().collect();
         let id = alloc::format!("Tarot{}{}", Self::source_code(source), ante.max(1));
-        let choice = *self.rng.rand_choice_str(&id, &all);
+        let choice = *self.rng.rand_choice_str_in(&id, &all, &self.run, &Tarots::Strength);
         self.seen.tarots.push(choice);
         choice
     }
@@ -196,7 +270,7 @@ impl GameState {
     #[inline]
     pub fn next_planet(&mut self) -> Planets {
         let all: Vec<Planets> = Planets::iter().collect();
-        let choice = *self.rng.rand_choice(Self::id_base_for(RngStream::Planet, RandomSource::Shop), &all);
+        let choice = *self.rng.rand_choice_or(Self::id_base_for(RngStream::Planet, RandomSource::Shop), &all, &Planets::Pluto);
         self.seen.planets.push(choice);
         choice
     }
@@ -204,7 +278,7 @@ impl GameState {
     #[inline]
     pub fn next_planet_from(&mut self, source: RandomSource) -> Planets {
         let all: Vec<Planets> = Planets::iter().collect();
-        let choice = *self.rng.rand_choice(Self::id_base_for(RngStream::Planet, source), &all);
+        let choice = *self.rng.rand_choice_or(Self::id_base_for(RngStream::Planet, source), &all, &Planets::Pluto);
         self.seen.planets.push(choice);
         choice
     }
@@ -213,7 +287,7 @@ impl GameState {
     pub fn next_planet_from_at_ante(&mut self, source: RandomSource, ante: i32) -> Planets {
         let all: Vec<Planets> = Planets::iter().collect();
         let id = alloc::format!("Planet{}{}", Self::source_code(source), ante.max(1));
-        let choice = *self.rng.rand_choice_str(&id, &all);
+        let choice = *self.rng.rand_choice_str_in(&id, &all, &self.run, &Planets::Pluto);
         self.seen.planets.push(choice);
         choice
     }
@@ -244,26 +318,28 @@ impl GameState {
         choice
     }
 
+    /// The next boss, panicking only when a challenge bans every boss.
     #[inline]
     pub fn next_boss(&mut self) -> Bosses {
+        self.try_next_boss().unwrap_or_else(|e| panic!("no boss to draw: {e}"))
+    }
+
+    /// The next boss. Once the locks rule out every boss, they are all made
+    /// eligible again before drawing, as the game does when each boss has
+    /// been used; a challenge's bans still hold, and if they cover every boss
+    /// this fails with [`ChoiceError::NoneUsable`].
+    pub fn try_next_boss(&mut self) -> Result<Bosses, ChoiceError> {
         // Apply per-ante unlocks as Blueprint does at start of generateAnte
         self.lock.init_unlocks(self.ante, false);
         let all: Vec<Bosses> = Bosses::iter().collect();
-        let base_id = "boss";
-        // resample on locked boss names
-        let mut id = String::from(base_id);
-        let mut resample = 2usize;
-        loop {
-            let mixed = self.rng.get_node_str(&id);
-            self.rng.lua_random = LuaRandom::new(mixed);
-            let idx = floor(self.rng.lua_random.random() * (all.len() as f64)) as usize;
-            let choice = all[idx];
-            if !self.lock.is_locked(crate::names::boss_name(&choice)) {
-                self.seen.bosses.push(choice);
-                return choice;
-            }
-            id.clear(); id.push_str(base_id); id.push_str("_resample"); id.push_str(&alloc::format!("{}", resample)); resample+=1;
+        if all.iter().all(|b| self.lock.is_locked(boss_name(b))) {
+            self.lock.unlock_many(all.iter().map(boss_name));
         }
+        // resample on locked boss names
+        let lock = &self.lock;
+        let choice = all[self.rng.try_rand_index_str("boss", all.len(), |i| !lock.is_locked(boss_name(&all[i])))?];
+        self.seen.bosses.push(choice);
+        Ok(choice)
     }
 
     #[inline]
@@ -282,23 +358,31 @@ impl GameState {
     #[inline]
     pub fn next_voucher(&mut self) -> Vouchers {
         self.lock.init_unlocks(self.ante, false);
+        self.draw_voucher("Voucher1")
+    }
+
+    /// [`GameState::next_voucher`], failing instead of giving Blank when
+    /// every voucher is locked.
+    pub fn try_next_voucher(&mut self) -> Result<Vouchers, ChoiceError> {
+        self.lock.init_unlocks(self.ante, false);
+        self.try_draw_voucher("Voucher1")
+    }
+
+    // The shop voucher for `id`, resampling locked ones. Like the game, falls
+    // back to Blank when every voucher is locked.
+    fn draw_voucher(&mut self, id: &str) -> Vouchers {
+        self.try_draw_voucher(id).unwrap_or_else(|_| {
+            self.seen.vouchers.push(Vouchers::Blank);
+            Vouchers::Blank
+        })
+    }
+
+    fn try_draw_voucher(&mut self, id: &str) -> Result<Vouchers, ChoiceError> {
         let all: Vec<Vouchers> = Vouchers::iter().collect();
-        let base_id = "Voucher1";
-        let mut id = String::from(base_id);
-        let mut resample = 2usize;
-        loop {
-            let mixed = self.rng.get_node_str(&id);
-            self.rng.lua_random = LuaRandom::new(mixed);
-            let idx = floor(self.rng.lua_random.random() * (all.len() as f64)) as usize;
-            let choice = all[idx];
-            let name = voucher_name(&choice);
-            let locked = self.lock.is_locked(name);
-            if !locked {
-                self.seen.vouchers.push(choice);
-                return choice;
-            }
-            id.clear(); id.push_str(base_id); id.push_str("_resample"); id.push_str(&alloc::format!("{}", resample)); resample += 1;
-        }
+        let lock = &self.lock;
+        let choice = all[self.rng.try_rand_index_str(id, all.len(), |i| !lock.is_locked(voucher_name(&all[i])))?];
+        self.seen.vouchers.push(choice);
+        Ok(choice)
     }
 
     #[inline]
@@ -312,26 +396,14 @@ impl GameState {
         let _ = source;
         // Use lock-aware version and id Voucher{ante}
         self.lock.init_unlocks(ante, false);
-        let all: Vec<Vouchers> = Vouchers::iter().collect();
-        let base_id = alloc::format!("Voucher{}", ante.max(1));
-        let mut id = base_id.clone();
-        let mut resample = 2usize;
-        loop {
-            let mixed = self.rng.get_node_str(&id);
-            self.rng.lua_random = LuaRandom::new(mixed);
-            let idx = floor(self.rng.lua_random.random() * (all.len() as f64)) as usize;
-            let choice = all[idx];
-            let name = voucher_name(&choice);
-            if !self.lock.is_locked(name) { self.seen.vouchers.push(choice); return choice; }
-            id.clear(); id.push_str(&base_id); id.push_str("_resample"); id.push_str(&alloc::format!("{}", resample)); resample += 1;
-        }
+        self.draw_voucher(&alloc::format!("Voucher{}", ante.max(1)))
     }
 
     #[inline]
     pub fn next_tag_from_at_ante(&mut self, ante: i32) -> Tags {
         let all: Vec<Tags> = Tags::iter().collect();
         let id = alloc::format!("Tag{}", ante.max(1));
-        *self.rng.rand_choice_str(&id, &all)
+        *self.rng.rand_choice_str_in(&id, &all, &self.run, &Tags::HandyTag)
     }
 
     #[inline]
@@ -343,25 +415,139 @@ impl GameState {
         last
     }
 
+    /// The next joker from `source` in `ante`, through its rarity pool, see
+    /// [`pools::next_joker`]. Shop and Buffoon pack jokers poll for stickers.
     #[inline]
-    pub fn debug_tag_once(&mut self, ante: i32) -> (alloc::string::String, f64, f64, usize, &'static str) {
-        let all: Vec<Tags> = Tags::iter().collect();
-        let id = alloc::format!("Tag{}", ante.max(1));
-        let mixed = self.rng.get_node_str(&id);
-        self.rng.lua_random = LuaRandom::new(mixed);
-        let rand = self.rng.lua_random.random();
-        let idx = floor(rand * (all.len() as f64)) as usize;
-        let name = crate::names::tag_name(&all[idx]);
-        (id, mixed, rand, idx, name)
+    pub fn next_joker_from_at_ante(&mut self, source: RandomSource, ante: i32) -> Joker {
+        let stickers = matches!(source, RandomSource::Shop | RandomSource::BuffonPack);
+        let joker = pools::next_joker(&mut self.rng, &self.run, source, ante.max(1), stickers);
+        self.seen.jokers.push(joker.joker);
+        joker
     }
 
+    /// Fill the next shop slot of `ante` (see [`shop::next_shop_item`]) and
+    /// put it on show in `run.shop`, which keeps it out of the pools of the
+    /// slots after it. Clear `run.shop` when the shop rerolls or closes.
     #[inline]
-    pub fn next_joker_from_at_ante(&mut self, source: RandomSource, ante: i32) -> JokerTypes {
-        let all: Vec<JokerTypes> = JokerTypes::iter().collect();
-        let id = alloc::format!("Joker{}{}", Self::source_code(source), ante.max(1));
-        let choice = *self.rng.rand_choice_str(&id, &all);
-        self.seen.jokers.push(choice);
-        choice
+    pub fn next_shop_item_at_ante(&mut self, ante: i32, illusion: bool) -> ShopItem {
+        let item = shop::next_shop_item(&mut self.rng, &self.run, ante.max(1), illusion);
+        if let ShopItem::Joker(joker) = item {
+            self.seen.jokers.push(joker.joker);
+        }
+        self.run.shop.push(item);
+        item
+    }
+
+    /// The next Tarot card from `source` in `ante`, which packs can turn into
+    /// The Soul, see [`pools::next_tarot`].
+    #[inline]
+    pub fn next_tarot_card_at_ante(&mut self, source: RandomSource, ante: i32) -> Consumable {
+        let card = pools::next_tarot(&mut self.rng, &self.run, source, ante.max(1), source.soulable());
+        if let Consumable::Tarot(tarot) = card {
+            self.seen.tarots.push(tarot);
+        }
+        card
+    }
+
+    /// The next Planet card from `source` in `ante`, which packs can turn into
+    /// Black Hole, see [`pools::next_planet`].
+    #[inline]
+    pub fn next_planet_card_at_ante(&mut self, source: RandomSource, ante: i32) -> Consumable {
+        let card = pools::next_planet(&mut self.rng, &self.run, source, ante.max(1), source.soulable());
+        if let Consumable::Planet(planet) = card {
+            self.seen.planets.push(planet);
+        }
+        card
+    }
+
+    /// The next Spectral card from `source` in `ante`, see [`pools::next_spectral`].
+    #[inline]
+    pub fn next_spectral_card_at_ante(&mut self, source: RandomSource, ante: i32) -> Spectral {
+        pools::next_spectral(&mut self.rng, &self.run, source, ante.max(1), source.soulable())
     }
 
+    /// The legendary joker the next Soul makes in `ante`. Its pool draws from
+    /// `Joker4` for the whole run; only the edition depends on the ante.
+    #[inline]
+    pub fn next_legendary_joker(&mut self, ante: i32) -> Joker {
+        let joker = pools::next_joker(&mut self.rng, &self.run, RandomSource::Soul, ante.max(1), false);
+        self.seen.jokers.push(joker.joker);
+        joker
+    }
+
+    #[inline]
+    pub fn next_standard_card_at_ante(&mut self, ante: i32) -> Card {
+        cards::standard_pack_card(&mut self.rng, ante.max(1), 1.0)
+    }
+
+    #[inline]
+    pub fn next_shop_card_at_ante(&mut self, ante: i32, illusion: bool) -> Card {
+        cards::shop_card(&mut self.rng, ante.max(1), illusion)
+    }
+
+    /// The next roll of the per-round joker targets in `ante`, given every
+    /// playing card the run owns ([`cards::starting_deck`] at first). Call it
+    /// once when the run starts and once after each round played.
+    #[inline]
+    pub fn next_round_targets(&mut self, ante: i32, deck: &[Card]) -> RoundTargets {
+        let targets = round::roll_round_targets(&mut self.rng, ante.max(1), deck, self.ancient_suit);
+        self.ancient_suit = Some(targets.ancient);
+        targets
+    }
+
+    /// To Do List's next hand, see [`round::roll_to_do_hand`].
+    #[inline]
+    pub fn next_to_do_hand(
+        &mut self,
+        current: Option<HandTypes>,
+        visible: impl Fn(HandTypes) -> bool,
+        order: &[HandTypes],
+    ) -> HandTypes {
+        round::roll_to_do_hand(&mut self.rng, current, visible, order)
+    }
+
+    /// The hand of the next Orbital Tag, given the visible hands in
+    /// [`GameState::run`]. See [`hands::orbital_hand`].
+    #[inline]
+    pub fn next_orbital_hand(&mut self, order: &[HandTypes]) -> HandTypes {
+        hands::orbital_hand(&mut self.rng, &self.run.hands, order)
+    }
+
+    /// Use `card` on [`GameState::run`] in the current ante, see
+    /// [`consumables::use_consumable`].
+    #[inline]
+    pub fn use_consumable(&mut self, card: Consumable, selected: &[usize]) -> Result<(), UseError> {
+        consumables::use_consumable(&mut self.rng, &mut self.run, card, self.ante.max(1), selected)
+    }
+
+    /// Start a round against `boss` (`None` for the Small and Big Blinds) in
+    /// the current ante, shuffling the deck of [`GameState::run`].
+    #[inline]
+    pub fn new_blind_round(&mut self, boss: Option<Bosses>) -> BlindRound {
+        BlindRound::new(&mut self.rng, &self.run, boss, self.ante.max(1))
+    }
+
+    /// Fill the hand of [`GameState::run`], see [`BlindRound::draw`].
+    #[inline]
+    pub fn draw_to_hand(&mut self, round: &mut BlindRound) -> Vec<usize> {
+        round.draw(&mut self.rng, &mut self.run)
+    }
+
+    /// Play `cards` from the hand of [`GameState::run`], returning what The
+    /// Hook discards, see [`BlindRound::play`].
+    #[inline]
+    pub fn play_hand(&mut self, round: &mut BlindRound, cards: &[usize]) -> Result<Vec<usize>, PlayError> {
+        round.play(&mut self.rng, &mut self.run, cards)
+    }
+
+    /// Whether the next chance roll of `roll` hits, see [`odds::roll`].
+    #[inline]
+    pub fn next_roll(&mut self, roll: Roll, ante: i32, probabilities: Probabilities) -> bool {
+        odds::roll(&mut self.rng, roll, ante.max(1), probabilities)
+    }
+
+    #[inline]
+    pub fn next_wheel_of_fortune(&mut self, eligible: usize, probabilities: Probabilities) -> Option<WheelHit> {
+        odds::wheel_of_fortune(&mut self.rng, eligible, probabilities)
+    }
 }
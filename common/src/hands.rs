@@ -0,0 +1,142 @@
+//! Poker hand levels as `G.GAME.hands` tracks them, and the planets used on
+//! them. Hand levels decide which planets the game offers: Planet X, Ceres and
+//! Eris stay out of the Planet pool until their secret hand has been played.
+
+use crate::items::{HandTypes, Planets};
+use crate::random::Random;
+use alloc::vec::Vec;
+use strum::IntoEnumIterator;
+
+// Base chips and mult, then chips and mult per level, from `init_game_object`,
+// in `HandTypes` order.
+const BASE: [(i32, i32, i32, i32); 12] = [
+    (160, 16, 50, 3),
+    (140, 14, 40, 4),
+    (120, 12, 35, 3),
+    (100, 8, 40, 4),
+    (60, 7, 30, 3),
+    (40, 4, 25, 2),
+    (35, 4, 15, 2),
+    (30, 4, 30, 3),
+    (30, 3, 20, 2),
+    (20, 2, 20, 1),
+    (10, 2, 15, 1),
+    (5, 1, 10, 1),
+];
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct HandLevel {
+    pub level: i32,
+    pub chips: i32,
+    pub mult: i32,
+    /// Times played this run.
+    pub played: u32,
+    /// Secret hands become visible once played.
+    pub visible: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HandLevels {
+    hands: [HandLevel; 12],
+    // Every planet used, in order; `G.GAME.consumeable_usage` for the Planet set.
+    planets_used: Vec<Planets>,
+}
+
+impl Default for HandLevels {
+    fn default() -> Self {
+        let mut hands = [HandLevel { level: 1, chips: 0, mult: 0, played: 0, visible: true }; 12];
+        for hand in HandTypes::iter() {
+            let (chips, mult, _, _) = BASE[hand as usize];
+            hands[hand as usize] = HandLevel { level: 1, chips, mult, played: 0, visible: !hand.is_secret() };
+        }
+        Self { hands, planets_used: Vec::new() }
+    }
+}
+
+impl HandLevels {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, hand: HandTypes) -> &HandLevel {
+        &self.hands[hand as usize]
+    }
+
+    /// The game's `level_up_hand`: levels never drop below 0, mult below 1 or
+    /// chips below 0.
+    pub fn level_up(&mut self, hand: HandTypes, amount: i32) {
+        let (chips, mult, l_chips, l_mult) = BASE[hand as usize];
+        let h = &mut self.hands[hand as usize];
+        h.level = (h.level + amount).max(0);
+        h.mult = (mult + l_mult * (h.level - 1)).max(1);
+        h.chips = (chips + l_chips * (h.level - 1)).max(0);
+    }
+
+    /// Record a hand being played.
+    pub fn play(&mut self, hand: HandTypes) {
+        let h = &mut self.hands[hand as usize];
+        h.played += 1;
+        h.visible = true;
+    }
+
+    /// Use a Planet card: one level for its hand.
+    pub fn use_planet(&mut self, planet: Planets) {
+        self.level_up(planet.hand(), 1);
+        self.planets_used.push(planet);
+    }
+
+    /// Use Black Hole: one level for every hand, secret or not. It is a
+    /// Spectral card, so Satellite and Constellation ignore it.
+    pub fn use_black_hole(&mut self) {
+        for hand in HandTypes::iter() {
+            self.level_up(hand, 1);
+        }
+    }
+
+    pub fn planets_used(&self) -> &[Planets] {
+        &self.planets_used
+    }
+
+    /// Satellite's end of round payout: $1 per distinct planet used this run.
+    pub fn satellite_dollars(&self) -> i32 {
+        let mut seen: Vec<Planets> = Vec::new();
+        for &p in &self.planets_used {
+            if !seen.contains(&p) {
+                seen.push(p);
+            }
+        }
+        seen.len() as i32
+    }
+
+    /// Constellation's XMult when bought after the first `owned_after`
+    /// planets were used. The game adds X0.1 per planet one at a time, so the
+    /// float sum is reproduced step by step.
+    pub fn constellation_xmult(&self, owned_after: usize) -> f64 {
+        let mut xmult = 1.0;
+        for _ in owned_after.min(self.planets_used.len())..self.planets_used.len() {
+            xmult += 0.1;
+        }
+        xmult
+    }
+
+    /// Whether `get_current_pool` keeps `planet` in the Planet pool: Planet X,
+    /// Ceres and Eris only once their hand has been played.
+    pub fn planet_available(&self, planet: Planets) -> bool {
+        match planet {
+            Planets::PlanetX | Planets::Ceres | Planets::Eris => self.get(planet.hand()).played > 0,
+            _ => true,
+        }
+    }
+}
+
+/// The hand an Orbital Tag levels up, three times. The game rolls it from
+/// `orbital` for every blind of an ante as the blind select screen first shows
+/// it, among the visible hands in `order`, the `pairs(G.GAME.hands)` order
+/// To Do List draws from too (see [`crate::round::to_do_order`]).
+pub fn orbital_hand(random: &mut Random, levels: &HandLevels, order: &[HandTypes]) -> HandTypes {
+    let hands: Vec<HandTypes> = order.iter().copied().filter(|&h| levels.get(h).visible).collect();
+    hands[random.rand_int_str("orbital", 0, hands.len() as i32 - 1) as usize]
+}
+
+/// Levels an Orbital Tag adds to its hand.
+pub const ORBITAL_LEVELS: i32 = 3;
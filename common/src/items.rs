@@ -1,8 +1,11 @@
+use crate::challenge::Ban;
 use crate::random::ItemChoice;
+use crate::run::RunState;
 use strum::EnumIter;
 #[cfg(feature = "std")]
 use core::fmt::{self, Display};
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum RandomSource {
     Shop,
     Soul,
@@ -12,9 +15,45 @@ pub enum RandomSource {
     UncommonTag,
     Arcana,
     Celestial,
+    Judgement,
+    Emperor,
+    HighPriestess,
+    SpectralPack,
+    /// A Spectral card in an Arcana pack, with Omen Globe.
+    OmenGlobe,
 }
 
-#[derive(Debug, EnumIter, Copy, Clone)]
+impl RandomSource {
+    /// The `key_append` the game adds to stream ids for items from this source.
+    pub fn code(&self) -> &'static str {
+        match self {
+            RandomSource::Shop => "sho",
+            RandomSource::BuffonPack => "buf",
+            RandomSource::Wraith => "wra",
+            RandomSource::RareTag => "rta",
+            RandomSource::UncommonTag => "uta",
+            RandomSource::Soul => "sou",
+            RandomSource::Arcana => "ar1",
+            RandomSource::Celestial => "pl1",
+            RandomSource::Judgement => "jud",
+            RandomSource::Emperor => "emp",
+            RandomSource::HighPriestess => "pri",
+            RandomSource::SpectralPack => "spe",
+            RandomSource::OmenGlobe => "ar2",
+        }
+    }
+
+    /// Whether cards from this source can roll The Soul or Black Hole: only
+    /// packs' can.
+    pub fn soulable(&self) -> bool {
+        matches!(
+            self,
+            RandomSource::Arcana | RandomSource::Celestial | RandomSource::SpectralPack | RandomSource::OmenGlobe
+        )
+    }
+}
+
+#[derive(Debug, EnumIter, Copy, Clone, PartialEq, Eq)]
 pub enum Tags {
     UncommonTag,
     RareTag,
@@ -45,25 +84,17 @@ pub enum Tags {
 impl ItemChoice for Tags {
     fn retry(&self) -> bool { false }
     fn locked(&self) -> bool { false }
+    fn retry_in(&self, run: &RunState) -> bool { run.is_banned(Ban::Tag(*self)) }
 }
 
 #[cfg(feature = "std")]
 impl Display for RandomSource {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            RandomSource::Shop => write!(f, "sho"),
-            RandomSource::BuffonPack => write!(f, "buf"),
-            RandomSource::Wraith => write!(f, "wra"),
-            RandomSource::RareTag => write!(f, "rta"),
-            RandomSource::UncommonTag => write!(f, "uta"),
-            RandomSource::Soul => write!(f, "sou"),
-            RandomSource::Arcana => write!(f, "ar1"),
-            RandomSource::Celestial => write!(f, "pl1"),
-        }
+        f.write_str(self.code())
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, EnumIter, Copy, Clone, PartialEq, Eq)]
 pub enum Editions {
     Negative,
     Polychrome,
@@ -72,7 +103,7 @@ pub enum Editions {
     None,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum JokerRarity {
     Common,
     Uncommon,
@@ -87,7 +118,7 @@ pub enum JokerStickers {
     Rental,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct Joker {
     pub joker: JokerTypes,
     pub rarity: JokerRarity,
@@ -121,12 +152,13 @@ impl Display for Joker {
     }
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, EnumIter, Copy, Clone, PartialEq, Eq)]
 pub enum Pack {
     Buffoon,
     Arcana,
     Spectral,
-    Planet
+    Planet,
+    Standard,
 }
 
 impl ItemChoice for Pack {
@@ -134,7 +166,7 @@ impl ItemChoice for Pack {
     fn locked(&self) -> bool { false }
 }
 
-#[derive(Debug, EnumIter, Copy, Clone)]
+#[derive(Debug, EnumIter, Copy, Clone, PartialEq, Eq)]
 pub enum CardTypes {
     One,
     Two,
@@ -152,7 +184,7 @@ pub enum CardTypes {
     Ace,
 }
 
-#[derive(Debug, EnumIter, Copy, Clone)]
+#[derive(Debug, EnumIter, Copy, Clone, PartialEq, Eq)]
 pub enum CardSuits {
     Hearts,
     Diamonds,
@@ -160,15 +192,16 @@ pub enum CardSuits {
     Spades,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum SealTypes {
     None,
     Red,
     Blue,
     Gold,
+    Purple,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum EnhancementTypes {
     None,
     Bonus,
@@ -181,7 +214,31 @@ pub enum EnhancementTypes {
     Lucky,
 }
 
-#[derive(Debug)]
+/// Poker hands in the game's `order`, best first.
+#[derive(Debug, EnumIter, Copy, Clone, PartialEq, Eq)]
+pub enum HandTypes {
+    FlushFive,
+    FlushHouse,
+    FiveOfAKind,
+    StraightFlush,
+    FourOfAKind,
+    FullHouse,
+    Flush,
+    Straight,
+    ThreeOfAKind,
+    TwoPair,
+    Pair,
+    HighCard,
+}
+
+impl HandTypes {
+    /// Secret hands stay hidden until first played.
+    pub fn is_secret(&self) -> bool {
+        matches!(self, HandTypes::FlushFive | HandTypes::FlushHouse | HandTypes::FiveOfAKind)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct Card {
     pub rank: CardTypes,
     pub suit: CardSuits,
@@ -207,7 +264,7 @@ impl Display for Card {
     }
 }
 
-#[derive(Debug, EnumIter, Copy, Clone)]
+#[derive(Debug, EnumIter, Copy, Clone, PartialEq, Eq)]
 pub enum Tarots {
     TheFool,
     TheMagician,
@@ -241,9 +298,14 @@ impl ItemChoice for Tarots {
     fn locked(&self) -> bool {
         false
     }
+
+    fn retry_in(&self, run: &RunState) -> bool {
+        (run.holds(Consumable::Tarot(*self)) && !run.allows_duplicates())
+            || run.is_banned(Ban::Consumable(Consumable::Tarot(*self)))
+    }
 }
 
-#[derive(Debug, EnumIter, Copy, Clone)]
+#[derive(Debug, EnumIter, Copy, Clone, PartialEq, Eq)]
 pub enum Planets {
     Mercury,
     Venus,
@@ -259,6 +321,26 @@ pub enum Planets {
     Eris,
 }
 
+impl Planets {
+    /// The poker hand this planet levels up.
+    pub fn hand(&self) -> HandTypes {
+        match self {
+            Planets::Mercury => HandTypes::Pair,
+            Planets::Venus => HandTypes::ThreeOfAKind,
+            Planets::Earth => HandTypes::FullHouse,
+            Planets::Mars => HandTypes::FourOfAKind,
+            Planets::Jupiter => HandTypes::Flush,
+            Planets::Saturn => HandTypes::Straight,
+            Planets::Uranus => HandTypes::TwoPair,
+            Planets::Neptune => HandTypes::StraightFlush,
+            Planets::Pluto => HandTypes::HighCard,
+            Planets::PlanetX => HandTypes::FiveOfAKind,
+            Planets::Ceres => HandTypes::FlushHouse,
+            Planets::Eris => HandTypes::FlushFive,
+        }
+    }
+}
+
 impl ItemChoice for Planets {
     fn retry(&self) -> bool {
         false
@@ -267,12 +349,62 @@ impl ItemChoice for Planets {
     fn locked(&self) -> bool {
         false
     }
+
+    fn retry_in(&self, run: &RunState) -> bool {
+        (run.holds(Consumable::Planet(*self)) && !run.allows_duplicates())
+            || !run.hands.planet_available(*self)
+            || run.is_banned(Ban::Consumable(Consumable::Planet(*self)))
+    }
+}
+
+#[derive(Debug, EnumIter, Copy, Clone, PartialEq, Eq)]
+pub enum Spectral {
+    Familiar,
+    Grim,
+    Incantation,
+    Talisman,
+    Aura,
+    Wraith,
+    Sigil,
+    Ouija,
+    Ectoplasm,
+    Immolate,
+    Ankh,
+    DejaVu,
+    Hex,
+    Trance,
+    Medium,
+    Cryptid,
+    TheSoul,
+    BlackHole,
+}
+
+impl ItemChoice for Spectral {
+    /// The Soul and Black Hole only come from their own rolls, never the pool.
+    fn retry(&self) -> bool {
+        matches!(self, Spectral::TheSoul | Spectral::BlackHole)
+    }
+
+    fn locked(&self) -> bool {
+        false
+    }
+
+    fn retry_in(&self, run: &RunState) -> bool {
+        self.retry()
+            || (run.holds(Consumable::Spectral(*self)) && !run.allows_duplicates())
+            || run.is_banned(Ban::Consumable(Consumable::Spectral(*self)))
+    }
 }
 
-#[derive(Debug)]
-pub enum Spectral {}
+/// A card that sits in a consumable slot.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Consumable {
+    Tarot(Tarots),
+    Planet(Planets),
+    Spectral(Spectral),
+}
 
-#[derive(Debug, EnumIter, Copy, Clone)]
+#[derive(Debug, EnumIter, Copy, Clone, PartialEq, Eq)]
 pub enum Vouchers {
     Overstock,
     OverstockPlus,
@@ -550,4 +682,24 @@ impl ItemChoice for JokerTypes {
     fn locked(&self) -> bool {
         false
     }
+
+    fn retry_in(&self, run: &RunState) -> bool {
+        let gate = match self {
+            JokerTypes::SteelJoker => Some(EnhancementTypes::Steel),
+            JokerTypes::StoneJoker => Some(EnhancementTypes::Stone),
+            JokerTypes::LuckyCat => Some(EnhancementTypes::Lucky),
+            JokerTypes::GoldenTicket => Some(EnhancementTypes::Gold),
+            JokerTypes::GlassJoker => Some(EnhancementTypes::Glass),
+            _ => None,
+        };
+        let flagged = match self {
+            JokerTypes::GrosMichel => run.gros_michel_extinct,
+            JokerTypes::Cavendish => !run.gros_michel_extinct,
+            _ => false,
+        };
+        ((run.holds_joker(*self) || run.shows_joker(*self)) && !run.allows_duplicates())
+            || gate.is_some_and(|e| !run.has_enhancement(e))
+            || flagged
+            || run.is_banned(Ban::Joker(*self))
+    }
 }
@@ -5,10 +5,24 @@ extern crate alloc;
 #[cfg(feature = "std")]
 pub mod deck;
 pub mod items;
-//pub mod pools;
+pub mod pools;
 pub mod random;
-//pub mod shop;
+pub mod shop;
 pub mod util;
 pub mod game;
 pub mod names;
-pub mod lock;
\ No newline at end of file
+pub mod lock;
+pub mod filter;
+pub mod score;
+pub mod reference;
+pub mod batch;
+pub mod walk;
+pub mod trace;
+pub mod cards;
+pub mod round;
+pub mod odds;
+pub mod hands;
+pub mod run;
+pub mod consumables;
+pub mod blind;
+pub mod challenge;
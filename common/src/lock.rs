@@ -1,12 +1,15 @@
 use alloc::string::String;
 use alloc::vec::Vec;
 
+#[derive(Default)]
 pub struct Lock {
     locked: Vec<String>,
     unlocked: Vec<String>,
+    // Out for good, whatever is unlocked later: a challenge's bans and the
+    // vouchers it starts with.
+    banned: Vec<String>,
 }
 
-impl Default for Lock { fn default() -> Self { Self { locked: Vec::new(), unlocked: Vec::new() } } }
 impl Lock {
     pub fn new() -> Self { Self::default() }
 
@@ -18,9 +21,11 @@ impl Lock {
     pub fn lock_many<'a, I: IntoIterator<Item=&'a str>>(&mut self, names: I) { for n in names { self.lock(n); } }
     pub fn unlock<S: AsRef<str>>(&mut self, name: S) { Self::push_unique(&mut self.unlocked, name.as_ref()); }
     pub fn unlock_many<'a, I: IntoIterator<Item=&'a str>>(&mut self, names: I) { for n in names { self.unlock(n); } }
+    pub fn ban<S: AsRef<str>>(&mut self, name: S) { Self::push_unique(&mut self.banned, name.as_ref()); }
 
     pub fn is_locked<S: AsRef<str>>(&self, name: S) -> bool {
         let n = name.as_ref();
+        if self.banned.iter().any(|s| s.as_str() == n) { return true; }
         if self.unlocked.iter().any(|s| s.as_str() == n) { return false; }
         self.locked.iter().any(|s| s.as_str() == n)
     }
@@ -51,9 +56,10 @@ impl Lock {
             ].iter().copied());
         }
 
+        // Planet X, Ceres and Eris are not locks but depend on the hands
+        // played, see `HandLevels::planet_available`.
         if fresh_run {
             self.lock_many([
-                "Planet X","Ceres","Eris",
                 "Stone Joker","Steel Joker","Glass Joker","Golden Ticket","Lucky Cat",
                 "Cavendish","Overstock Plus","Liquidation","Glow Up","Reroll Glut","Omen Globe","Observatory","Nacho Tong","Recyclomancy","Tarot Tycoon","Planet Tycoon","Money Tree","Antimatter","Illusion","Petroglyph","Retcon","Palette",
             ].iter().copied());
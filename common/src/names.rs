@@ -1,4 +1,85 @@
-use crate::items::{Bosses, Vouchers, Tags, Tarots, Planets};
+use crate::items::{Bosses, Vouchers, Tags, Tarots, Planets, JokerTypes, Spectral, Pack, Editions, HandTypes};
+use alloc::string::String;
+use core::fmt;
+use core::str::FromStr;
+use strum::IntoEnumIterator;
+
+/// Display names matching the game's (and Blueprint's) strings, with a
+/// forgiving reverse lookup for user input such as CLI filters.
+pub trait ItemName: Sized + Copy + IntoEnumIterator {
+    /// Short label for the item category, used in parse errors.
+    const KIND: &'static str;
+
+    fn name(&self) -> &'static str;
+
+    /// Case-insensitive lookup that ignores spaces and punctuation, so
+    /// "drivers license" finds "Driver's License" and "Oops All 6s" finds
+    /// "Oops! All 6s". A leading "The" may also be omitted.
+    fn from_name(s: &str) -> Option<Self> {
+        Self::iter()
+            .find(|item| names_match(item.name(), s))
+            .or_else(|| Self::iter().find(|item| names_match(strip_the(item.name()), s)))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseNameError {
+    pub kind: &'static str,
+    pub input: String,
+}
+
+impl fmt::Display for ParseNameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown {} name '{}'", self.kind, self.input)
+    }
+}
+
+#[inline]
+fn normalized(s: &str) -> impl Iterator<Item = char> + '_ {
+    s.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase)
+}
+
+#[inline]
+pub(crate) fn names_match(name: &str, input: &str) -> bool {
+    normalized(name).eq(normalized(input))
+}
+
+#[inline]
+fn strip_the(name: &str) -> &str {
+    name.strip_prefix("The ").unwrap_or(name)
+}
+
+macro_rules! item_names {
+    ($($ty:ty => $kind:literal, $func:ident;)*) => {$(
+        impl ItemName for $ty {
+            const KIND: &'static str = $kind;
+
+            #[inline]
+            fn name(&self) -> &'static str { $func(self) }
+        }
+
+        impl FromStr for $ty {
+            type Err = ParseNameError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                <$ty as ItemName>::from_name(s).ok_or_else(|| ParseNameError { kind: $kind, input: String::from(s) })
+            }
+        }
+    )*};
+}
+
+item_names! {
+    Bosses => "boss", boss_name;
+    Tags => "tag", tag_name;
+    Tarots => "tarot", tarot_name;
+    Planets => "planet", planet_name;
+    Vouchers => "voucher", voucher_name;
+    JokerTypes => "joker", joker_name;
+    Spectral => "spectral", spectral_name;
+    Pack => "pack", pack_name;
+    Editions => "edition", edition_name;
+    HandTypes => "hand", hand_name;
+}
 
 pub fn boss_name(b: &Bosses) -> &'static str {
     match b {
@@ -142,3 +223,218 @@ pub fn voucher_name(v: &Vouchers) -> &'static str {
         Vouchers::Palette => "Palette",
     }
 }
+
+pub fn spectral_name(s: &Spectral) -> &'static str {
+    match s {
+        Spectral::Familiar => "Familiar",
+        Spectral::Grim => "Grim",
+        Spectral::Incantation => "Incantation",
+        Spectral::Talisman => "Talisman",
+        Spectral::Aura => "Aura",
+        Spectral::Wraith => "Wraith",
+        Spectral::Sigil => "Sigil",
+        Spectral::Ouija => "Ouija",
+        Spectral::Ectoplasm => "Ectoplasm",
+        Spectral::Immolate => "Immolate",
+        Spectral::Ankh => "Ankh",
+        Spectral::DejaVu => "Deja Vu",
+        Spectral::Hex => "Hex",
+        Spectral::Trance => "Trance",
+        Spectral::Medium => "Medium",
+        Spectral::Cryptid => "Cryptid",
+        Spectral::TheSoul => "The Soul",
+        Spectral::BlackHole => "Black Hole",
+    }
+}
+
+pub fn pack_name(p: &Pack) -> &'static str {
+    match p {
+        Pack::Buffoon => "Buffoon Pack",
+        Pack::Arcana => "Arcana Pack",
+        Pack::Spectral => "Spectral Pack",
+        Pack::Planet => "Celestial Pack",
+        Pack::Standard => "Standard Pack",
+    }
+}
+
+pub fn edition_name(e: &Editions) -> &'static str {
+    match e {
+        Editions::Negative => "Negative",
+        Editions::Polychrome => "Polychrome",
+        Editions::Holographic => "Holographic",
+        Editions::Foil => "Foil",
+        Editions::None => "No Edition",
+    }
+}
+
+pub fn hand_name(h: &HandTypes) -> &'static str {
+    match h {
+        HandTypes::FlushFive => "Flush Five",
+        HandTypes::FlushHouse => "Flush House",
+        HandTypes::FiveOfAKind => "Five of a Kind",
+        HandTypes::StraightFlush => "Straight Flush",
+        HandTypes::FourOfAKind => "Four of a Kind",
+        HandTypes::FullHouse => "Full House",
+        HandTypes::Flush => "Flush",
+        HandTypes::Straight => "Straight",
+        HandTypes::ThreeOfAKind => "Three of a Kind",
+        HandTypes::TwoPair => "Two Pair",
+        HandTypes::Pair => "Pair",
+        HandTypes::HighCard => "High Card",
+    }
+}
+
+pub fn joker_name(j: &JokerTypes) -> &'static str {
+    match j {
+        JokerTypes::Joker => "Joker",
+        JokerTypes::GreedyJoker => "Greedy Joker",
+        JokerTypes::LustyJoker => "Lusty Joker",
+        JokerTypes::WrathfulJoker => "Wrathful Joker",
+        JokerTypes::GluttonousJoker => "Gluttonous Joker",
+        JokerTypes::JollyJoker => "Jolly Joker",
+        JokerTypes::ZanyJoker => "Zany Joker",
+        JokerTypes::MadJoker => "Mad Joker",
+        JokerTypes::CrazyJoker => "Crazy Joker",
+        JokerTypes::DrollJoker => "Droll Joker",
+        JokerTypes::SlyJoker => "Sly Joker",
+        JokerTypes::WilyJoker => "Wily Joker",
+        JokerTypes::CleverJoker => "Clever Joker",
+        JokerTypes::DeviousJoker => "Devious Joker",
+        JokerTypes::CraftyJoker => "Crafty Joker",
+        JokerTypes::HalfJoker => "Half Joker",
+        JokerTypes::CreditCard => "Credit Card",
+        JokerTypes::Banner => "Banner",
+        JokerTypes::MysticSummit => "Mystic Summit",
+        JokerTypes::EightBall => "8 Ball",
+        JokerTypes::Misprint => "Misprint",
+        JokerTypes::RaisedFist => "Raised Fist",
+        JokerTypes::ChaostheClown => "Chaos the Clown",
+        JokerTypes::ScaryFace => "Scary Face",
+        JokerTypes::AbstractJoker => "Abstract Joker",
+        JokerTypes::DelayedGratification => "Delayed Gratification",
+        JokerTypes::GrosMichel => "Gros Michel",
+        JokerTypes::EvenSteven => "Even Steven",
+        JokerTypes::OddTodd => "Odd Todd",
+        JokerTypes::Scholar => "Scholar",
+        JokerTypes::BusinessCard => "Business Card",
+        JokerTypes::Supernova => "Supernova",
+        JokerTypes::RideTheBus => "Ride the Bus",
+        JokerTypes::Egg => "Egg",
+        JokerTypes::Runner => "Runner",
+        JokerTypes::IceCream => "Ice Cream",
+        JokerTypes::Splash => "Splash",
+        JokerTypes::BlueJoker => "Blue Joker",
+        JokerTypes::FacelessJoker => "Faceless Joker",
+        JokerTypes::GreenJoker => "Green Joker",
+        JokerTypes::Superposition => "Superposition",
+        JokerTypes::ToDoList => "To Do List",
+        JokerTypes::Cavendish => "Cavendish",
+        JokerTypes::RedCard => "Red Card",
+        JokerTypes::SquareJoker => "Square Joker",
+        JokerTypes::RiffRaff => "Riff-raff",
+        JokerTypes::Photograph => "Photograph",
+        JokerTypes::ReservedParking => "Reserved Parking",
+        JokerTypes::MailInRebate => "Mail-In Rebate",
+        JokerTypes::Hallucination => "Hallucination",
+        JokerTypes::FortuneTeller => "Fortune Teller",
+        JokerTypes::Juggler => "Juggler",
+        JokerTypes::Drunkard => "Drunkard",
+        JokerTypes::GoldenJoker => "Golden Joker",
+        JokerTypes::Popcorn => "Popcorn",
+        JokerTypes::WalkieTalkie => "Walkie Talkie",
+        JokerTypes::SmileyFace => "Smiley Face",
+        JokerTypes::GoldenTicket => "Golden Ticket",
+        JokerTypes::Swashbuckler => "Swashbuckler",
+        JokerTypes::HangingChad => "Hanging Chad",
+        JokerTypes::ShootTheMoon => "Shoot the Moon",
+        JokerTypes::JokerStencil => "Joker Stencil",
+        JokerTypes::FourFingers => "Four Fingers",
+        JokerTypes::Mime => "Mime",
+        JokerTypes::CeremonialDagger => "Ceremonial Dagger",
+        JokerTypes::MarbleJoker => "Marble Joker",
+        JokerTypes::LoyaltyCard => "Loyalty Card",
+        JokerTypes::Dusk => "Dusk",
+        JokerTypes::Fibonacci => "Fibonacci",
+        JokerTypes::SteelJoker => "Steel Joker",
+        JokerTypes::Hack => "Hack",
+        JokerTypes::Pareidolia => "Pareidolia",
+        JokerTypes::SpaceJoker => "Space Joker",
+        JokerTypes::Burglar => "Burglar",
+        JokerTypes::Blackboard => "Blackboard",
+        JokerTypes::SixthSense => "Sixth Sense",
+        JokerTypes::Constellation => "Constellation",
+        JokerTypes::Hiker => "Hiker",
+        JokerTypes::CardSharp => "Card Sharp",
+        JokerTypes::Madness => "Madness",
+        JokerTypes::Seance => "Seance",
+        JokerTypes::Vampire => "Vampire",
+        JokerTypes::Shortcut => "Shortcut",
+        JokerTypes::Hologram => "Hologram",
+        JokerTypes::Cloud9 => "Cloud 9",
+        JokerTypes::Rocket => "Rocket",
+        JokerTypes::MidasMask => "Midas Mask",
+        JokerTypes::Luchador => "Luchador",
+        JokerTypes::GiftCard => "Gift Card",
+        JokerTypes::TurtleBean => "Turtle Bean",
+        JokerTypes::Erosion => "Erosion",
+        JokerTypes::ToTheMoon => "To the Moon",
+        JokerTypes::StoneJoker => "Stone Joker",
+        JokerTypes::LuckyCat => "Lucky Cat",
+        JokerTypes::Bull => "Bull",
+        JokerTypes::DietCola => "Diet Cola",
+        JokerTypes::TradingCard => "Trading Card",
+        JokerTypes::FlashCard => "Flash Card",
+        JokerTypes::SpareTrousers => "Spare Trousers",
+        JokerTypes::Ramen => "Ramen",
+        JokerTypes::Seltzer => "Seltzer",
+        JokerTypes::Castle => "Castle",
+        JokerTypes::MrBones => "Mr. Bones",
+        JokerTypes::Acrobat => "Acrobat",
+        JokerTypes::SockAndBuskin => "Sock and Buskin",
+        JokerTypes::Troubadour => "Troubadour",
+        JokerTypes::Certificate => "Certificate",
+        JokerTypes::SmearedJoker => "Smeared Joker",
+        JokerTypes::Throwback => "Throwback",
+        JokerTypes::RoughGem => "Rough Gem",
+        JokerTypes::Bloodstone => "Bloodstone",
+        JokerTypes::Arrowhead => "Arrowhead",
+        JokerTypes::OnyxAgate => "Onyx Agate",
+        JokerTypes::GlassJoker => "Glass Joker",
+        JokerTypes::Showman => "Showman",
+        JokerTypes::FlowerPot => "Flower Pot",
+        JokerTypes::MerryAndy => "Merry Andy",
+        JokerTypes::OopsAllSixes => "Oops! All 6s",
+        JokerTypes::TheIdol => "The Idol",
+        JokerTypes::SeeingDouble => "Seeing Double",
+        JokerTypes::Matador => "Matador",
+        JokerTypes::Satellite => "Satellite",
+        JokerTypes::Cartomancer => "Cartomancer",
+        JokerTypes::Astronomer => "Astronomer",
+        JokerTypes::Bootstraps => "Bootstraps",
+        JokerTypes::DNA => "DNA",
+        JokerTypes::Vagabond => "Vagabond",
+        JokerTypes::Baron => "Baron",
+        JokerTypes::Obelisk => "Obelisk",
+        JokerTypes::BaseballCard => "Baseball Card",
+        JokerTypes::AncientJoker => "Ancient Joker",
+        JokerTypes::Campfire => "Campfire",
+        JokerTypes::Blueprint => "Blueprint",
+        JokerTypes::WeeJoker => "Wee Joker",
+        JokerTypes::HitTheRoad => "Hit the Road",
+        JokerTypes::TheDuo => "The Duo",
+        JokerTypes::TheTrio => "The Trio",
+        JokerTypes::TheFamily => "The Family",
+        JokerTypes::TheOrder => "The Order",
+        JokerTypes::TheTribe => "The Tribe",
+        JokerTypes::Stuntman => "Stuntman",
+        JokerTypes::InvisibleJoker => "Invisible Joker",
+        JokerTypes::Brainstorm => "Brainstorm",
+        JokerTypes::DriversLicense => "Driver's License",
+        JokerTypes::BurntJoker => "Burnt Joker",
+        JokerTypes::Canio => "Canio",
+        JokerTypes::Triboulet => "Triboulet",
+        JokerTypes::Yorick => "Yorick",
+        JokerTypes::Chicot => "Chicot",
+        JokerTypes::Perkeo => "Perkeo",
+    }
+}
@@ -0,0 +1,137 @@
+//! Chance-based effects. The game rolls each with `pseudorandom(stream) <
+//! G.GAME.probabilities.normal / odds`, where every Oops! All 6s doubles
+//! `normal`. The streams are shared by every card of a kind and never reset,
+//! so the n-th roll of a stream in a run always lands the same way; a route
+//! only changes which rolls it spends where.
+
+use crate::items::Editions;
+use crate::random::Random;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Effects that succeed with a fixed chance.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Roll {
+    /// Lucky card, +20 Mult: 1 in 5.
+    LuckyMult,
+    /// Lucky card, $20: 1 in 15.
+    LuckyMoney,
+    /// The Wheel of Fortune adds an edition: 1 in 4. See [`wheel_of_fortune`].
+    WheelOfFortune,
+    /// Glass card breaks after scoring: 1 in 4.
+    Glass,
+    /// Bloodstone, X1.5 Mult for a scored Heart: 1 in 2.
+    Bloodstone,
+    /// 8 Ball, a Tarot for a scored 8: 1 in 4. Only rolled while a consumable slot is free.
+    EightBall,
+    /// Space Joker levels up the played hand: 1 in 4.
+    Space,
+    /// Business Card, $2 for a scored face card: 1 in 2.
+    Business,
+    /// Reserved Parking, $1 for a face card held in hand: 1 in 2.
+    ReservedParking,
+    /// Hallucination, a Tarot when a booster pack is opened: 1 in 2.
+    Hallucination,
+    /// Gros Michel goes extinct at the end of a round: 1 in 6.
+    GrosMichel,
+    /// Cavendish goes extinct at the end of a round: 1 in 1000.
+    Cavendish,
+}
+
+impl Roll {
+    /// The `N` of "1 in N".
+    pub fn odds(self) -> u32 {
+        match self {
+            Roll::LuckyMult => 5,
+            Roll::LuckyMoney => 15,
+            Roll::WheelOfFortune | Roll::Glass | Roll::EightBall | Roll::Space => 4,
+            Roll::Bloodstone | Roll::Business | Roll::ReservedParking | Roll::Hallucination => 2,
+            Roll::GrosMichel => 6,
+            Roll::Cavendish => 1000,
+        }
+    }
+
+    /// Stream id; only Hallucination's changes with the ante.
+    pub fn stream(self, ante: i32) -> String {
+        String::from(match self {
+            Roll::LuckyMult => "lucky_mult",
+            Roll::LuckyMoney => "lucky_money",
+            Roll::WheelOfFortune => "wheel_of_fortune",
+            Roll::Glass => "glass",
+            Roll::Bloodstone => "bloodstone",
+            Roll::EightBall => "8ball",
+            Roll::Space => "space",
+            Roll::Business => "business",
+            Roll::ReservedParking => "parking",
+            Roll::Hallucination => return alloc::format!("halu{ante}"),
+            Roll::GrosMichel => "gros_michel",
+            Roll::Cavendish => "cavendish",
+        })
+    }
+}
+
+/// `G.GAME.probabilities.normal`: 1, doubled by each Oops! All 6s.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Probabilities {
+    pub normal: f64,
+}
+
+impl Default for Probabilities {
+    fn default() -> Self {
+        Self { normal: 1.0 }
+    }
+}
+
+impl Probabilities {
+    pub fn with_oops_all_sixes(count: u32) -> Self {
+        Self { normal: libm::exp2(count as f64) }
+    }
+
+    /// Whether a draw of `poll` succeeds at 1 in `odds`.
+    #[inline]
+    pub fn hits(&self, poll: f64, odds: u32) -> bool {
+        poll < self.normal / odds as f64
+    }
+}
+
+/// The next roll of `roll`'s stream.
+pub fn roll(random: &mut Random, roll: Roll, ante: i32, probabilities: Probabilities) -> bool {
+    probabilities.hits(random.random_str(&roll.stream(ante)), roll.odds())
+}
+
+/// The next `n` rolls of `roll`'s stream, in order.
+pub fn rolls(random: &mut Random, roll: Roll, ante: i32, probabilities: Probabilities, n: usize) -> Vec<bool> {
+    let id = roll.stream(ante);
+    (0..n).map(|_| probabilities.hits(random.random_str(&id), roll.odds())).collect()
+}
+
+/// What a successful The Wheel of Fortune does.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct WheelHit {
+    /// Index of the joker among those without an edition, in the order they
+    /// were created (their `sort_id`).
+    pub joker: usize,
+    /// Foil, Holographic or Polychrome; never Negative.
+    pub edition: Editions,
+}
+
+/// One use of The Wheel of Fortune with `eligible` jokers lacking an edition.
+/// The roll, the joker and its edition all come from `wheel_of_fortune`, the
+/// last two only on a hit. The game does not allow the card without an
+/// eligible joker, so then nothing is drawn.
+pub fn wheel_of_fortune(random: &mut Random, eligible: usize, probabilities: Probabilities) -> Option<WheelHit> {
+    if eligible == 0 || !roll(random, Roll::WheelOfFortune, 0, probabilities) {
+        return None;
+    }
+    let joker = random.rand_int_str("wheel_of_fortune", 0, eligible as i32 - 1) as usize;
+    // poll_edition('wheel_of_fortune', nil, true, true): guaranteed, no Negative.
+    let poll = random.random_str("wheel_of_fortune");
+    let edition = if poll > 1.0 - 0.006 * 25.0 {
+        Editions::Polychrome
+    } else if poll > 1.0 - 0.02 * 25.0 {
+        Editions::Holographic
+    } else {
+        Editions::Foil
+    };
+    Some(WheelHit { joker, edition })
+}
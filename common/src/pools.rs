@@ -1,14 +1,18 @@
-#[cfg(feature = "std")]
+use alloc::vec::Vec;
 use strum::IntoEnumIterator;
-use crate::items::{JokerTypes, RandomSource, Spectral};
-#[cfg(feature = "std")]
-use crate::items::{Editions, Joker, JokerRarity, Planets, Tarots};
+use crate::cards::poll_edition;
+use crate::items::{self, Consumable, JokerRarity, JokerTypes, Planets, RandomSource, Spectral, Tarots};
 
 use crate::items::JokerTypes::*;
 use crate::random::Random;
+use crate::run::RunState;
 
-#[cfg(feature = "std")]
-pub fn next_joker(random: &mut Random, source: RandomSource, ante: i32, has_stickers: bool) -> Joker {
+/// `create_card('Joker', ...)` from `source`: a rarity from `rarity{ante}{source}`
+/// unless the source fixes it, a joker from that rarity's pool less the ones
+/// `run` rules out, then an edition from `edi{source}{ante}` at the run's
+/// edition rate.
+pub fn next_joker(random: &mut Random, run: &RunState, source: RandomSource, ante: i32, has_stickers: bool) -> items::Joker {
+    let code = source.code();
     // Get rarity
     let rarity = match source {
         RandomSource::Soul => JokerRarity::Legendary,
@@ -16,7 +20,7 @@ pub fn next_joker(random: &mut Random, source: RandomSource, ante: i32, has_stic
         RandomSource::RareTag => JokerRarity::Rare,
         RandomSource::UncommonTag => JokerRarity::Uncommon,
         _ => {
-            match random.random(&format!("rarity{ante}{source}")) {
+            match random.random_str(&alloc::format!("rarity{ante}{code}")) {
                 x if x > 0.95 => JokerRarity::Rare,
                 x if x > 0.7 => JokerRarity::Uncommon,
                 _ => JokerRarity::Common,
@@ -24,27 +28,16 @@ pub fn next_joker(random: &mut Random, source: RandomSource, ante: i32, has_stic
         }
     };
 
-    // Get edition
-    let edition_rate = 1.0f64;
-    // TODO glow up/hone vouchers
-    let edition = match random.random(&format!("edi{source}{ante}")) {
-        x if x > 0.997 => Editions::Negative,
-        x if x > 1.0 - 0.006 * edition_rate => Editions::Polychrome,
-        x if x > 1.0 - 0.02 * edition_rate => Editions::Holographic,
-        x if x > 1.0 - 0.04 * edition_rate => Editions::Foil,
-        _ => Editions::None,
-    };
-
-    // Get next joker
+    // Get next joker; the legendary pool has one stream for the whole run
     let joker = *match rarity {
-        JokerRarity::Legendary => random.rand_choice("Joker4", &LEGENDARY_JOKERS),
-        JokerRarity::Rare => random.rand_choice(&format!("Joker3{source}{ante}"), &RARE_JOKERS),
-        JokerRarity::Uncommon => random.rand_choice(&format!("Joker2{source}{ante}"), &UNCOMMON_JOKERS),
-        JokerRarity::Common => random.rand_choice(&format!("Joker1{source}{ante}"), &COMMON_JOKERS),
+        JokerRarity::Legendary => random.rand_choice_str_in("Joker4", &LEGENDARY_JOKERS, run, &Joker),
+        JokerRarity::Rare => random.rand_choice_str_in(&alloc::format!("Joker3{code}{ante}"), &RARE_JOKERS, run, &Joker),
+        JokerRarity::Uncommon => random.rand_choice_str_in(&alloc::format!("Joker2{code}{ante}"), &UNCOMMON_JOKERS, run, &Joker),
+        JokerRarity::Common => random.rand_choice_str_in(&alloc::format!("Joker1{code}{ante}"), &COMMON_JOKERS, run, &Joker),
     };
 
     // Get next joker stickers
-    let stickers = vec![false; 3];
+    let stickers = [false; 3];
     if has_stickers {
         /* TODO stickers
         let _stickers = random.random(&format!("{ante}{}", match source {
@@ -72,7 +65,10 @@ pub fn next_joker(random: &mut Random, source: RandomSource, ante: i32, has_stic
         }*/
     }
 
-    Joker {
+    // Get edition
+    let edition = poll_edition(random, &alloc::format!("edi{code}{ante}"), 1.0, run.edition_rate, false);
+
+    items::Joker {
         joker,
         rarity,
         edition,
@@ -80,24 +76,65 @@ pub fn next_joker(random: &mut Random, source: RandomSource, ante: i32, has_stic
     }
 }
 
-#[cfg(feature = "std")]
-pub fn next_tarot(random: &mut Random, source: RandomSource, ante: i32, soulable: bool) -> Tarots {
-    if soulable && random.random(&format!("soul_Tarot{ante}")) > 0.997 {
-        return Tarots::TheSoul;
+/// `create_card`'s rolls for The Soul (Tarot and Spectral cards) then Black
+/// Hole (Planet and Spectral cards) from `soul_{set}{ante}`; the later hit
+/// wins. A card the run already holds is not rolled for, unless it owns Showman.
+fn soul_roll(random: &mut Random, run: &RunState, set: &str, ante: i32) -> Option<Spectral> {
+    let id = alloc::format!("soul_{set}{ante}");
+    let mut forced = None;
+    for (card, sets) in [(Spectral::TheSoul, ["Tarot", "Spectral"]), (Spectral::BlackHole, ["Planet", "Spectral"])] {
+        let held = run.holds(Consumable::Spectral(card)) && !run.allows_duplicates();
+        if sets.contains(&set) && !held && random.random_str(&id) > 0.997 {
+            forced = Some(card);
+        }
     }
-    *random.rand_choice(&format!("Tarot{source}{ante}"), Tarots::iter().collect::<Vec<_>>().as_slice())
+    forced
 }
 
-#[cfg(feature = "std")]
-pub fn next_planet(random: &mut Random, source: RandomSource, ante: i32, soulable: bool) -> Planets {
-    if soulable && random.random(&format!("soul_Planet{ante}")) > 0.997 {
-        return Planets::BlackHole;
+/// `create_card('Tarot', ...)` from `source`: The Soul when `soulable` and its
+/// roll hits, else a Tarot from `Tarot{source}{ante}`.
+pub fn next_tarot(random: &mut Random, run: &RunState, source: RandomSource, ante: i32, soulable: bool) -> Consumable {
+    if let Some(card) = soulable.then(|| soul_roll(random, run, "Tarot", ante)).flatten() {
+        return Consumable::Spectral(card);
     }
-    *random.rand_choice(&format!("Planet{source}{ante}"), Planets::iter().collect::<Vec<_>>().as_slice())
+    let all: Vec<Tarots> = Tarots::iter().collect();
+    let id = alloc::format!("Tarot{}{ante}", source.code());
+    Consumable::Tarot(*random.rand_choice_str_in(&id, &all, run, &Tarots::Strength))
 }
 
-pub fn next_spectral(_random: &mut Random, _source: RandomSource, _ante: i32, _soulable: bool) -> Spectral {
-    todo!()
+/// `create_card('Planet', ...)` from `source`: Black Hole when `soulable` and
+/// its roll hits, else a Planet from `Planet{source}{ante}`.
+pub fn next_planet(random: &mut Random, run: &RunState, source: RandomSource, ante: i32, soulable: bool) -> Consumable {
+    if let Some(card) = soulable.then(|| soul_roll(random, run, "Planet", ante)).flatten() {
+        return Consumable::Spectral(card);
+    }
+    let all: Vec<Planets> = Planets::iter().collect();
+    let id = alloc::format!("Planet{}{ante}", source.code());
+    Consumable::Planet(*random.rand_choice_str_in(&id, &all, run, &Planets::Pluto))
+}
+
+/// `create_card('Spectral', ...)` from `source`: The Soul or Black Hole when
+/// `soulable` and a roll hits, else a Spectral from `Spectral{source}{ante}`.
+pub fn next_spectral(random: &mut Random, run: &RunState, source: RandomSource, ante: i32, soulable: bool) -> Spectral {
+    if let Some(card) = soulable.then(|| soul_roll(random, run, "Spectral", ante)).flatten() {
+        return card;
+    }
+    let all: Vec<Spectral> = Spectral::iter().collect();
+    let id = alloc::format!("Spectral{}{ante}", source.code());
+    *random.rand_choice_str_in(&id, &all, run, &Spectral::Incantation)
+}
+
+/// The rarity pool `joker` belongs to.
+pub fn rarity(joker: JokerTypes) -> JokerRarity {
+    if COMMON_JOKERS.contains(&joker) {
+        JokerRarity::Common
+    } else if UNCOMMON_JOKERS.contains(&joker) {
+        JokerRarity::Uncommon
+    } else if RARE_JOKERS.contains(&joker) {
+        JokerRarity::Rare
+    } else {
+        JokerRarity::Legendary
+    }
 }
 
 pub const COMMON_JOKERS: [JokerTypes; 61] = [
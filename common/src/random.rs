@@ -1,9 +1,12 @@
+use crate::run::RunState;
+use crate::trace::TraceEvent;
 use crate::util::{pseudohash_bytes, round13, LuaRandom};
-use libm::{floor, fma};
+use libm::floor;
+use core::fmt;
 use core::mem::MaybeUninit;
 use alloc::collections::BTreeMap;
 use alloc::string::String;
-use alloc::collections::btree_map::Entry;
+use alloc::vec::Vec;
 use alloc::borrow::ToOwned;
 
 pub struct Random {
@@ -13,7 +16,7 @@ pub struct Random {
     // Full seed string for string-based IDs (Blueprint parity)
     seed_full: String,
     pub hashed_seed: f64,
-    // JS-style hashed seed for string-id path (computed via pseudohash_bytes over seed_full)
+    // Game hashed seed of seed_full for the string-id path; equal to hashed_seed for seeds of up to 8 bytes
     hashed_seed_js: f64,
     pub lua_random: LuaRandom,
     // Lazily initialized node values; avoid touching memory on construction by
@@ -22,6 +25,8 @@ pub struct Random {
     init_mask: [u64; (IDS_LEN + 63) / 64],
     // String-keyed nodes to mirror Blueprint's id+seed hashing (e.g., "boss1", "Voucher1", "Tarotsho1_resample2")
     str_nodes: BTreeMap<String, f64>,
+    // Uses of string-keyed streams, recorded only once tracing is enabled
+    trace: Option<Vec<TraceEvent>>,
 }
 
 impl Default for Random {
@@ -36,11 +41,15 @@ impl Default for Random {
             nodes: MaybeUninit::uninit(),
             init_mask: [0u64; (IDS_LEN + 63) / 64],
             str_nodes: BTreeMap::new(),
+            trace: None,
         }
     }
 }
 
 pub const RESAMPLE_IDS_LEN: usize = 10;
+// The game resamples string-keyed pools until it finds a usable item. Once one
+// exists, 10k draws miss it with odds below e^-60 even in the largest pool.
+pub const MAX_STR_RESAMPLES: usize = 10_000;
 // Support many independent RNG groups (streams x sources). Keep a safety margin.
 pub const IDS_LEN: usize = 6000;
 
@@ -50,10 +59,39 @@ pub enum RandIds {
 
 // node_mapping removed; we directly incorporate group/resample indices into the hash.
 
+/// Why a draw from a pool found no usable item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChoiceError {
+    /// The pool has no items.
+    Empty,
+    /// Every item is locked or asks for a retry; `first` is the index drawn.
+    NoneUsable { first: usize },
+    /// Usable items exist but `attempts` draws missed them all.
+    Exhausted { first: usize, attempts: usize },
+}
+
+impl fmt::Display for ChoiceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChoiceError::Empty => write!(f, "the pool is empty"),
+            ChoiceError::NoneUsable { .. } => write!(f, "every item in the pool is locked"),
+            ChoiceError::Exhausted { attempts, .. } => write!(f, "no usable item after {attempts} draws"),
+        }
+    }
+}
+
 pub trait ItemChoice {
     fn retry(&self) -> bool;
 
     fn locked(&self) -> bool;
+
+    /// [`ItemChoice::retry`] in a run in progress, where `get_current_pool`
+    /// also leaves out cards `run` already holds and those its pool flags or
+    /// deck rule out.
+    fn retry_in(&self, run: &RunState) -> bool {
+        let _ = run;
+        self.retry()
+    }
 }
 
 impl Random {
@@ -70,6 +108,7 @@ impl Random {
             nodes: MaybeUninit::uninit(),
             init_mask: [0u64; (IDS_LEN + 63) / 64],
             str_nodes: BTreeMap::new(),
+            trace: None,
         };
         s.set_seed_bytes(seed);
         s.seed_full = core::str::from_utf8(seed).map(|s| s.to_owned()).unwrap_or_else(|_| {
@@ -77,7 +116,7 @@ impl Random {
             String::from_utf8_lossy(seed).into_owned()
         });
         s.hashed_seed = pseudohash_bytes([s.seed_bytes()]);
-        s.hashed_seed_js = Self::pseudohash_js_str(&s.seed_full);
+        s.hashed_seed_js = pseudohash_bytes([s.seed_full.as_bytes()]);
         s
     }
 
@@ -88,7 +127,7 @@ impl Random {
             String::from_utf8_lossy(seed).into_owned()
         });
         self.hashed_seed = pseudohash_bytes([self.seed_bytes()]);
-        self.hashed_seed_js = Self::pseudohash_js_str(&self.seed_full);
+        self.hashed_seed_js = pseudohash_bytes([self.seed_full.as_bytes()]);
         // Clear initialization mask so nodes will be lazily recomputed for the new seed
         for m in &mut self.init_mask {
             *m = 0;
@@ -111,6 +150,9 @@ impl Random {
         &self.seed_inline[..self.seed_inline_len as usize]
     }
 
+    /// Fast numeric node for benchmarks and synthetic streams. The hashing and
+    /// progression are the game's, but the key strings are made up from `id`,
+    /// so the values match no game stream; use [`Random::get_node_str`] for those.
     #[inline(always)]
     pub fn get_node(&mut self, id: usize) -> f64 {
         debug_assert!(id < IDS_LEN);
@@ -161,8 +203,8 @@ impl Random {
         }
         // Safe: guarded by init_mask bit above
         let current = unsafe { (*self.nodes.as_ptr())[id] };
-        // Use fused multiply-add and fast fractional part extraction to avoid costly `% 1.0`.
-        let t = fma(current, 1.72431234, 2.134453429141);
+        // Same progression as the game's pseudoseed; a fused multiply-add would round differently.
+        let t = current * 1.72431234 + 2.134453429141;
         let advanced = round13(t - floor(t));
         // Update stored node value
         unsafe { (*self.nodes.as_mut_ptr())[id] = advanced };
@@ -181,33 +223,50 @@ impl Random {
         self.lua_random.randint(min, max)
     }
 
+    /// [`Random::try_rand_choice`] for pools that always hold a usable item.
+    ///
+    /// # Panics
+    /// When no usable item is found.
     pub fn rand_choice<'b, T: ItemChoice>(&mut self, id: usize, items: &'b [T]) -> &'b T {
+        self.try_rand_choice(id, items).unwrap_or_else(|e| Self::no_item_found(e))
+    }
+
+    /// [`Random::try_rand_choice`], or `fallback` when no usable item is found.
+    pub fn rand_choice_or<'b, T: ItemChoice>(&mut self, id: usize, items: &'b [T], fallback: &'b T) -> &'b T {
+        self.try_rand_choice(id, items).unwrap_or(fallback)
+    }
+
+    /// Draw from `items`, resampling within the group of `id` while the pick is
+    /// locked or asks for a retry. A group holds [`RESAMPLE_IDS_LEN`] ids, so
+    /// this gives up after that many draws.
+    pub fn try_rand_choice<'b, T: ItemChoice>(&mut self, id: usize, items: &'b [T]) -> Result<&'b T, ChoiceError> {
+        if items.is_empty() {
+            return Err(ChoiceError::Empty);
+        }
+        let max = items.len() as i32 - 1;
         // Initial draw
-        self.lua_random = LuaRandom::new(self.get_node(id));
-        let mut item = &items[self.lua_random.randint(0, items.len() as i32 - 1) as usize];
-
-        // If the item is not usable, deterministically resample with new per-attempt IDs,
-        // using a stack buffer to avoid allocations.
-        if item.locked() || item.retry() {
-            for resample in 2usize..RESAMPLE_IDS_LEN {
-                // Target string should be: "{id}_resample{resample}"
-                // Stay within the same group as `id` to avoid out-of-bounds indexing.
-                // Group base is the start index of the group containing `id`.
-                let group_base = id - (id % RESAMPLE_IDS_LEN);
-                self.lua_random = LuaRandom::new(self.get_node(group_base + resample));
-                item = &items[self.lua_random.randint(0, items.len() as i32 - 1) as usize];
-                if !item.retry() && !item.locked() {
-                    return item;
-                }
+        let first = self.rand_int(id, 0, max) as usize;
+        if usable(&items[first]) {
+            return Ok(&items[first]);
+        }
+        if !items.iter().any(usable) {
+            return Err(ChoiceError::NoneUsable { first });
+        }
+        // Stay within the same group as `id` to avoid out-of-bounds indexing.
+        // Group base is the start index of the group containing `id`.
+        let group_base = id - (id % RESAMPLE_IDS_LEN);
+        for resample in 2usize..RESAMPLE_IDS_LEN {
+            let item = &items[self.rand_int(group_base + resample, 0, max) as usize];
+            if usable(item) {
+                return Ok(item);
             }
-            Self::no_item_found();
         }
-        item
+        Err(ChoiceError::Exhausted { first, attempts: RESAMPLE_IDS_LEN - 1 })
     }
 
     #[cold]
-    fn no_item_found() -> ! {
-        panic!("Failed to find a usable item!");
+    fn no_item_found(e: ChoiceError) -> ! {
+        panic!("Failed to find a usable item: {e}");
     }
 
     // Convert usize to decimal string in a stack buffer; returns the string slice within `buf`.
@@ -241,112 +300,131 @@ impl Random {
     }
 
     // ---------------------- String-based helpers (QueueName parity) ----------------------
+    /// The game's `pseudoseed(id)`, bit-for-bit (see [`crate::reference`]).
     #[inline(always)]
-    // JS-style pseudohash over a single string (no fused ops)
-    fn pseudohash_js_str(s: &str) -> f64 {
-        let bytes = s.as_bytes();
-        let mut num = 1.0f64;
-        // iterate from end to start
-        let mut i = bytes.len() as i32;
-        while i > 0 {
-            let b = bytes[(i - 1) as usize] as f64;
-            // t = (1.1239285023/num) * b * PI + PI * i
-            let t = (1.1239285023 / num) * b * core::f64::consts::PI
-                + core::f64::consts::PI * (i as f64);
-            num = t - floor(t);
-            i -= 1;
-        }
-        num
-    }
-
-    // JS-style pseudohash over concatenation of two strings: id + seed
-    fn pseudohash_js_concat(id: &str, seed: &str) -> f64 {
-        // Build concatenated byte buffer
-        let mut buf = alloc::vec::Vec::with_capacity(id.len() + seed.len());
-        buf.extend_from_slice(id.as_bytes());
-        buf.extend_from_slice(seed.as_bytes());
-        let mut num = 1.0f64;
-        let mut i = buf.len() as i32;
-        while i > 0 {
-            let b = buf[(i - 1) as usize] as f64;
-            let t = (1.1239285023 / num) * b * core::f64::consts::PI
-                + core::f64::consts::PI * (i as f64);
-            num = t - floor(t);
-            i -= 1;
-        }
-        num
-    }
-    #[inline(always)]
-    pub(crate) fn get_node_str(&mut self, id: &str) -> f64 {
-        // Lookup or initialize node value for this string id
-        let entry = if let Entry::Occupied(e) = self.str_nodes.entry(String::from(id)) {
-            e.into_mut()
+    pub fn get_node_str(&mut self, id: &str) -> f64 {
+        // Progression mirrors the Lua (no fused operations)
+        let advance = |c: f64| {
+            let t = (c * 1.72431234) + 2.134453429141f64;
+            round13(t - floor(t))
+        };
+        let (advanced, initial) = if let Some(node) = self.str_nodes.get_mut(id) {
+            *node = advance(*node);
+            (*node, None)
         } else {
-            // compute initial without borrowing self across map mutation
             // JS uses pseudohash(id + seed) on the concatenated string
-            let init = Self::pseudohash_js_concat(id, &self.seed_full);
-            self.str_nodes.insert(String::from(id), init);
-            // safe to unwrap since just inserted
-            self.str_nodes.get_mut(id).unwrap()
+            let init = pseudohash_bytes([id.as_bytes(), self.seed_full.as_bytes()]);
+            let advanced = advance(init);
+            self.str_nodes.insert(String::from(id), advanced);
+            (advanced, Some(init))
         };
-        // Progression and final mix mirrors JS (avoid fused operations)
-        let t = (*entry * 1.72431234) + 2.134453429141f64;
-        let advanced = round13(t - floor(t));
-        *entry = advanced;
-        (advanced + self.hashed_seed_js) / 2.0
+        let mixed = (advanced + self.hashed_seed_js) / 2.0;
+        if let Some(trace) = &mut self.trace {
+            trace.push(TraceEvent {
+                seed: self.seed_full.clone(),
+                id: String::from(id),
+                initial,
+                node: advanced,
+                mixed,
+                draw: None,
+            });
+        }
+        mixed
     }
 
-    #[inline(always)]
-    pub(crate) fn debug_node_str(&mut self, id: &str) -> (f64, f64, f64) {
-        // initial c without mutating cache yet
-        let c = if let Some(v) = self.str_nodes.get(id) {
-            *v
-        } else {
-            Self::pseudohash_js_concat(id, &self.seed_full)
-        };
-        let t = (c * 1.72431234) + 2.134453429141f64;
-        let value = round13(t - floor(t));
-        let mixed = (value + self.hashed_seed_js) / 2.0;
-        // advance cache like get_node_str
-        self.str_nodes.insert(String::from(id), value);
-        (c, value, mixed)
+    /// Record string-keyed stream uses from now on, see [`Random::take_trace`].
+    pub fn enable_trace(&mut self) {
+        self.trace.get_or_insert_with(Vec::new);
     }
 
+    /// Events recorded since tracing was enabled or last taken, oldest first.
+    /// Numeric ids ([`Random::get_node`]) are synthetic and never recorded.
+    pub fn take_trace(&mut self) -> Vec<TraceEvent> {
+        self.trace.as_mut().map(core::mem::take).unwrap_or_default()
+    }
+
+    /// `pseudorandom(id)`: seed the Lua generator from `pseudoseed(id)` and draw once.
     #[inline(always)]
     pub fn random_str(&mut self, id: &str) -> f64 {
         self.lua_random = LuaRandom::new(self.get_node_str(id));
-        self.lua_random.random()
+        let draw = self.lua_random.random();
+        if let Some(event) = self.trace.as_mut().and_then(|t| t.last_mut()) {
+            event.draw = Some(draw);
+        }
+        draw
     }
 
     #[inline(always)]
     pub fn rand_int_str(&mut self, id: &str, min: i32, max: i32) -> i32 {
-        self.lua_random = LuaRandom::new(self.get_node_str(id));
-        self.lua_random.randint(min, max)
+        LuaRandom::scale_int(self.random_str(id), min, max)
     }
 
+    /// [`Random::try_rand_choice_str`] for pools that always hold a usable item.
+    ///
+    /// # Panics
+    /// When no usable item is found.
     pub fn rand_choice_str<'b, T: ItemChoice>(&mut self, id: &str, items: &'b [T]) -> &'b T {
-        // Initial draw
-        self.lua_random = LuaRandom::new(self.get_node_str(id));
-        let mut item = &items[self.lua_random.randint(0, items.len() as i32 - 1) as usize];
-        if item.locked() || item.retry() {
-            for resample in 2usize..RESAMPLE_IDS_LEN {
-                let res_id = {
-                    let mut s = String::from(id);
-                    s.push_str("_resample");
-                    // write resample number (<= 4 digits as in usize->itoa used elsewhere)
-                    let mut buf = [0u8; 4];
-                    let digits = Self::itoa_usize_bytes(&mut buf, resample);
-                    s.push_str(core::str::from_utf8(digits).unwrap_or(""));
-                    s
-                };
-                self.lua_random = LuaRandom::new(self.get_node_str(&res_id));
-                item = &items[self.lua_random.randint(0, items.len() as i32 - 1) as usize];
-                if !item.retry() && !item.locked() {
-                    return item;
-                }
+        self.try_rand_choice_str(id, items).unwrap_or_else(|e| Self::no_item_found(e))
+    }
+
+    /// [`Random::try_rand_choice_str`], falling back as the game does: a pool
+    /// with nothing available holds only its default item (Joker, Strength,
+    /// Pluto, ...), after one draw from `id`.
+    pub fn rand_choice_str_or<'b, T: ItemChoice>(&mut self, id: &str, items: &'b [T], fallback: &'b T) -> &'b T {
+        self.try_rand_choice_str(id, items).unwrap_or(fallback)
+    }
+
+    /// The game's `pseudorandom_element` with its resampling: draw from `items`
+    /// with `id`, then with `{id}_resample2`, `{id}_resample3`, ... while the
+    /// pick is locked or asks for a retry.
+    pub fn try_rand_choice_str<'b, T: ItemChoice>(&mut self, id: &str, items: &'b [T]) -> Result<&'b T, ChoiceError> {
+        self.try_rand_index_str(id, items.len(), |i| usable(&items[i])).map(|i| &items[i])
+    }
+
+    /// [`Random::rand_choice_str_or`] for the pools of `run`, resampling while
+    /// the pick is locked or [`ItemChoice::retry_in`] holds.
+    pub fn rand_choice_str_in<'b, T: ItemChoice>(
+        &mut self,
+        id: &str,
+        items: &'b [T],
+        run: &RunState,
+        fallback: &'b T,
+    ) -> &'b T {
+        self.try_rand_index_str(id, items.len(), |i| !items[i].locked() && !items[i].retry_in(run))
+            .map_or(fallback, |i| &items[i])
+    }
+
+    /// Index in `0..len` drawn like [`Random::try_rand_choice_str`], resampling
+    /// while `usable(index)` is false. For pools whose availability is decided
+    /// outside [`ItemChoice`], such as the game's unlocks.
+    pub fn try_rand_index_str(
+        &mut self,
+        id: &str,
+        len: usize,
+        mut usable: impl FnMut(usize) -> bool,
+    ) -> Result<usize, ChoiceError> {
+        if len == 0 {
+            return Err(ChoiceError::Empty);
+        }
+        let max = len as i32 - 1;
+        let first = self.rand_int_str(id, 0, max) as usize;
+        if usable(first) {
+            return Ok(first);
+        }
+        if !(0..len).any(&mut usable) {
+            return Err(ChoiceError::NoneUsable { first });
+        }
+        for resample in 2..=MAX_STR_RESAMPLES {
+            let i = self.rand_int_str(&alloc::format!("{id}_resample{resample}"), 0, max) as usize;
+            if usable(i) {
+                return Ok(i);
             }
-            Self::no_item_found();
         }
-        item
+        Err(ChoiceError::Exhausted { first, attempts: MAX_STR_RESAMPLES })
     }
-}
\ No newline at end of file
+}
+
+#[inline(always)]
+fn usable<T: ItemChoice>(item: &T) -> bool {
+    !item.locked() && !item.retry()
+}
@@ -0,0 +1,82 @@
+//! Line-by-line transcriptions of the game's seeding functions, written for
+//! obviousness rather than speed. The fast paths in [`crate::util`] and
+//! [`crate::random`] are tested against these and against golden vectors from
+//! LuaJIT; nothing in the search itself uses them.
+//!
+//! ```lua
+//! function pseudohash(str)
+//!   local num = 1
+//!   for i=#str, 1, -1 do
+//!     num = ((1.1239285023/num)*string.byte(str, i)*math.pi + math.pi*i)%1
+//!   end
+//!   return num
+//! end
+//!
+//! function pseudoseed(key)
+//!   if not G.GAME.pseudorandom[key] then
+//!     G.GAME.pseudorandom[key] = pseudohash(key..(G.GAME.pseudorandom.seed or ''))
+//!   end
+//!   G.GAME.pseudorandom[key] = math.abs(tonumber(string.format("%.13f", (2.134453429141+G.GAME.pseudorandom[key]*1.72431234)%1)))
+//!   return (G.GAME.pseudorandom[key] + (G.GAME.pseudorandom.hashed_seed or 0))/2
+//! end
+//! ```
+
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::String;
+use core::f64::consts::PI;
+use libm::floor;
+
+/// Lua's `a % b` for numbers: `a - floor(a/b)*b`.
+fn lua_mod(a: f64, b: f64) -> f64 {
+    a - floor(a / b) * b
+}
+
+pub fn pseudohash(s: &str) -> f64 {
+    let bytes = s.as_bytes();
+    let mut num = 1.0;
+    for i in (1..=bytes.len()).rev() {
+        num = lua_mod((1.1239285023 / num) * bytes[i - 1] as f64 * PI + PI * i as f64, 1.0);
+    }
+    num
+}
+
+/// `tonumber(string.format("%.13f", x))` as LuaJIT computes it: round the
+/// exact decimal expansion of `x` to 13 places, halves away from zero, then
+/// parse the result back to the nearest double.
+pub fn round13(x: f64) -> f64 {
+    // 1100 places print any double exactly.
+    let exact = format!("{:.1100}", x.abs());
+    let (int, frac) = exact.split_once('.').unwrap_or((&exact, ""));
+    let mut digits = String::from(int);
+    digits.push_str(&frac[..13]);
+    let mut n: u128 = digits.parse().unwrap_or(0);
+    if frac.as_bytes()[13] >= b'5' {
+        n += 1;
+    }
+    let rounded: f64 = format!("{n}e-13").parse().unwrap_or(0.0);
+    if x < 0.0 { -rounded } else { rounded }
+}
+
+/// `G.GAME.pseudorandom`: the seed, its hash and every key's node value.
+pub struct PseudoRandom {
+    pub seed: String,
+    pub hashed_seed: f64,
+    pub nodes: BTreeMap<String, f64>,
+}
+
+impl PseudoRandom {
+    pub fn new(seed: &str) -> Self {
+        Self { seed: String::from(seed), hashed_seed: pseudohash(seed), nodes: BTreeMap::new() }
+    }
+
+    pub fn pseudoseed(&mut self, key: &str) -> f64 {
+        let node = match self.nodes.get(key) {
+            Some(&node) => node,
+            None => pseudohash(&format!("{key}{}", self.seed)),
+        };
+        let node = round13(lua_mod(2.134453429141 + node * 1.72431234, 1.0)).abs();
+        self.nodes.insert(String::from(key), node);
+        (node + self.hashed_seed) / 2.0
+    }
+}
@@ -0,0 +1,87 @@
+//! Targets that jokers roll each round. The game rerolls The Idol, Mail-In
+//! Rebate, Ancient Joker and Castle together when a run starts and at the end
+//! of every round played (skipped blinds roll nothing), from the streams
+//! `idol`, `mail`, `anc` and `cas` suffixed with the current ante. To Do List
+//! rolls its hand from `to_do` when created and at the end of each round.
+
+use crate::items::{Card, CardSuits, CardTypes, EnhancementTypes, HandTypes};
+use crate::random::Random;
+use alloc::vec::Vec;
+use strum::IntoEnumIterator;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RoundTargets {
+    /// The Idol's rank and suit, Ace of Spades when the deck has no cards to pick.
+    pub idol: (CardTypes, CardSuits),
+    /// Mail-In Rebate's rank, Ace by default.
+    pub mail: CardTypes,
+    /// Ancient Joker's suit, never the one of the previous roll.
+    pub ancient: CardSuits,
+    /// Castle's suit, Spades by default.
+    pub castle: CardSuits,
+}
+
+// The suits Ancient Joker picks from, as listed in `reset_ancient_card`.
+const ANCIENT_SUITS: [CardSuits; 4] = [CardSuits::Spades, CardSuits::Hearts, CardSuits::Clubs, CardSuits::Diamonds];
+
+/// `pairs(G.GAME.hands)` as a rotation of the game's hand order; To Do List
+/// and Orbital Tag draw from that order. LuaJIT walks the table
+/// `init_game_object` builds in an order that depends on a string hash seed it
+/// picks at random per launch, so the same run seed can give different hands.
+/// About nine launches in ten walk one of these 12 rotations; the rest walk
+/// another permutation that only the running game can tell.
+pub fn to_do_order(rotation: usize) -> [HandTypes; 12] {
+    let mut hands = [HandTypes::FlushFive; 12];
+    for (hand, h) in hands.iter_mut().zip(HandTypes::iter().cycle().skip(rotation % 12)) {
+        *hand = h;
+    }
+    hands
+}
+
+// `pseudorandom_element` over playing cards, which it orders by `sort_id`.
+// Stone cards have no rank or suit and are never picked.
+fn deck_card<'a>(random: &mut Random, id: &str, candidates: &[&'a Card]) -> Option<&'a Card> {
+    if candidates.is_empty() {
+        return None;
+    }
+    Some(candidates[random.rand_int_str(id, 0, candidates.len() as i32 - 1) as usize])
+}
+
+/// Roll the targets as the game does in `ante`. `deck` is every playing card
+/// the run owns in any order; cards are taken by `sort_id`, the order the
+/// game created them in. `previous_ancient` is Ancient Joker's last suit, or
+/// `None` for the roll at the start of a run.
+pub fn roll_round_targets(
+    random: &mut Random,
+    ante: i32,
+    deck: &[Card],
+    previous_ancient: Option<CardSuits>,
+) -> RoundTargets {
+    let mut candidates: Vec<&Card> = deck.iter().filter(|c| c.enhancement != EnhancementTypes::Stone).collect();
+    candidates.sort_by_key(|c| c.sort_id);
+
+    let idol = deck_card(random, &alloc::format!("idol{ante}"), &candidates)
+        .map_or((CardTypes::Ace, CardSuits::Spades), |c| (c.rank, c.suit));
+    let mail = deck_card(random, &alloc::format!("mail{ante}"), &candidates).map_or(CardTypes::Ace, |c| c.rank);
+    let suits: Vec<CardSuits> = ANCIENT_SUITS.into_iter().filter(|&s| Some(s) != previous_ancient).collect();
+    let ancient = suits[random.rand_int_str(&alloc::format!("anc{ante}"), 0, suits.len() as i32 - 1) as usize];
+    let castle = deck_card(random, &alloc::format!("cas{ante}"), &candidates).map_or(CardSuits::Spades, |c| c.suit);
+    RoundTargets { idol, mail, ancient, castle }
+}
+
+/// To Do List's next hand: any hand `visible` holds other than `current`,
+/// taken in `order`, the `pairs(G.GAME.hands)` order (see [`to_do_order`]).
+/// Secret hands become visible once played. With nothing else visible the
+/// hand stays `current`, or High Card for a new joker.
+pub fn roll_to_do_hand(
+    random: &mut Random,
+    current: Option<HandTypes>,
+    visible: impl Fn(HandTypes) -> bool,
+    order: &[HandTypes],
+) -> HandTypes {
+    let hands: Vec<HandTypes> = order.iter().copied().filter(|&h| visible(h) && Some(h) != current).collect();
+    if hands.is_empty() {
+        return current.unwrap_or(HandTypes::HighCard);
+    }
+    hands[random.rand_int_str("to_do", 0, hands.len() as i32 - 1) as usize]
+}
@@ -0,0 +1,175 @@
+//! What a run holds between draws: the deck and the cards in hand, jokers,
+//! consumables, money and hand levels. Consumable effects
+//! ([`crate::consumables`]) read and change it.
+
+use crate::cards::starting_deck;
+use crate::challenge::{Ban, Challenge};
+use crate::hands::HandLevels;
+use crate::items::{Card, Consumable, Editions, EnhancementTypes, Joker, JokerTypes};
+use crate::odds::Probabilities;
+use crate::shop::{ShopItem, ShopRates};
+use alloc::vec::Vec;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunState {
+    /// Every playing card the run owns, in the order they were added.
+    pub deck: Vec<Card>,
+    /// `sort_id`s of the cards held in hand, left to right.
+    pub hand: Vec<usize>,
+    /// Owned jokers, oldest first; the game picks among them in that order.
+    pub jokers: Vec<Joker>,
+    pub joker_slots: usize,
+    pub consumables: Vec<Consumable>,
+    pub consumable_slots: usize,
+    pub hand_size: i32,
+    pub dollars: i32,
+    pub hands: HandLevels,
+    pub probabilities: Probabilities,
+    /// `G.GAME.edition_rate`: 1, or 2 with Hone and 4 with Glow Up.
+    pub edition_rate: f64,
+    /// The last Tarot or Planet used, which The Fool creates.
+    pub last_tarot_planet: Option<Consumable>,
+    /// Hand size the next Ectoplasm takes; each use raises it by one.
+    pub ecto_minus: i32,
+    /// `G.GAME.pool_flags.gros_michel_extinct`: set once Gros Michel dies,
+    /// which swaps it for Cavendish in the Joker pool.
+    pub gros_michel_extinct: bool,
+    /// What the challenge being played bans, see [`RunState::for_challenge`].
+    pub banned: Vec<Ban>,
+    /// Card type weights of the shop's slots, raised by the Merchant and
+    /// Tycoon vouchers and Magic Trick.
+    pub shop_rates: ShopRates,
+    /// Cards on show in the shop. The game holds their keys like owned cards'
+    /// until the shop rerolls or closes, so the pools leave them out too.
+    pub shop: Vec<ShopItem>,
+    // The consumable being used, which holds its key until it dissolves.
+    using: Option<Consumable>,
+    next_sort_id: usize,
+}
+
+impl Default for RunState {
+    fn default() -> Self {
+        let deck = starting_deck();
+        let next_sort_id = deck.len();
+        Self {
+            deck,
+            hand: Vec::new(),
+            jokers: Vec::new(),
+            joker_slots: 5,
+            consumables: Vec::new(),
+            consumable_slots: 2,
+            hand_size: 8,
+            dollars: 4,
+            hands: HandLevels::new(),
+            probabilities: Probabilities::default(),
+            edition_rate: 1.0,
+            last_tarot_planet: None,
+            ecto_minus: 1,
+            gros_michel_extinct: false,
+            banned: Vec::new(),
+            shop_rates: ShopRates::default(),
+            shop: Vec::new(),
+            using: None,
+            next_sort_id,
+        }
+    }
+}
+
+impl RunState {
+    /// A fresh run on the standard deck.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A fresh run of `challenge`: its deck, jokers, consumables and starting
+    /// modifiers, with its bans kept out of the pools. Vouchers are up to the
+    /// caller, see [`crate::game::GameState::with_challenge`].
+    pub fn for_challenge(challenge: &Challenge) -> Self {
+        let mut run = Self::new();
+        run.deck = starting_deck()
+            .into_iter()
+            .filter(|c| !challenge.removed_ranks.contains(&c.rank))
+            .enumerate()
+            .map(|(i, c)| Card { sort_id: i, enhancement: challenge.enhancement, seal: challenge.seal, ..c })
+            .collect();
+        run.next_sort_id = run.deck.len();
+        run.jokers = challenge.jokers.iter().map(|j| j.to_joker()).collect();
+        run.consumables = challenge.consumables.to_vec();
+        let m = challenge.modifiers;
+        run.dollars = m.dollars.unwrap_or(run.dollars);
+        run.hand_size = m.hand_size.unwrap_or(run.hand_size);
+        run.joker_slots = m.joker_slots.unwrap_or(run.joker_slots);
+        run.consumable_slots = m.consumable_slots.unwrap_or(run.consumable_slots);
+        run.banned = challenge.bans.to_vec();
+        run
+    }
+
+    pub fn card(&self, sort_id: usize) -> Option<&Card> {
+        self.deck.iter().find(|c| c.sort_id == sort_id)
+    }
+
+    pub fn card_mut(&mut self, sort_id: usize) -> Option<&mut Card> {
+        self.deck.iter_mut().find(|c| c.sort_id == sort_id)
+    }
+
+    /// Add `card` to the deck and the hand with the next `sort_id`, and return that id.
+    pub fn add_to_hand(&mut self, mut card: Card) -> usize {
+        card.sort_id = self.next_sort_id;
+        self.next_sort_id += 1;
+        self.deck.push(card);
+        self.hand.push(card.sort_id);
+        card.sort_id
+    }
+
+    /// Remove a card from the deck and the hand.
+    pub fn destroy_card(&mut self, sort_id: usize) {
+        self.deck.retain(|c| c.sort_id != sort_id);
+        self.hand.retain(|&id| id != sort_id);
+    }
+
+    pub fn free_consumable_slots(&self) -> usize {
+        self.consumable_slots.saturating_sub(self.consumables.len())
+    }
+
+    /// Whether the run holds `joker`, which keeps it out of the Joker pool.
+    pub fn holds_joker(&self, joker: JokerTypes) -> bool {
+        self.jokers.iter().any(|j| j.joker == joker)
+    }
+
+    /// Whether `joker` is on show in the shop.
+    pub fn shows_joker(&self, joker: JokerTypes) -> bool {
+        self.shop.iter().any(|item| item.joker().is_some_and(|j| j.joker == joker))
+    }
+
+    /// Whether the run holds `card`, counting the one being used and those on
+    /// show in the shop, which keeps it out of its pool.
+    pub fn holds(&self, card: Consumable) -> bool {
+        self.using == Some(card) || self.consumables.contains(&card) || self.shop.iter().any(|i| i.consumable() == Some(card))
+    }
+
+    /// Showman lets pools offer cards the run already holds.
+    pub fn allows_duplicates(&self) -> bool {
+        self.holds_joker(JokerTypes::Showman)
+    }
+
+    /// Whether any playing card has `enhancement`; Steel Joker, Stone Joker,
+    /// Lucky Cat, Golden Ticket and Glass Joker wait for one.
+    pub fn has_enhancement(&self, enhancement: EnhancementTypes) -> bool {
+        self.deck.iter().any(|c| c.enhancement == enhancement)
+    }
+
+    /// Whether the challenge being played bans `ban`.
+    pub fn is_banned(&self, ban: Ban) -> bool {
+        self.banned.contains(&ban)
+    }
+
+    pub(crate) fn set_using(&mut self, card: Option<Consumable>) {
+        self.using = card;
+    }
+
+    /// Negative jokers bring their own slot.
+    pub fn free_joker_slots(&self) -> usize {
+        let negatives = self.jokers.iter().filter(|j| j.edition == Editions::Negative).count();
+        (self.joker_slots + negatives).saturating_sub(self.jokers.len())
+    }
+}
@@ -0,0 +1,117 @@
+use crate::filter::{Clause, ParseClauseError, SeedView};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+
+/// Words per criterion in the GPU encoding: the clause word and the weight.
+pub const CRITERION_WORDS: usize = 2;
+/// Best seeds each GPU block reports per launch. Ranked GPU results are exact
+/// as long as no single block holds more than this many of the overall top K.
+pub const GPU_BLOCK_TOP: usize = 16;
+/// Largest block the GPU scoring kernel takes; it sizes the kernel's shared
+/// scratch for the block reductions. Also CUDA's own limit.
+pub const GPU_MAX_BLOCK: usize = 1024;
+
+/// A weighted clause. A seed earns `weight` for every time the clause holds,
+/// so `tag=Double Tag:3` scores 6 when both blinds offer a Double Tag.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Criterion {
+    pub clause: Clause,
+    pub weight: i32,
+}
+
+impl Criterion {
+    #[inline]
+    pub fn score(&self, view: &mut SeedView) -> i32 {
+        self.weight * self.clause.count(view) as i32
+    }
+
+    pub fn to_words(&self) -> [u32; CRITERION_WORDS] {
+        [self.clause.to_word(), self.weight as u32]
+    }
+
+    pub fn from_words(words: [u32; CRITERION_WORDS]) -> Option<Self> {
+        Some(Self { clause: Clause::from_word(words[0])?, weight: words[1] as i32 })
+    }
+}
+
+impl fmt::Display for Criterion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{:+}", self.clause, self.weight)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseCriterionError {
+    Clause(ParseClauseError),
+    BadWeight(String),
+}
+
+impl fmt::Display for ParseCriterionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseCriterionError::Clause(e) => e.fmt(f),
+            ParseCriterionError::BadWeight(s) => write!(f, "weight '{s}' is not a whole number"),
+        }
+    }
+}
+
+impl From<ParseClauseError> for ParseCriterionError {
+    fn from(e: ParseClauseError) -> Self {
+        ParseCriterionError::Clause(e)
+    }
+}
+
+/// `clause[:weight]`, e.g. `joker@1=Blueprint:10`; the weight defaults to 1.
+impl FromStr for Criterion {
+    type Err = ParseCriterionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (clause, weight) = match s.rsplit_once(':') {
+            Some((clause, weight)) => {
+                let weight = weight.trim();
+                let parsed = weight.trim_start_matches('+').parse::<i32>();
+                (clause, parsed.map_err(|_| ParseCriterionError::BadWeight(String::from(weight)))?)
+            }
+            None => (s, 1),
+        };
+        Ok(Self { clause: clause.parse()?, weight })
+    }
+}
+
+/// Sum of weighted criteria; higher is better.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Scorer {
+    pub criteria: Vec<Criterion>,
+}
+
+impl Scorer {
+    pub fn new(criteria: Vec<Criterion>) -> Self {
+        Self { criteria }
+    }
+
+    pub fn score(&self, view: &mut SeedView) -> i32 {
+        self.criteria.iter().map(|c| c.score(view)).sum()
+    }
+
+    pub fn score_seed(&self, seed: &str) -> i32 {
+        self.score(&mut SeedView::new(seed))
+    }
+
+    /// Flat encoding consumed by [`score_words`] and the GPU kernels.
+    pub fn to_words(&self) -> Vec<u32> {
+        self.criteria.iter().flat_map(|c| c.to_words()).collect()
+    }
+}
+
+/// Score a seed against criteria encoded with [`Scorer::to_words`], without
+/// decoding them into a `Vec` first. Malformed criteria score nothing.
+#[inline]
+pub fn score_words(words: &[u32], view: &mut SeedView) -> i32 {
+    words
+        .chunks_exact(CRITERION_WORDS)
+        .filter_map(|w| Criterion::from_words([w[0], w[1]]))
+        .map(|c| c.score(view))
+        .sum()
+}
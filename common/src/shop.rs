@@ -1,22 +1,25 @@
-#[cfg(feature = "std")]
-use core::fmt::Display;
+//! The shop's card slots. `create_card_for_shop` picks each slot's card type
+//! from `cdt{ante}`, weighted by the run's [`ShopRates`], then creates the
+//! card from the shop's own streams (`sho`).
 
-use crate::items::{Card, Joker, Pack, Planets, RandomSource, Spectral, Tarots, Vouchers};
-#[cfg(feature = "std")]
+use crate::cards::shop_card;
+use crate::items::{Card, Consumable, Joker, RandomSource};
 use crate::pools::{next_joker, next_planet, next_spectral, next_tarot};
 use crate::random::Random;
+use crate::run::RunState;
 
-pub struct Shop<'a> {
-    pub ante: i32,
-    pub random: &'a mut Random<'a>,
-    pub rates: ShopRates,
-}
-
+/// `G.GAME.joker_rate` and its siblings: how often a shop slot holds each
+/// card type, relative to their total.
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub struct ShopRates {
     pub joker_rate: f64,
+    /// 9.6 with Tarot Merchant, 32 with Tarot Tycoon.
     pub tarot_rate: f64,
+    /// 9.6 with Planet Merchant, 32 with Planet Tycoon.
     pub planet_rate: f64,
+    /// 4 with Magic Trick.
     pub playing_card_rate: f64,
+    /// 2 on the Ghost Deck.
     pub spectral_rate: f64,
 }
 
@@ -38,74 +41,57 @@ impl ShopRates {
     }
 }
 
-#[derive(Debug)]
+/// A card on show in one shop slot.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum ShopItem {
     Joker(Joker),
-    Tarot(Tarots),
-    Planet(Planets),
-    Spectral(Spectral),
-    Card(Card)
+    Consumable(Consumable),
+    Card(Card),
 }
 
-#[cfg(feature = "std")]
-impl Display for ShopItem {
-    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        match self {
-            ShopItem::Joker(joker) => write!(f, "{}", joker),
-            ShopItem::Tarot(tarot) => write!(f, "{:?}", tarot),
-            ShopItem::Planet(planet) => write!(f, "{:?}", planet),
-            ShopItem::Spectral(spectral) => write!(f, "{:?}", spectral),
-            ShopItem::Card(card) => write!(f, "{:?}", card),
+impl ShopItem {
+    pub fn joker(&self) -> Option<Joker> {
+        match *self {
+            ShopItem::Joker(j) => Some(j),
+            _ => None,
         }
     }
-}
-
-pub struct ShopState<const N: usize> {
-    pub refreshable: [ShopItem; N],
-    pub vouchers: [Vouchers; 3],
-    pub packs: [Pack; 2],
-}
-
-impl Shop<'_> {
-    pub fn new<'a>(ante: i32, rates: ShopRates, random: &'a mut Random<'a>) -> Shop<'a> {
-        Shop { ante, rates, random }
-    }
 
-    #[cfg(feature = "std")]
-    pub fn generate<const N: usize>(&mut self) -> ShopState<N> {
-        ShopState {
-            refreshable: (0..N).map(|_| self.next_shop_item()).collect::<Vec<ShopItem>>().try_into().unwrap(),
-            vouchers: [Vouchers::Blank; 3],
-            packs: [Pack::Buffoon; 2],
+    pub fn consumable(&self) -> Option<Consumable> {
+        match *self {
+            ShopItem::Consumable(c) => Some(c),
+            _ => None,
         }
     }
+}
 
-    #[cfg(feature = "std")]
-    fn next_shop_item(&mut self) -> ShopItem {
-        let mut cdt_poll = self.random.random(&format!("cdt{}", self.ante)) * self.rates.total_rate();
-
-        if cdt_poll < self.rates.joker_rate  {
-            return ShopItem::Joker(next_joker(&mut self.random, RandomSource::Shop, self.ante, true))
-        } else {
-            cdt_poll -= self.rates.joker_rate;
-        }
-
-        if cdt_poll < self.rates.tarot_rate  {
-            return ShopItem::Tarot(next_tarot(&mut self.random, RandomSource::Shop, self.ante, false))
-        } else {
-            cdt_poll -= self.rates.tarot_rate;
-        }
-
-        if cdt_poll < self.rates.planet_rate {
-            return ShopItem::Planet(next_planet(&mut self.random, RandomSource::Shop, self.ante, false))
-        } else {
-            cdt_poll -= self.rates.planet_rate;
-        }
-
-        if cdt_poll < self.rates.playing_card_rate {
-            todo!()
-        }
-
-        ShopItem::Spectral(next_spectral(&mut self.random, RandomSource::Shop, self.ante, false))
+/// The next shop slot of `ante`, as `create_card_for_shop` fills it: the roll
+/// on `cdt{ante}` lands in the first card type whose share of the total rate
+/// covers it, then the card comes from that type's `sho` streams. Shop cards
+/// never roll for The Soul or Black Hole. With Illusion owned, the game polls
+/// `illusion` for every slot while building its table of types, whichever
+/// type the slot gets. Tags that force a shop joker are not followed.
+pub fn next_shop_item(random: &mut Random, run: &RunState, ante: i32, illusion: bool) -> ShopItem {
+    let rates = run.shop_rates;
+    let poll = random.random_str(&alloc::format!("cdt{ante}")) * rates.total_rate();
+    let weights = [rates.joker_rate, rates.tarot_rate, rates.planet_rate, rates.playing_card_rate, rates.spectral_rate];
+    let mut check = 0.0;
+    let kind = weights.iter().position(|&rate| {
+        let hit = poll > check && poll <= check + rate;
+        check += rate;
+        hit
+    });
+    // `shop_card` makes the poll itself for playing cards.
+    if illusion && kind != Some(3) {
+        random.random_str("illusion");
     }
-}
\ No newline at end of file
+    let source = RandomSource::Shop;
+    match kind {
+        Some(1) => ShopItem::Consumable(next_tarot(random, run, source, ante, false)),
+        Some(2) => ShopItem::Consumable(next_planet(random, run, source, ante, false)),
+        Some(3) => ShopItem::Card(shop_card(random, ante, illusion)),
+        Some(4) => ShopItem::Consumable(Consumable::Spectral(next_spectral(random, run, source, ante, false))),
+        // A roll of exactly 0 matches no type in the game; it is taken as a joker here.
+        _ => ShopItem::Joker(next_joker(random, run, source, ante, true)),
+    }
+}
@@ -0,0 +1,77 @@
+use alloc::string::String;
+use core::fmt::{self, Write};
+
+/// One use of a string-keyed stream, as recorded by [`crate::random::Random`]
+/// once tracing is enabled. The fields follow the game's `pseudoseed` and
+/// `pseudorandom`, so a trace can be diffed line by line against one logged
+/// from Blueprint or the game.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceEvent {
+    pub seed: String,
+    pub id: String,
+    /// `pseudohash(id .. seed)`, on the first use of `id` only.
+    pub initial: Option<f64>,
+    /// Node value after this step, the stored `G.GAME.pseudorandom[id]`.
+    pub node: f64,
+    /// `(node + hashed_seed) / 2`, the value `pseudoseed` returns.
+    pub mixed: f64,
+    /// `math.random()` after seeding with `mixed`, when the node fed a draw.
+    pub draw: Option<f64>,
+}
+
+// Shortest round-trip form; JSON has no NaN or infinities.
+fn number(out: &mut String, x: Option<f64>) -> fmt::Result {
+    match x {
+        Some(x) if x.is_finite() => write!(out, "{x:?}"),
+        _ => out.write_str("null"),
+    }
+}
+
+fn string(out: &mut String, s: &str) -> fmt::Result {
+    out.write_char('"')?;
+    for c in s.chars() {
+        match c {
+            '"' => out.write_str("\\\"")?,
+            '\\' => out.write_str("\\\\")?,
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32)?,
+            c => out.write_char(c)?,
+        }
+    }
+    out.write_char('"')
+}
+
+impl TraceEvent {
+    /// The event as one JSON object without a trailing newline:
+    /// `{"seed":..,"id":..,"initial":..,"node":..,"mixed":..,"draw":..}`.
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        self.write_json(&mut out).expect("writing to a String cannot fail");
+        out
+    }
+
+    fn write_json(&self, out: &mut String) -> fmt::Result {
+        out.write_str("{\"seed\":")?;
+        string(out, &self.seed)?;
+        out.write_str(",\"id\":")?;
+        string(out, &self.id)?;
+        out.write_str(",\"initial\":")?;
+        number(out, self.initial)?;
+        out.write_str(",\"node\":")?;
+        number(out, Some(self.node))?;
+        out.write_str(",\"mixed\":")?;
+        number(out, Some(self.mixed))?;
+        out.write_str(",\"draw\":")?;
+        number(out, self.draw)?;
+        out.write_char('}')
+    }
+}
+
+/// Events as JSON lines, one object per line.
+pub fn to_json_lines(events: &[TraceEvent]) -> String {
+    let mut out = String::new();
+    for event in events {
+        event.write_json(&mut out).expect("writing to a String cannot fail");
+        out.push('\n');
+    }
+    out
+}
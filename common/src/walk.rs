@@ -0,0 +1,128 @@
+//! Enumeration of the seed space, ordered so neighbouring seeds share hashing.
+//!
+//! `pseudohash` consumes a seed from its last character to its first, so two
+//! seeds that differ only near the front share the hash state of their common
+//! suffix. Seed indices therefore run through the first character fastest:
+//! within one length, index `n` and `n + 1` differ in the first character
+//! unless it wraps around, and a walk over consecutive indices only rehashes
+//! the characters that changed, about 1.03 hashing steps per seed instead of 8.
+//!
+//! Each length covers the same seeds as the plain base-35 numbering it
+//! replaced, but in a different order, so a `--start`/`--total` range now
+//! names different seeds than it did under that numbering.
+
+use crate::util::pseudohash_step;
+use core::ops::Range;
+
+pub const CHARSET: [u8; 35] = *b"ABCDEFGHIJKLMNOPQRSTUVWXYZ123456789";
+pub const MAX_SEED_LEN: usize = 8;
+
+const RADIX: u64 = CHARSET.len() as u64;
+
+/// Number of indices: the 35 one-character seeds, then every seed of two to
+/// eight characters that does not start with 'A', the zero digit. Longer
+/// seeds starting with 'A' are never enumerated; seed lists, patterns and
+/// samples can still name them.
+pub const SEED_SPACE: u64 = RADIX.pow(MAX_SEED_LEN as u32);
+
+/// Indices of the enumerated seeds of `len` characters (1 to
+/// [`MAX_SEED_LEN`]): `35^(len-1)..35^len`, or `0..35` for one character.
+pub fn length_indices(len: usize) -> Range<u64> {
+    let len = len.clamp(1, MAX_SEED_LEN) as u32;
+    let end = RADIX.pow(len);
+    if len == 1 { 0..end } else { end / RADIX..end }
+}
+
+/// Seed length at index `n` and the indices of that length. Lengths take as
+/// many indices as `n` has base-35 digits, so `0..35` are the one-character
+/// seeds, `35..35^2` the two-character ones and so on up to [`SEED_SPACE`].
+#[inline(always)]
+fn length_class(n: u64) -> (usize, Range<u64>) {
+    let mut len = 1;
+    let mut first = 0;
+    let mut next = RADIX;
+    while n >= next && len < MAX_SEED_LEN {
+        len += 1;
+        first = next;
+        next *= RADIX;
+    }
+    (len, first..next)
+}
+
+// Smallest character index at `pos` (0-based). Seeds longer than one
+// character never start with 'A', the zero digit.
+#[inline(always)]
+fn min_digit(len: usize, pos: usize) -> u8 {
+    (pos == 0 && len > 1) as u8
+}
+
+/// Character indices of seed `n`, first character first, and its length.
+#[inline(always)]
+fn digits(n: u64) -> ([u8; MAX_SEED_LEN], usize) {
+    let (len, class) = length_class(n);
+    let mut j = n - class.start;
+    let mut out = [0u8; MAX_SEED_LEN];
+    for (pos, d) in out[..len].iter_mut().enumerate() {
+        let min = min_digit(len, pos) as u64;
+        let radix = RADIX - min;
+        *d = (min + j % radix) as u8;
+        j /= radix;
+    }
+    (out, len)
+}
+
+/// Write seed `n` right-aligned into `out`, returning its (offset, length).
+#[inline(always)]
+pub fn encode_seed(n: u64, out: &mut [u8; MAX_SEED_LEN]) -> (usize, usize) {
+    let (digits, len) = digits(n);
+    let off = MAX_SEED_LEN - len;
+    for (b, &d) in out[off..].iter_mut().zip(&digits[..len]) {
+        *b = CHARSET[d as usize];
+    }
+    (off, len)
+}
+
+/// Call `visit(n, seed, pseudohash(seed))` for every index in `range`, in
+/// order. Hash states of seed suffixes are kept between seeds, so only the
+/// characters that changed since the previous seed are hashed again.
+#[inline(always)]
+pub fn walk_hashed_seeds(range: Range<u64>, mut visit: impl FnMut(u64, &[u8], f64)) {
+    let mut n = range.start;
+    let range_end = range.end.min(SEED_SPACE);
+    while n < range_end {
+        let (len, class) = length_class(n);
+        let end = range_end.min(class.end);
+        let (mut digits, _) = digits(n);
+        let mut seed = [0u8; MAX_SEED_LEN];
+        for (b, &d) in seed.iter_mut().zip(&digits[..len]) {
+            *b = CHARSET[d as usize];
+        }
+        // partial[pos] is the hash state after characters len-1 down to pos;
+        // partial[len] is the initial state.
+        let mut partial = [1.0f64; MAX_SEED_LEN + 1];
+        let mut dirty = len;
+        loop {
+            for pos in (0..dirty).rev() {
+                partial[pos] = pseudohash_step(partial[pos + 1], seed[pos], pos + 1);
+            }
+            visit(n, &seed[..len], partial[0]);
+            n += 1;
+            if n == end {
+                break;
+            }
+            // Odometer increment, first character fastest.
+            let mut pos = 0;
+            loop {
+                digits[pos] += 1;
+                if (digits[pos] as u64) < RADIX {
+                    break;
+                }
+                digits[pos] = min_digit(len, pos);
+                seed[pos] = CHARSET[digits[pos] as usize];
+                pos += 1;
+            }
+            seed[pos] = CHARSET[digits[pos] as usize];
+            dirty = pos + 1;
+        }
+    }
+}
@@ -1,57 +1,221 @@
 use cuda_std::prelude::*;
+use cuda_std::shared_array;
+use common::filter::{Clause, SeedView};
+use common::game::RunConfig;
 use common::random::Random;
+use common::score::{score_words, GPU_BLOCK_TOP, GPU_MAX_BLOCK};
+use common::walk::{encode_seed, walk_hashed_seeds};
+use core::sync::atomic::{AtomicU64, Ordering};
 
-const CHARSET: [u8; 35] = *b"ABCDEFGHIJKLMNOPQRSTUVWXYZ123456789";
+/// Sums the hashed seeds of enumerated seeds `start..start + total` into one
+/// slot per thread. Each thread walks a contiguous share of the range with
+/// `common::walk`, so neighbouring seeds reuse the hash of their shared suffix.
+///
+/// # Safety
+/// `out_checksums` must hold one slot per launched thread.
+#[kernel]
+#[inline(never)]
+pub unsafe fn iterate_seeds(start: u64, total: u64, out_checksums: *mut f64) {
+    // index_1d is already the thread id across the grid (block_idx * block_dim + thread_idx)
+    let global_idx = thread::index_1d() as u64;
+    let tid_usize = global_idx as usize;
+    let threads = thread::grid_dim_x() as u64 * thread::block_dim_x() as u64;
+    let share = total.div_ceil(threads);
 
-#[inline(always)]
-fn encode_seed(mut n: u64, out: &mut [u8; 8]) -> (usize, usize) {
-    // Encode n in base-35 using our custom charset. Return (start, length) in bytes.
-    // 0 maps to 'A'. Variable length without leading symbols.
-    let mut end = out.len(); // exclusive end index
-    if n == 0 {
-        end -= 1;
-        out[end] = CHARSET[0];
-        return (end, 1);
-    }
-    while n > 0 && end > 0 {
-        let rem = (n % 35) as usize;
-        end -= 1;
-        out[end] = CHARSET[rem];
-        n /= 35;
-    }
-    let len = out.len() - end;
-    (end, len)
-}
+    let mut sum = 0.0f64;
+    let lo = (global_idx * share).min(total);
+    let hi = (lo + share).min(total);
+    walk_hashed_seeds(start + lo..start + hi, |_, _, hashed| sum += hashed);
 
+    let out_ptr = unsafe { out_checksums.add(tid_usize) };
+    unsafe { *out_ptr = sum; }
+}
+/// Same as `iterate_seeds`, but over an explicit list of seeds packed as
+/// 8 zero-padded bytes each (seed lists, patterns and random samples).
+///
+/// # Safety
+/// `seeds` must hold `total * 8` bytes and `out_checksums` one slot per launched thread.
 #[kernel]
 #[inline(never)]
-pub unsafe fn iterate_seeds(start: u64, total: u64, out_checksums: *mut f64) {
-    let tid = thread::index_1d() as u64;
-    let tid_usize = tid as usize;
+pub unsafe fn iterate_seed_list(seeds: *const u8, total: u64, out_checksums: *mut f64) {
+    let global_idx = thread::index_1d() as u64;
+    let tid_usize = global_idx as usize;
     let block_dim = thread::block_dim_x() as u64;
-    let block_idx = thread::block_idx_x() as u64;
     let grid_dim = thread::grid_dim_x() as u64;
-
-    let global_idx = block_idx * block_dim + tid; // thread id across grid
-    let stride = grid_dim * block_dim; // grid-stride loop
+    let stride = grid_dim * block_dim;
 
     let mut sum = 0.0f64;
-    let mut seed_buf = [0u8; 8];
-    // Reuse a single RNG per thread and just reset its seed each iteration
     let mut rng = Random::new(&[]);
 
     let mut i = global_idx;
     while i < total {
-        let idx = start + i;
-        let (start_off, len) = encode_seed(idx, &mut seed_buf);
-        // Reset seed for this iteration (Random owns the seed bytes internally)
-        rng.reset_seed(&seed_buf[start_off..start_off + len]);
-        // Accumulate hashed_seed to avoid being optimized out
-       // sum += rng.get_node((i as usize) % IDS_LEN);
+        let packed = unsafe { core::slice::from_raw_parts(seeds.add((i * 8) as usize), 8) };
+        let len = packed.iter().position(|&b| b == 0).unwrap_or(8);
+        rng.reset_seed(&packed[..len]);
         sum += rng.hashed_seed;
         i += stride;
     }
 
     let out_ptr = unsafe { out_checksums.add(tid_usize) };
     unsafe { *out_ptr = sum; }
-}
\ No newline at end of file
+}
+
+// Seed `i` of a launch: enumerated from `start` when `seeds` is null, otherwise
+// read from the packed 8-byte, zero-padded records.
+#[inline(always)]
+unsafe fn seed_at(start: u64, seeds: *const u8, i: u64, buf: &mut [u8; 8]) -> &[u8] {
+    if seeds.is_null() {
+        let (off, len) = encode_seed(start + i, buf);
+        &buf[off..off + len]
+    } else {
+        let packed = unsafe { core::slice::from_raw_parts(seeds.add((i * 8) as usize), 8) };
+        let len = packed.iter().position(|&b| b == 0).unwrap_or(8);
+        &packed[..len]
+    }
+}
+
+// Orders candidates best first: higher score, then lower index, the same
+// rule as the checker's TopK.
+#[inline(always)]
+fn better(a: (i32, u64), b: (i32, u64)) -> bool {
+    a.0 > b.0 || (a.0 == b.0 && a.1 < b.1)
+}
+
+/// Ranked search: scores every seed against `criteria` (encoded with
+/// `common::score::Scorer::to_words`) and writes each block's best
+/// `GPU_BLOCK_TOP` seeds as (score, chunk-relative index) pairs. Seeds are
+/// enumerated from `start` like `iterate_seeds` when `seeds` is null, and read
+/// like `iterate_seed_list` otherwise, each in the run `config` describes
+/// (`common::game::RunConfig::to_word`). Empty slots hold `i32::MIN`.
+///
+/// # Safety
+/// `criteria` must hold `criteria_len` words, `seeds` (if non-null) `total * 8` bytes,
+/// `out_scores`/`out_indices` `GPU_BLOCK_TOP` slots per block, `out_checksums` one
+/// slot per thread, and the block size must not exceed `GPU_MAX_BLOCK`.
+#[kernel]
+#[inline(never)]
+#[allow(clippy::too_many_arguments)] // kernel parameters are the launch ABI
+pub unsafe fn score_seeds(
+    start: u64,
+    seeds: *const u8,
+    total: u64,
+    criteria: *const u32,
+    criteria_len: u32,
+    config: u32,
+    out_scores: *mut i32,
+    out_indices: *mut u64,
+    out_checksums: *mut f64,
+) {
+    let shared_scores = shared_array![i32; GPU_MAX_BLOCK];
+    let shared_indices = shared_array![u64; GPU_MAX_BLOCK];
+
+    let global_idx = thread::index_1d() as u64;
+    let tid = thread::thread_idx_x() as usize;
+    let block = thread::block_idx_x() as usize;
+    let block_dim = thread::block_dim_x() as usize;
+    let stride = thread::grid_dim_x() as u64 * block_dim as u64;
+    let words = unsafe { core::slice::from_raw_parts(criteria, criteria_len as usize) };
+    let config = RunConfig::from_word(config).unwrap_or_default();
+
+    // Each thread keeps its own best GPU_BLOCK_TOP, sorted best first.
+    let mut top = [(i32::MIN, u64::MAX); GPU_BLOCK_TOP];
+    let mut sum = 0.0f64;
+    let mut seed_buf = [0u8; 8];
+
+    let mut i = global_idx;
+    while i < total {
+        let bytes = unsafe { seed_at(start, seeds, i, &mut seed_buf) };
+        let score = score_words(words, &mut SeedView::with_config(core::str::from_utf8(bytes).unwrap_or(""), config));
+        sum += score as f64;
+        if better((score, i), top[GPU_BLOCK_TOP - 1]) {
+            let mut j = GPU_BLOCK_TOP - 1;
+            while j > 0 && better((score, i), top[j - 1]) {
+                top[j] = top[j - 1];
+                j -= 1;
+            }
+            top[j] = (score, i);
+        }
+        i += stride;
+    }
+    unsafe { *out_checksums.add(global_idx as usize) = sum; }
+
+    // Merge the per-thread lists: each round, every thread offers its best
+    // remaining entry, a tree reduction picks the winner, and its owner moves on.
+    let mut head = 0usize;
+    for round in 0..GPU_BLOCK_TOP {
+        let offer = if head < GPU_BLOCK_TOP { top[head] } else { (i32::MIN, u64::MAX) };
+        unsafe {
+            *shared_scores.add(tid) = offer.0;
+            *shared_indices.add(tid) = offer.1;
+        }
+        thread::sync_threads();
+        let mut width = block_dim;
+        while width > 1 {
+            let half = width.div_ceil(2);
+            if tid + half < width {
+                unsafe {
+                    let other = (*shared_scores.add(tid + half), *shared_indices.add(tid + half));
+                    if better(other, (*shared_scores.add(tid), *shared_indices.add(tid))) {
+                        *shared_scores.add(tid) = other.0;
+                        *shared_indices.add(tid) = other.1;
+                    }
+                }
+            }
+            thread::sync_threads();
+            width = half;
+        }
+        let winner = unsafe { (*shared_scores, *shared_indices) };
+        if tid == 0 {
+            unsafe {
+                *out_scores.add(block * GPU_BLOCK_TOP + round) = winner.0;
+                *out_indices.add(block * GPU_BLOCK_TOP + round) = winner.1;
+            }
+        }
+        // Indices are unique per thread, so exactly one thread owns the winner.
+        if winner.0 != i32::MIN && head < GPU_BLOCK_TOP && top[head] == winner {
+            head += 1;
+        }
+        thread::sync_threads();
+    }
+}
+
+/// One stage of the filter pipeline. Evaluates `clause` (a
+/// `common::filter::Clause::to_word`) for `count` candidates and appends the
+/// indices of those that pass to `out_indices`, bumping `out_count`. Candidates
+/// are `candidates[0..count]`, or `0..count` for the first stage (null
+/// `candidates`). Seeds are addressed, and their runs set up, as in `score_seeds`.
+///
+/// # Safety
+/// `candidates` (if non-null) must hold `count` indices and `out_indices` room for
+/// `count` more; `seeds` (if non-null) must cover every candidate index; `out_count`
+/// must point to a zeroed device `u64`.
+#[kernel]
+#[inline(never)]
+#[allow(clippy::too_many_arguments)] // kernel parameters are the launch ABI
+pub unsafe fn filter_stage(
+    start: u64,
+    seeds: *const u8,
+    candidates: *const u64,
+    count: u64,
+    clause: u32,
+    config: u32,
+    out_indices: *mut u64,
+    out_count: *mut u64,
+) {
+    let Some(clause) = Clause::from_word(clause) else { return };
+    let config = RunConfig::from_word(config).unwrap_or_default();
+    let survivors = unsafe { &*(out_count as *const AtomicU64) };
+    let stride = thread::grid_dim_x() as u64 * thread::block_dim_x() as u64;
+    let mut seed_buf = [0u8; 8];
+
+    let mut j = thread::index_1d() as u64;
+    while j < count {
+        let i = if candidates.is_null() { j } else { unsafe { *candidates.add(j as usize) } };
+        let bytes = unsafe { seed_at(start, seeds, i, &mut seed_buf) };
+        if clause.matches(&mut SeedView::with_config(core::str::from_utf8(bytes).unwrap_or(""), config)) {
+            let slot = survivors.fetch_add(1, Ordering::Relaxed);
+            unsafe { *out_indices.add(slot as usize) = i; }
+        }
+        j += stride;
+    }
+}